@@ -2,17 +2,20 @@
 //! manipulating the actual Document Object Model in the browser
 
 pub use cmd::Cmd;
-pub use component::Component;
+pub use component::{fingerprint_of, Component};
+pub use context::{provide, use_context, Context, ContextScope};
 pub use effects::Effects;
 
 mod cmd;
 mod component;
+mod context;
 mod effects;
 
 use cfg_if::cfg_if;
 
 cfg_if! {if #[cfg(feature = "with-dom")] {
     mod application;
+    mod dom_backend;
     mod dom_node;
     mod dom_patch;
     mod dom_attr;
@@ -22,24 +25,35 @@ cfg_if! {if #[cfg(feature = "with-dom")] {
     mod ric;
     mod window;
     mod document;
+    mod error;
     mod time;
     mod timeout;
+    mod transition;
+    mod flip;
+    mod virtual_list;
+    mod delegation;
+    mod style_registry;
 
     pub mod events;
     pub mod dispatch;
+    pub mod headless;
     pub mod util;
 
     pub use application::{Application, Measurements, SkipDiff, skip_if, skip_diff, SkipPath};
     pub use component::{stateful_component, StatefulComponent, StatefulModel, StatelessModel};
     pub use component::component;
+    pub use component::{register_custom_element, register_custom_element_with, CustomElementHandle};
     pub use dispatch::Dispatch;
     pub use document::Document;
+    pub use error::Error;
+    pub use dom_backend::DomBackend;
     pub use dom_patch::{DomPatch, PatchVariant, apply_dom_patches, convert_patches};
     pub use dom_attr::{DomAttr, DomAttrValue, GroupedDomAttrValues};
     pub use dom_node::DomNode;
     pub use dom_node::create_dom_node;
+    pub use headless::{HeadlessEvent, HeadlessNode, HeadlessProgram};
     pub use http::Http;
-    pub use program::{MountAction, MountTarget, Program, MountProcedure};
+    pub use program::{Middleware, MountAction, MountTarget, Program, MountProcedure, SchedulerConfig, UpdateStrategy};
     pub use util::{
         document, history, now, performance,
         spawn_local, window, inject_style,
@@ -49,16 +63,26 @@ cfg_if! {if #[cfg(feature = "with-dom")] {
     pub use timeout::{delay, request_timeout_callback, TimeoutCallbackHandle};
     pub use window::Window;
     pub use time::Time;
+    pub use virtual_list::{ItemSize, VirtualList, VirtualListMsg};
+    pub use delegation::{DelegatedListener, DelegatedListeners};
+
+    #[cfg(feature = "with-debugger")]
+    mod debugger;
+    #[cfg(feature = "with-debugger")]
+    pub use debugger::{Debugger, DebuggerMsg};
 
     use crate::dom::events::MountEvent;
 
     /// Map the Event to DomEvent, which are browser events
     #[derive(Debug, Clone)]
+    #[non_exhaustive]
     pub enum Event {
         /// native dome events web_sys::Events
         WebEvent(web_sys::Event),
         /// custom event here follows
         MountEvent(MountEvent),
+        /// event dispatched to an element in the headless in-memory DOM
+        HeadlessEvent(HeadlessEvent),
     }
 
 }}
@@ -1,4 +1,4 @@
-use crate::dom::Cmd;
+use crate::dom::{Cmd, Error};
 use crate::vdom::Node;
 pub use skip_diff::{skip_if, SkipDiff, SkipPath};
 
@@ -31,7 +31,8 @@ pub trait Application: Sized + 'static {
         vec![]
     }
 
-    /// dynamic style of an application which will be reinjected when the application style changed
+    /// dynamic style of an application, it is checked after every update
+    /// and the style element is updated in place when the application style changed
     fn style(&self) -> Vec<String> {
         vec![]
     }
@@ -41,6 +42,14 @@ pub trait Application: Sized + 'static {
     ///
     /// Warning: DO NOT use for anything else other than the intended purpose
     fn measurements(&mut self, _measurements: Measurements) {}
+
+    /// This is called when a recoverable error occurs in the runtime, such as failing to patch
+    /// the DOM. The Program then recovers from the error by re-rendering the whole view of the app.
+    ///
+    /// The default implementation logs the error
+    fn on_error(&mut self, error: &Error) {
+        log::error!("{error}");
+    }
 }
 
 /// Contains the time it took for the last app update call for the component
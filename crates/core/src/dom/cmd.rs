@@ -2,6 +2,8 @@ use crate::dom::Effects;
 use futures::channel::mpsc;
 use futures::channel::mpsc::UnboundedReceiver;
 use futures::StreamExt;
+#[cfg(feature = "with-dom")]
+use serde::Serialize;
 use std::future::Future;
 use std::pin::Pin;
 #[cfg(feature = "with-dom")]
@@ -53,6 +55,48 @@ where
         }
     }
 
+    /// Dispatch a `CustomEvent` from the target element, with the `detail` serialized into a
+    /// javascript value. The event bubbles and crosses the shadow root boundaries, so the parent
+    /// apps and non-sauron code can listen to it, ie: with [`on_custom`](crate::dom::events::on_custom).
+    /// The event is dispatched after the update of the app has returned.
+    /// # Example
+    /// ```rust,ignore
+    /// Cmd::emit_custom_event(self.host.clone(), "date-changed", &self.date)
+    /// ```
+    #[cfg(feature = "with-dom")]
+    pub fn emit_custom_event<T>(
+        target: impl Into<web_sys::EventTarget>,
+        name: &str,
+        detail: &T,
+    ) -> Self
+    where
+        T: Serialize + ?Sized,
+    {
+        let target = target.into();
+        let name = name.to_string();
+        let detail = serde_wasm_bindgen::to_value(detail);
+        Self {
+            commands: vec![Command::Action(Action::effect(async move {
+                let detail = match detail {
+                    Ok(detail) => detail,
+                    Err(error) => {
+                        log::error!("unable to serialize the detail of {name}: {error}");
+                        return;
+                    }
+                };
+                let event_init = web_sys::CustomEventInit::new();
+                event_init.set_bubbles(true);
+                event_init.set_composed(true);
+                event_init.set_detail(&detail);
+                let event = web_sys::CustomEvent::new_with_event_init_dict(&name, &event_init)
+                    .expect("must create a custom event");
+                if let Err(error) = target.dispatch_event(&event) {
+                    log::error!("unable to dispatch the custom event {name}: {error:?}");
+                }
+            }))],
+        }
+    }
+
     /// map the msg of this Cmd such that Cmd<MSG> becomes Cmd<MSG2>.
     pub fn map_msg<F, MSG2>(self, f: F) -> Cmd<MSG2>
     where
@@ -81,6 +125,11 @@ where
     pub fn none() -> Self {
         Self { commands: vec![] }
     }
+
+    /// returns true if there is nothing to be executed in this Cmd
+    pub fn is_none(&self) -> bool {
+        self.commands.is_empty()
+    }
 }
 
 impl<MSG> From<Effects<MSG, ()>> for Cmd<MSG>
@@ -146,7 +195,8 @@ where
 
 /// Action is used to do asynchronous operations
 pub struct Action<MSG> {
-    task: Pin<Box<dyn Future<Output = MSG>>>,
+    /// the task which resolves to None when it is only executed for its side effects
+    task: Pin<Box<dyn Future<Output = Option<MSG>>>>,
     /// a marker to indicate if the value of the future is awaited.
     /// any attempt to await it again will error,
     /// saying that the async function is resumed after completion.
@@ -163,7 +213,22 @@ where
         F: Future<Output = MSG> + 'static,
     {
         Self {
-            task: Box::pin(f),
+            task: Box::pin(async move { Some(f.await) }),
+            done: false,
+        }
+    }
+
+    /// create a task which doesn't result to a msg
+    #[cfg(feature = "with-dom")]
+    fn effect<F>(f: F) -> Self
+    where
+        F: Future<Output = ()> + 'static,
+    {
+        Self {
+            task: Box::pin(async move {
+                f.await;
+                None
+            }),
             done: false,
         }
     }
@@ -175,10 +240,10 @@ where
         MSG2: 'static,
     {
         let task = self.task;
-        Action::new(async move {
-            let msg = task.await;
-            f(msg)
-        })
+        Action {
+            task: Box::pin(async move { task.await.map(f) }),
+            done: self.done,
+        }
     }
 
     /// get the next value
@@ -190,7 +255,7 @@ where
             let msg = self.task.as_mut().await;
             // mark as done
             self.done = true;
-            Some(msg)
+            msg
         }
     }
 }
@@ -4,14 +4,35 @@ use crate::vdom::AttributeValue;
 use crate::vdom::Leaf;
 use crate::{dom::Effects, vdom::Node};
 use derive_where::derive_where;
-use std::any::TypeId;
+use std::{
+    any::{Any, TypeId},
+    cell::{OnceCell, RefCell},
+    collections::{hash_map::DefaultHasher, HashMap},
+    fmt,
+    hash::{Hash, Hasher},
+    rc::Rc,
+};
 
+#[cfg(feature = "with-dom")]
+pub(crate) use stateful_component::Slots;
 #[cfg(feature = "with-dom")]
 pub use stateful_component::{stateful_component, StatefulComponent, StatefulModel};
 
+#[cfg(feature = "with-dom")]
+pub use custom_element::{
+    register_custom_element, register_custom_element_with, CustomElementHandle,
+};
+
+#[cfg(feature = "with-dom")]
+mod custom_element;
 #[cfg(feature = "with-dom")]
 mod stateful_component;
 
+thread_local! {
+    /// the view cache of the program which view is currently being created
+    static CURRENT_VIEWS: RefCell<Option<Rc<RefCell<ViewCache>>>> = const { RefCell::new(None) };
+}
+
 /// A component has a view and can update itself.
 ///
 /// The update function returns an effect which can contain
@@ -49,19 +70,82 @@ pub trait Component {
         vec![]
     }
 
+    /// a fingerprint of the state which the view of this component is created from.
+    /// When the fingerprint of the component is the same as the previous one,
+    /// the view of the component is not created again and is not diffed,
+    /// since it is assumed to be unchanged.
+    ///
+    /// Defaults to `None`, which always diffs the view of the component.
+    ///
+    /// # Example
+    /// ```rust
+    /// use sauron::{*, dom::fingerprint_of};
+    ///
+    /// struct Avatar {
+    ///     name: String,
+    ///     size: u32,
+    /// }
+    ///
+    /// impl Component for Avatar {
+    ///     type MSG = ();
+    ///     type XMSG = ();
+    ///
+    ///     fn update(&mut self, _msg: ()) -> Effects<(), ()> {
+    ///         Effects::none()
+    ///     }
+    ///
+    ///     fn view(&self) -> Node<()> {
+    ///         img([alt(&self.name), width(self.size)], [])
+    ///     }
+    ///
+    ///     fn fingerprint(&self) -> Option<u64> {
+    ///         Some(fingerprint_of(&(&self.name, self.size)))
+    ///     }
+    /// }
+    /// ```
+    fn fingerprint(&self) -> Option<u64> {
+        None
+    }
+
     /// in addition, component can contain dynamic style
     /// which can change when the model is updated
     fn style(&self) -> Vec<String> {
         vec![]
     }
 
+    /// the scope of the class names and selectors of this component, usually `module_path!()`.
+    /// When set, the component name is suffixed with a hash of the scope and the struct name,
+    /// ie: `Button_1f3a9c2e` instead of `Button`, such that components with the same name
+    /// from different modules don't share their styles.
+    /// The hash only depends on this scope, so the class names rendered in the server
+    /// matches the class names in the client.
+    ///
+    /// Defaults to `None`
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// fn style_scope() -> Option<&'static str> {
+    ///     Some(module_path!())
+    /// }
+    /// ```
+    fn style_scope() -> Option<&'static str>
+    where
+        Self: Sized,
+    {
+        None
+    }
+
     /// return the component name
-    /// defaults to the struct simplified name
+    /// defaults to the struct simplified name,
+    /// which is suffixed with the hash of the style scope if it is set
     fn component_name() -> String
     where
         Self: Sized,
     {
-        extract_simple_struct_name::<Self>()
+        match Self::style_scope() {
+            Some(scope) => extract_scoped_struct_name::<Self>(scope),
+            None => extract_simple_struct_name::<Self>(),
+        }
     }
 
     /// prefix the class bane
@@ -148,14 +232,35 @@ pub(crate) fn extract_simple_struct_name<T: ?Sized>() -> String {
         .expect("must have a name")
 }
 
+/// the simple struct name suffixed with the hash of the scope and the simple struct name,
+/// the generic arguments are not included, so the instances of a generic type share the name
+pub(crate) fn extract_scoped_struct_name<T: ?Sized>(scope: &str) -> String {
+    let name = extract_simple_struct_name::<T>();
+    // FNV-1a, which has a fixed output unlike the DefaultHasher,
+    // the output of `type_name` is not used since it may change between compiler versions
+    let hash = [scope, "::", &name]
+        .iter()
+        .flat_map(|part| part.bytes())
+        .fold(0x811c_9dc5_u32, |hash, byte| {
+            (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+        });
+    format!("{name}_{hash:08x}")
+}
+
 /// Contains necessary information for creating template
 /// of the Component of this type_id
 #[derive_where(Debug)]
 pub struct StatelessModel<MSG> {
-    /// the view of this stateless model
-    pub view: Box<Node<MSG>>,
+    /// the view of this stateless model, which is shared with the previous view of the program
+    /// when the component has the same fingerprint
+    view: Rc<ComponentView<MSG>>,
     /// component type id
     pub type_id: TypeId,
+    /// the fingerprint of the component when the view is created, see [`Component::fingerprint`]
+    pub(crate) fingerprint: Option<u64>,
+    /// the [`Component::stylesheet`] of the component,
+    /// which is injected once while there are instances of the component in the view
+    pub(crate) stylesheet: fn() -> Vec<String>,
 }
 
 impl<MSG> StatelessModel<MSG> {
@@ -168,33 +273,123 @@ impl<MSG> StatelessModel<MSG> {
     {
         StatelessModel {
             type_id: self.type_id,
-            view: Box::new(self.view.map_msg(cb.clone())),
+            view: Rc::new(ComponentView::map_msg(self.view, cb)),
+            fingerprint: self.fingerprint,
+            stylesheet: self.stylesheet,
         }
     }
 
+    /// the view of this stateless model
+    pub fn view(&self) -> &Node<MSG> {
+        self.view.get()
+    }
+
     /// return the attribute values of the view node matching the attribute name `name`
     pub fn attribute_value(&self, name: &AttributeName) -> Option<Vec<&AttributeValue<MSG>>> {
-        self.view.attribute_value(name)
+        self.view().attribute_value(name)
     }
 
     ///
     pub fn attributes(&self) -> Option<&[Attribute<MSG>]> {
-        self.view.attributes()
+        self.view().attributes()
+    }
+
+    /// returns true if the view of both models are created from the same state,
+    /// so diffing them can be skipped
+    pub fn is_unchanged(&self, other: &Self) -> bool {
+        self.type_id == other.type_id
+            && self.fingerprint.is_some()
+            && self.fingerprint == other.fingerprint
     }
 }
 
 impl<MSG> Clone for StatelessModel<MSG> {
     fn clone(&self) -> Self {
         Self {
-            view: self.view.clone(),
+            view: Rc::clone(&self.view),
             type_id: self.type_id,
+            fingerprint: self.fingerprint,
+            stylesheet: self.stylesheet,
         }
     }
 }
 
 impl<MSG> PartialEq for StatelessModel<MSG> {
     fn eq(&self, other: &Self) -> bool {
-        self.view == other.view && self.type_id == other.type_id
+        // the views of unchanged models are not compared, so they don't have to be mapped
+        self.is_unchanged(other)
+            || (self.type_id == other.type_id
+                && self.fingerprint == other.fingerprint
+                && self.view() == other.view())
+    }
+}
+
+/// The view of a stateless component.
+/// The view which is shared with the view cache is mapped into the msg of the parent only
+/// when it is used, which is never for the view of an unchanged component since it is
+/// not diffed.
+struct ComponentView<MSG> {
+    view: OnceCell<Rc<Node<MSG>>>,
+    /// maps the shared view into this view, None if the view is set
+    map_view: Option<Box<dyn Fn() -> Node<MSG>>>,
+}
+
+impl<MSG> ComponentView<MSG> {
+    fn new(view: Rc<Node<MSG>>) -> Self {
+        Self {
+            view: OnceCell::from(view),
+            map_view: None,
+        }
+    }
+
+    fn get(&self) -> &Node<MSG> {
+        self.view.get_or_init(|| match self.map_view.as_ref() {
+            Some(map_view) => Rc::new(map_view()),
+            None => unreachable!("the view is set when it is not mapped"),
+        })
+    }
+
+    /// the view, if it is not shared
+    fn into_owned(self) -> Result<Node<MSG>, Self> {
+        let Self { view, map_view } = self;
+        match view.into_inner().map(Rc::try_unwrap) {
+            Some(Ok(view)) => Ok(view),
+            Some(Err(view)) => Err(Self {
+                view: OnceCell::from(view),
+                map_view,
+            }),
+            None => Err(Self {
+                view: OnceCell::new(),
+                map_view,
+            }),
+        }
+    }
+
+    fn map_msg<F, MSG2>(this: Rc<Self>, cb: F) -> ComponentView<MSG2>
+    where
+        F: Fn(MSG) -> MSG2 + Clone + 'static,
+        MSG2: 'static,
+        MSG: 'static,
+    {
+        // the view which is not shared is mapped right away, without cloning it
+        let shared = match Rc::try_unwrap(this).map(Self::into_owned) {
+            Ok(Ok(view)) => return ComponentView::new(Rc::new(view.map_msg(cb))),
+            Ok(Err(this)) => Rc::new(this),
+            Err(this) => this,
+        };
+        ComponentView {
+            view: OnceCell::new(),
+            map_view: Some(Box::new(move || shared.get().clone().map_msg(cb.clone()))),
+        }
+    }
+}
+
+impl<MSG> fmt::Debug for ComponentView<MSG> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.view.get() {
+            Some(view) => view.fmt(f),
+            None => write!(f, "<unmapped view>"),
+        }
     }
 }
 
@@ -204,13 +399,85 @@ where
     COMP: Component + 'static,
 {
     let type_id = TypeId::of::<COMP>();
-    let app_view = app.view();
+    let fingerprint = app.fingerprint();
+    let view = match fingerprint {
+        Some(fingerprint) => cached_view((type_id, fingerprint), || app.view()),
+        None => Rc::new(app.view()),
+    };
     Node::Leaf(Leaf::StatelessComponent(StatelessModel {
-        view: Box::new(app_view),
+        view: Rc::new(ComponentView::new(view)),
         type_id,
+        fingerprint,
+        stylesheet: COMP::stylesheet,
     }))
 }
 
+/// reuse the view of the component from the previous view of the program,
+/// the view is only created when there is no view with the same fingerprint
+fn cached_view<MSG: 'static>(
+    key: (TypeId, u64),
+    create_view: impl FnOnce() -> Node<MSG>,
+) -> Rc<Node<MSG>> {
+    let Some(cache) = CURRENT_VIEWS.with(|current| current.borrow().clone()) else {
+        return Rc::new(create_view());
+    };
+    if let Some(view) = cache.borrow_mut().get(key) {
+        return view;
+    }
+    // the cache is not borrowed while the view creates the nested components
+    let view = Rc::new(create_view());
+    cache.borrow_mut().current.insert(key, view.clone());
+    view
+}
+
+/// The views of the stateless components which have a fingerprint, keyed by their type and fingerprint.
+/// The views in the previous view of the program are reused when its view is created again.
+#[derive(Default)]
+pub(crate) struct ViewCache {
+    /// the views of the components in the previous view of the program
+    previous: HashMap<(TypeId, u64), Rc<dyn Any>>,
+    /// the views of the components in the view which is currently being created
+    current: HashMap<(TypeId, u64), Rc<dyn Any>>,
+}
+
+impl ViewCache {
+    /// use this cache for the components that are created while the returned scope is alive
+    pub(crate) fn enter(this: &Rc<RefCell<Self>>) -> ViewCacheScope {
+        let previous = CURRENT_VIEWS.with(|current| current.replace(Some(Rc::clone(this))));
+        ViewCacheScope { previous }
+    }
+
+    /// the view of the component with this type and fingerprint, which is kept for the next view
+    fn get<MSG: 'static>(&mut self, key: (TypeId, u64)) -> Option<Rc<Node<MSG>>> {
+        let view = Rc::clone(self.current.get(&key).or_else(|| self.previous.get(&key))?);
+        self.current.insert(key, Rc::clone(&view));
+        view.downcast::<Node<MSG>>().ok()
+    }
+}
+
+/// Restores the previous view cache when dropped,
+/// only the views of the components that were created in this scope are kept in the cache
+pub(crate) struct ViewCacheScope {
+    previous: Option<Rc<RefCell<ViewCache>>>,
+}
+
+impl Drop for ViewCacheScope {
+    fn drop(&mut self) {
+        let cache = CURRENT_VIEWS.with(|current| current.replace(self.previous.take()));
+        if let Some(cache) = cache {
+            let mut cache = cache.borrow_mut();
+            cache.previous = std::mem::take(&mut cache.current);
+        }
+    }
+}
+
+/// hash the value into a fingerprint, to be returned in [`Component::fingerprint`]
+pub fn fingerprint_of<T: Hash + ?Sized>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -263,4 +530,49 @@ mod test {
         let name = extract_simple_struct_name::<ComplexEditor<Msg, Xmsg>>();
         assert_eq!("ComplexEditor", name);
     }
+
+    #[test]
+    fn test_scoped_name_of_same_named_structs() {
+        mod first {
+            pub struct Editor<MSG>(pub std::marker::PhantomData<MSG>);
+        }
+        mod second {
+            pub struct Editor;
+        }
+
+        let first = extract_scoped_struct_name::<first::Editor<()>>("app::first");
+        let second = extract_scoped_struct_name::<second::Editor>("app::second");
+        assert!(first.starts_with("Editor_"));
+        assert!(second.starts_with("Editor_"));
+        assert_ne!(first, second);
+        assert_eq!(
+            first,
+            extract_scoped_struct_name::<first::Editor<u32>>("app::first")
+        );
+        // the hash only depends on the scope and the name
+        assert_eq!(
+            extract_scoped_struct_name::<second::Editor>("app"),
+            "Editor_6b73eca1"
+        );
+    }
+
+    #[test]
+    fn test_shared_view_is_mapped_when_used() {
+        let view: Node<u32> = div([class("badge")], [text("1")]);
+        let model = |view: Rc<Node<u32>>| StatelessModel {
+            view: Rc::new(ComponentView::new(view)),
+            type_id: TypeId::of::<()>(),
+            fingerprint: Some(1),
+            stylesheet: Vec::new,
+        };
+
+        let unshared = model(Rc::new(view.clone())).map_msg(|n| n + 1);
+        assert!(unshared.view.view.get().is_some());
+
+        let cached = Rc::new(view);
+        let shared = model(Rc::clone(&cached)).map_msg(|n| n + 1);
+        assert!(shared.view.view.get().is_none());
+        assert_eq!(shared.view().render_to_string(), cached.render_to_string());
+        assert!(shared.view.view.get().is_some());
+    }
 }
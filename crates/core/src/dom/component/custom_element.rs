@@ -0,0 +1,382 @@
+//! Register a [`Component`] as a browser custom element, such that it can be used
+//! as a plain html tag, ie: `<date-time date="2022-02-22"></date-time>`,
+//! even outside of a sauron app.
+use crate::{
+    dom::{
+        program::MountProcedure, spawn_local, Application, Cmd, Component, DomAttr, DomAttrValue,
+        Effects, Program, StatefulComponent,
+    },
+    vdom::{AttributeName, Node},
+};
+use serde::Serialize;
+use std::{cell::RefCell, rc::Rc};
+use wasm_bindgen::{closure::Closure, prelude::*, JsCast};
+
+#[wasm_bindgen(inline_js = r#"
+export function define_custom_element(custom_tag, observed_attributes, create) {
+    if (customElements.get(custom_tag)) {
+        return false;
+    }
+    customElements.define(custom_tag, class extends HTMLElement {
+        static get observedAttributes() {
+            return observed_attributes;
+        }
+        constructor() {
+            super();
+            this.instance = create(this);
+        }
+        // the instance is freed when the element is disconnected,
+        // it is recreated from the current attributes when the element is connected again
+        connected_instance() {
+            if (!this.instance) {
+                this.instance = create(this);
+                for (const name of observed_attributes) {
+                    const value = this.getAttribute(name);
+                    if (value !== null) {
+                        this.instance.attribute_changed_callback(name, value);
+                    }
+                }
+            }
+            return this.instance;
+        }
+        connectedCallback() {
+            this.connected_instance().connected_callback();
+        }
+        disconnectedCallback() {
+            if (this.instance) {
+                this.instance.disconnected_callback();
+                this.instance.free();
+                this.instance = null;
+            }
+        }
+        adoptedCallback() {
+            this.connected_instance().adopted_callback();
+        }
+        attributeChangedCallback(name, old_value, new_value) {
+            if (this.instance) {
+                this.instance.attribute_changed_callback(name, new_value);
+            }
+        }
+    });
+    return true;
+}"#)]
+extern "C" {
+    fn define_custom_element(
+        custom_tag: &str,
+        observed_attributes: js_sys::Array,
+        create: &js_sys::Function,
+    ) -> bool;
+}
+
+/// Register the component as the custom element `custom_tag`, the component is mounted
+/// into the element when the element is attached to the document.
+///
+/// - The changes to the [`Component::observed_attributes`] of the element are passed to
+///   [`StatefulComponent::attribute_changed`] and [`StatefulComponent::remove_attribute`]
+/// - The lifecycle callbacks of the element are passed to the component
+/// - The component and its program are dropped when the element is removed from the document,
+///   a new component is created from the attributes of the element when it is attached again
+/// - The `XMSG` of the component are dispatched from the element as a bubbling `CustomEvent`
+///   named after the enum variant, with the content of the variant as the detail,
+///   ie: `XMSG::DateChanged(date)` is dispatched as `DateChanged` event with `date` as the detail.
+///   An `XMSG` which is not an enum is dispatched as an event named `custom_tag`.
+///
+/// # Example
+/// ```rust,ignore
+/// register_custom_element::<DateTimeWidget>("date-time");
+/// ```
+pub fn register_custom_element<COMP>(custom_tag: &'static str)
+where
+    COMP: Component + StatefulComponent + Default + 'static,
+    COMP::XMSG: Serialize,
+{
+    register_custom_element_with::<COMP>(custom_tag, MountProcedure::append())
+}
+
+/// Register the component as the custom element `custom_tag`, using the mount procedure
+/// when mounting the component into the element, ie: `MountProcedure::append_to_shadow()`
+/// to mount the component into the shadow root of the element.
+pub fn register_custom_element_with<COMP>(custom_tag: &'static str, mount_procedure: MountProcedure)
+where
+    COMP: Component + StatefulComponent + Default + 'static,
+    COMP::XMSG: Serialize,
+{
+    let observed_attributes: js_sys::Array = COMP::observed_attributes()
+        .into_iter()
+        .map(JsValue::from_str)
+        .collect();
+    let create: Closure<dyn FnMut(web_sys::HtmlElement) -> CustomElementHandle> =
+        Closure::new(move |host: web_sys::HtmlElement| CustomElementHandle {
+            element: Box::new(CustomElementProgram::<COMP>::new(
+                custom_tag,
+                host.unchecked_into(),
+                mount_procedure,
+            )),
+        });
+    let is_defined = define_custom_element(
+        custom_tag,
+        observed_attributes,
+        create.as_ref().unchecked_ref(),
+    );
+    if is_defined {
+        // the element class lives as long as the document
+        create.forget();
+    } else {
+        log::warn!("custom element {custom_tag} is already defined");
+    }
+}
+
+/// The lifecycle callbacks of a custom element
+trait CustomElementLifecycle {
+    fn connected(&mut self);
+    fn disconnected(&mut self);
+    fn adopted(&mut self);
+    fn attribute_changed(&mut self, name: &str, new_value: JsValue);
+}
+
+/// The instance of the custom element class, which the element calls
+/// when its lifecycle callbacks are invoked
+#[wasm_bindgen(js_name = SauronCustomElement)]
+pub struct CustomElementHandle {
+    element: Box<dyn CustomElementLifecycle>,
+}
+
+#[wasm_bindgen(js_class = SauronCustomElement)]
+impl CustomElementHandle {
+    /// the element is attached to the document
+    pub fn connected_callback(&mut self) {
+        self.element.connected();
+    }
+
+    /// the element is removed from the document
+    pub fn disconnected_callback(&mut self) {
+        self.element.disconnected();
+    }
+
+    /// the element is moved to a new document
+    pub fn adopted_callback(&mut self) {
+        self.element.adopted();
+    }
+
+    /// an observed attribute of the element is set, changed or removed
+    pub fn attribute_changed_callback(&mut self, name: &str, new_value: JsValue) {
+        self.element.attribute_changed(name, new_value);
+    }
+}
+
+/// The app which wraps the component mounted in the custom element,
+/// the `XMSG` of the component are dispatched from the host element as custom events
+struct CustomElementApp<COMP> {
+    comp: COMP,
+    custom_tag: &'static str,
+    host: web_sys::Element,
+}
+
+impl<COMP> CustomElementApp<COMP>
+where
+    COMP: Component + 'static,
+    COMP::XMSG: Serialize,
+{
+    fn emit_external(&self, effects: Effects<COMP::MSG, COMP::XMSG>) -> Cmd<COMP::MSG> {
+        let Effects { local, external } = effects;
+        for cmd in external {
+            for mut command in cmd.commands {
+                let custom_tag = self.custom_tag;
+                let host = self.host.clone();
+                spawn_local(async move {
+                    while let Some(xmsg) = command.next().await {
+                        dispatch_xmsg(&host, custom_tag, &xmsg);
+                    }
+                });
+            }
+        }
+        Cmd::batch(local)
+    }
+}
+
+/// dispatch the XMSG from the host element as a custom event
+fn dispatch_xmsg<XMSG: Serialize>(host: &web_sys::Element, custom_tag: &str, xmsg: &XMSG) {
+    let value = match serde_wasm_bindgen::to_value(xmsg) {
+        Ok(value) => value,
+        Err(error) => {
+            log::error!("unable to serialize the XMSG of {custom_tag}: {error}");
+            return;
+        }
+    };
+    // externally tagged enum variants are serialized as `"Variant"` or `{"Variant": content}`
+    let (name, detail) = if let Some(variant) = value.as_string() {
+        (variant, JsValue::NULL)
+    } else {
+        let entries = value
+            .dyn_ref::<js_sys::Map>()
+            .map(|map| js_sys::Array::from(&map.entries()))
+            .or_else(|| {
+                value
+                    .is_object()
+                    .then(|| js_sys::Object::entries(value.unchecked_ref()))
+            })
+            .filter(|entries| entries.length() == 1);
+        let variant = entries.and_then(|entries| {
+            let entry = js_sys::Array::from(&entries.get(0));
+            Some((entry.get(0).as_string()?, entry.get(1)))
+        });
+        match variant {
+            Some(variant) => variant,
+            None => (custom_tag.to_string(), value),
+        }
+    };
+    let event_init = web_sys::CustomEventInit::new();
+    event_init.set_bubbles(true);
+    event_init.set_composed(true);
+    event_init.set_detail(&detail);
+    let event = match web_sys::CustomEvent::new_with_event_init_dict(&name, &event_init) {
+        Ok(event) => event,
+        Err(error) => {
+            log::error!("unable to create the {name} event of {custom_tag}: {error:?}");
+            return;
+        }
+    };
+    if let Err(error) = host.dispatch_event(&event) {
+        log::error!("unable to dispatch {name} from {custom_tag}: {error:?}");
+    }
+}
+
+impl<COMP> Application for CustomElementApp<COMP>
+where
+    COMP: Component + 'static,
+    COMP::XMSG: Serialize,
+{
+    type MSG = COMP::MSG;
+
+    fn init(&mut self) -> Cmd<Self::MSG> {
+        let effects = self.comp.init();
+        self.emit_external(effects)
+    }
+
+    fn update(&mut self, msg: COMP::MSG) -> Cmd<Self::MSG> {
+        let effects = self.comp.update(msg);
+        self.emit_external(effects)
+    }
+
+    fn view(&self) -> Node<Self::MSG> {
+        self.comp.view()
+    }
+
+    fn stylesheet() -> Vec<String> {
+        COMP::stylesheet()
+    }
+
+    fn style(&self) -> Vec<String> {
+        self.comp.style()
+    }
+}
+
+/// The component of a custom element and the program which is mounted
+/// when the element is attached to the document
+struct CustomElementProgram<COMP>
+where
+    COMP: Component + 'static,
+    COMP::XMSG: Serialize,
+{
+    app: Rc<RefCell<CustomElementApp<COMP>>>,
+    mount_procedure: MountProcedure,
+    program: Option<Program<CustomElementApp<COMP>>>,
+}
+
+impl<COMP> CustomElementProgram<COMP>
+where
+    COMP: Component + StatefulComponent + Default + 'static,
+    COMP::XMSG: Serialize,
+{
+    fn new(
+        custom_tag: &'static str,
+        host: web_sys::Element,
+        mount_procedure: MountProcedure,
+    ) -> Self {
+        Self {
+            app: Rc::new(RefCell::new(CustomElementApp {
+                comp: COMP::default(),
+                custom_tag,
+                host,
+            })),
+            mount_procedure,
+            program: None,
+        }
+    }
+
+    /// create the program and mount it into the host element,
+    /// the view is created from the attributes that are set before the element is connected
+    fn mount(&mut self) {
+        let host: web_sys::Node = self.app.borrow().host.clone().unchecked_into();
+        let mut program = Program::from_rc_app(Rc::clone(&self.app));
+        if let Err(error) = program.mount(&host, self.mount_procedure) {
+            program.app_mut().on_error(&error);
+        }
+        self.program = Some(program);
+    }
+
+    /// remove the view from the host element and drop the program
+    fn unmount(&mut self) {
+        if let Some(program) = self.program.take() {
+            if let Err(error) = program.unmount() {
+                program.app_mut().on_error(&error);
+            }
+        }
+    }
+
+    fn update_dom(&mut self) {
+        if let Some(program) = self.program.as_mut() {
+            if let Err(error) = program.update_dom() {
+                program.recover_from_error(error);
+            }
+        }
+    }
+}
+
+impl<COMP> CustomElementLifecycle for CustomElementProgram<COMP>
+where
+    COMP: Component + StatefulComponent + Default + 'static,
+    COMP::XMSG: Serialize,
+{
+    fn connected(&mut self) {
+        if self.program.is_none() {
+            self.mount();
+        }
+        self.app.borrow_mut().comp.connected_callback();
+        self.update_dom();
+    }
+
+    fn disconnected(&mut self) {
+        self.app.borrow_mut().comp.disconnected_callback();
+        self.unmount();
+    }
+
+    fn adopted(&mut self) {
+        self.app.borrow_mut().comp.adopted_callback();
+        self.update_dom();
+    }
+
+    fn attribute_changed(&mut self, name: &str, new_value: JsValue) {
+        // the attribute name of DomAttr is static
+        let Some(attr_name): Option<AttributeName> = COMP::observed_attributes()
+            .into_iter()
+            .find(|observed| *observed == name)
+        else {
+            log::warn!("{name} is not an observed attribute");
+            return;
+        };
+        {
+            let comp = &mut self.app.borrow_mut().comp;
+            if new_value.is_null() {
+                comp.remove_attribute(attr_name);
+            } else {
+                comp.attribute_changed(DomAttr {
+                    namespace: None,
+                    name: attr_name,
+                    value: vec![DomAttrValue::from(new_value)],
+                });
+            }
+        }
+        self.update_dom();
+    }
+}
@@ -1,11 +1,13 @@
 use crate::{
     dom::{
-        events::on_component_mount, program::MountProcedure, Application, Cmd, Component, DomAttr,
-        DomAttrValue, DomNode, Program,
+        events::on_component_mount, program::MountProcedure, style_registry::ComponentStyles,
+        Application, Cmd, Component, DomAttr, DomAttrValue, DomNode, Error, HeadlessNode,
+        HeadlessProgram, Program,
     },
     vdom::{Attribute, AttributeName, Leaf, Node},
 };
 use std::{any::TypeId, cell::RefCell, fmt, rc::Rc};
+use wasm_bindgen::JsCast;
 
 /// A component that can be used directly in the view without mapping
 pub trait StatefulComponent {
@@ -20,6 +22,21 @@ pub trait StatefulComponent {
     /// return the DomNode which contains the children DomNode
     fn child_container(&self) -> Option<DomNode>;
 
+    /// return the DomNode which contains the external children assigned to the named slot,
+    /// ie: `h1([slot("header")], [])`.
+    /// By default, the children are appended into the `<slot name="header">` element
+    /// in the view of the component.
+    fn slot_container(&self, _slot_name: &str) -> Option<DomNode> {
+        None
+    }
+
+    /// mount the view of the component into the shadow root of its host element,
+    /// the external children are then appended into the host element
+    /// and placed by the browser into the `<slot>` elements of the view.
+    fn use_shadow_root(&self) -> bool {
+        false
+    }
+
     /// append a child into this component
     fn append_children(&mut self, _children: Vec<DomNode>) {}
 
@@ -35,6 +52,10 @@ pub trait StatefulComponent {
     fn adopted_callback(&mut self) {}
 }
 
+/// create the view of the component in the in-memory DOM,
+/// along with the callback which updates it after the component is changed
+type CreateHeadless = Rc<dyn Fn() -> (HeadlessNode, Rc<dyn Fn()>)>;
+
 /// Wrapper for stateful component
 pub struct StatefulModel<MSG> {
     ///
@@ -45,6 +66,15 @@ pub struct StatefulModel<MSG> {
     pub attrs: Vec<Attribute<MSG>>,
     /// external children component
     pub children: Vec<Node<MSG>>,
+    /// re-render the view of the component after its attributes or external children are changed
+    pub update_view: Rc<dyn Fn()>,
+    /// the external children which are placed in the slots of the component
+    pub(crate) slots: Rc<RefCell<Slots>>,
+    /// the stylesheet of the component which is registered by the program rendering it,
+    /// None when the component injects it into its own shadow root
+    pub(crate) stylesheet: Option<fn() -> Vec<String>>,
+    /// create the view of the component when it is rendered in the [`HeadlessProgram`]
+    pub(crate) create_headless: CreateHeadless,
 }
 
 impl<MSG> fmt::Debug for StatefulModel<MSG> {
@@ -74,6 +104,10 @@ impl<MSG> StatefulModel<MSG> {
                 .into_iter()
                 .map(|c| c.map_msg(cb.clone()))
                 .collect(),
+            update_view: self.update_view,
+            slots: self.slots,
+            stylesheet: self.stylesheet,
+            create_headless: self.create_headless,
         }
     }
 }
@@ -85,6 +119,10 @@ impl<MSG> Clone for StatefulModel<MSG> {
             type_id: self.type_id,
             attrs: self.attrs.clone(),
             children: self.children.clone(),
+            update_view: Rc::clone(&self.update_view),
+            slots: Rc::clone(&self.slots),
+            stylesheet: self.stylesheet,
+            create_headless: Rc::clone(&self.create_headless),
         }
     }
 }
@@ -142,28 +180,193 @@ where
 
     let app = Rc::new(RefCell::new(app));
 
+    let shadow_root = app.borrow().use_shadow_root();
+    let slots = Rc::new(RefCell::new(Slots::new(shadow_root)));
+    let comp: Rc<RefCell<dyn StatefulComponent>> = app.clone();
+
     let mut program = Program::from_rc_app(Rc::clone(&app));
+    if !shadow_root {
+        *program.component_styles.borrow_mut() = ComponentStyles::rendered_by_parent();
+    }
+    let component_program = program.clone();
+    let update_view = Rc::new(move || {
+        let mut program = component_program.clone();
+        // the changes before the component is mounted are already in its initial view
+        if program.root_node.borrow().is_none() {
+            return;
+        }
+        if let Err(error) = program.update_dom() {
+            program.recover_from_error(error);
+        }
+    });
     let children: Vec<Node<MSG>> = children.into_iter().collect();
+    let mount_slots = Rc::clone(&slots);
+    let mount_comp = Rc::clone(&comp);
     let mount_event = on_component_mount(move |me| {
-        program.mount(
-            &me.target_node.as_node(),
-            //MountProcedure::append_to_shadow(),
-            MountProcedure::append(),
-        );
-        let stylesheet = <COMP as Component>::stylesheet().join("\n");
-        log::info!("stylesheet: {}", stylesheet);
-        program.inject_style_to_mount(&stylesheet);
-        program.inject_style_to_mount(&program.app_context.dynamic_style());
-        program.update_dom().expect("update dom");
+        let mount_procedure = if shadow_root {
+            MountProcedure::append_to_shadow()
+        } else {
+            MountProcedure::append()
+        };
+        let mounted = program
+            .mount(&me.target_node.as_node(), mount_procedure)
+            .and_then(|()| program.update_dom())
+            .and_then(|()| {
+                mount_slots
+                    .borrow_mut()
+                    .mount(&mount_comp, me.target_node.as_element())
+            });
+        if let Err(error) = mounted {
+            program.app_mut().on_error(&error);
+        }
     });
+    let headless_app = Rc::clone(&app);
+    let create_headless: CreateHeadless =
+        Rc::new(move || HeadlessProgram::create_component(Rc::clone(&headless_app)));
+    let stylesheet: fn() -> Vec<String> = <COMP as Component>::stylesheet;
     Node::Leaf(Leaf::StatefulComponent(StatefulModel {
-        comp: app,
+        comp,
         type_id,
         attrs: attrs.into_iter().chain([mount_event]).collect(),
         children: children.into_iter().collect(),
+        update_view,
+        slots,
+        stylesheet: (!shadow_root).then_some(stylesheet),
+        create_headless,
     }))
 }
 
+/// The placement of the external children of a stateful component into the slots of its view.
+/// The children without a slot are passed to the component with
+/// [`StatefulComponent::append_children`] instead.
+pub(crate) struct Slots {
+    /// the view of the component is mounted in the shadow root of the host element
+    shadow_root: bool,
+    /// the element which hosts the component, set once the component is mounted
+    host: Option<web_sys::Element>,
+    /// the slotted children which are placed once the component is mounted
+    pending: Vec<(Slot, DomNode)>,
+    /// the children which are passed to the component, in the order the component has them,
+    /// including the slotted children whose slot is not found in the view of the component
+    passed: Vec<DomNode>,
+}
+
+/// where a slotted child is placed
+enum Slot {
+    /// in the host element, where the browser assigns it to the slots of the shadow root
+    Host,
+    /// in the slot container or the `slot` element with this name
+    Named(String),
+}
+
+impl Slots {
+    fn new(shadow_root: bool) -> Self {
+        Self {
+            shadow_root,
+            host: None,
+            pending: vec![],
+            passed: vec![],
+        }
+    }
+
+    /// the slot of the child, None if the child is passed to the component
+    fn slot_of(&self, child: &DomNode) -> Option<Slot> {
+        if self.shadow_root {
+            Some(Slot::Host)
+        } else {
+            child.slot_name().map(Slot::Named)
+        }
+    }
+
+    /// place the slotted children in their slots and pass the rest to the component
+    pub(crate) fn append_children(
+        &mut self,
+        comp: &RefCell<dyn StatefulComponent>,
+        children: Vec<DomNode>,
+    ) -> Result<(), Error> {
+        let mut unslotted = vec![];
+        for child in children {
+            match self.slot_of(&child) {
+                Some(slot) => match self.host.clone() {
+                    Some(host) => self.place(comp, &host, slot, child)?,
+                    None => self.pending.push((slot, child)),
+                },
+                None => unslotted.push(child),
+            }
+        }
+        self.pass(comp, unslotted);
+        Ok(())
+    }
+
+    /// pass the children to the component
+    fn pass(&mut self, comp: &RefCell<dyn StatefulComponent>, children: Vec<DomNode>) {
+        if !children.is_empty() {
+            self.passed.extend(children.iter().cloned());
+            comp.borrow_mut().append_children(children);
+        }
+    }
+
+    /// the view of the component is mounted in the host element,
+    /// so the pending children can now be placed in their slots
+    fn mount(
+        &mut self,
+        comp: &RefCell<dyn StatefulComponent>,
+        host: web_sys::Element,
+    ) -> Result<(), Error> {
+        for (slot, child) in std::mem::take(&mut self.pending) {
+            self.place(comp, &host, slot, child)?;
+        }
+        self.host = Some(host);
+        Ok(())
+    }
+
+    fn place(
+        &mut self,
+        comp: &RefCell<dyn StatefulComponent>,
+        host: &web_sys::Element,
+        slot: Slot,
+        child: DomNode,
+    ) -> Result<(), Error> {
+        let slot_name = match slot {
+            // the browser assigns the children of the host to the slots of the shadow root
+            Slot::Host => {
+                let host: web_sys::Node = host.clone().unchecked_into();
+                return DomNode::from(host).append_children(vec![child]);
+            }
+            Slot::Named(slot_name) => slot_name,
+        };
+        let container = comp.borrow().slot_container(&slot_name).or_else(|| {
+            host.query_selector(&format!("slot[name=\"{slot_name}\"]"))
+                .ok()
+                .flatten()
+                .map(|slot| DomNode::from(web_sys::Node::from(slot)))
+        });
+        if let Some(container) = container {
+            container.append_children(vec![child])
+        } else {
+            log::warn!("there is no slot named {slot_name} in the component");
+            self.pass(comp, vec![child]);
+            Ok(())
+        }
+    }
+
+    /// remove the child from where it is placed,
+    /// returns the index of the child in the children which are passed to the component
+    /// when it is removed from the component instead
+    pub(crate) fn remove_child(&mut self, child: &DomNode) -> Result<Option<usize>, Error> {
+        if let Some(index) = self.passed.iter().position(|passed| passed == child) {
+            self.passed.remove(index);
+            return Ok(Some(index));
+        }
+        self.pending.retain(|(_, pending)| pending != child);
+        let node = child.as_node();
+        if let Some(parent) = node.parent_node() {
+            parent.remove_child(&node)?;
+        }
+        Ok(None)
+    }
+}
+
 #[cfg(feature = "with-dom")]
 impl From<wasm_bindgen::JsValue> for DomAttrValue {
     fn from(val: wasm_bindgen::JsValue) -> Self {
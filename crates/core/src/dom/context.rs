@@ -0,0 +1,110 @@
+//! Context, where a value such as the theme, the current user or an api client is provided
+//! to the views of the components under it, without passing it through every
+//! `view` function and component constructor.
+//!
+//! # Example
+//! ```rust
+//! use sauron::{*, dom::{provide, use_context}};
+//!
+//! struct Theme {
+//!     color: &'static str,
+//! }
+//!
+//! fn themed_button<MSG>() -> Node<MSG> {
+//!     let theme = use_context::<Theme>().expect("must have a theme");
+//!     button([style! {color: theme.color}], [text("Save")])
+//! }
+//!
+//! let view: Node<()> = provide(Theme { color: "red" }, || [themed_button()]);
+//! assert_eq!(
+//!     view.render_to_string(),
+//!     r#"<button style="color:red;">Save</button>"#
+//! );
+//! ```
+use crate::{html::node_list, vdom::Node};
+use std::{
+    any::{Any, TypeId},
+    cell::RefCell,
+    collections::HashMap,
+    fmt,
+    rc::Rc,
+};
+
+thread_local! {
+    /// the values provided to the views that are currently being created
+    static CURRENT: RefCell<Context> = RefCell::new(Context::default());
+}
+
+/// The values which are provided to the views, at most one value for each type
+#[derive(Clone, Default)]
+pub struct Context {
+    values: Rc<HashMap<TypeId, Rc<dyn Any>>>,
+}
+
+impl fmt::Debug for Context {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Context({} values)", self.values.len())
+    }
+}
+
+impl Context {
+    /// the context of the views that are currently being created
+    pub fn current() -> Self {
+        CURRENT.with(|current| current.borrow().clone())
+    }
+
+    /// return the provided value of this type
+    pub fn get<T: 'static>(&self) -> Option<Rc<T>> {
+        let value = self.values.get(&TypeId::of::<T>())?;
+        Rc::clone(value).downcast::<T>().ok()
+    }
+
+    /// a copy of this context, where the value replaces the previous value of the same type
+    pub fn with<T: 'static>(&self, value: T) -> Self {
+        let mut values = HashMap::clone(&self.values);
+        values.insert(TypeId::of::<T>(), Rc::new(value));
+        Self {
+            values: Rc::new(values),
+        }
+    }
+
+    /// use this context for the views that are created while the returned scope is alive
+    pub fn enter(&self) -> ContextScope {
+        let previous = CURRENT.with(|current| current.replace(self.clone()));
+        ContextScope { previous }
+    }
+}
+
+/// Restores the previous context when dropped
+#[derive(Debug)]
+pub struct ContextScope {
+    previous: Context,
+}
+
+impl Drop for ContextScope {
+    fn drop(&mut self) {
+        let previous = std::mem::take(&mut self.previous);
+        CURRENT.with(|current| current.replace(previous));
+    }
+}
+
+/// provide the value to the views created in `children`, which can read it with [`use_context`].
+///
+/// The children are created in a closure, since the view of the components are created
+/// eagerly, ie: `component(&comp)` calls `comp.view()` right away.
+/// The stateful components created in `children` keeps the context for their later updates.
+pub fn provide<T, MSG, F, I>(value: T, children: F) -> Node<MSG>
+where
+    T: 'static,
+    F: FnOnce() -> I,
+    I: IntoIterator<Item = Node<MSG>>,
+{
+    let _scope = Context::current().with(value).enter();
+    node_list(children())
+}
+
+/// return the nearest provided value of this type,
+/// this is meant to be called in the `view` of the apps and components
+pub fn use_context<T: 'static>() -> Option<Rc<T>> {
+    CURRENT.with(|current| current.borrow().get::<T>())
+}
@@ -0,0 +1,297 @@
+//! A time-travel debugger for the msgs dispatched to an Application.
+//!
+//! The [`Debugger`] wraps the app and records every dispatched MSG. The app can be stepped
+//! back and forward through the recorded history, which is done by re-running `update`
+//! from the initial state of the app up to the chosen msg. The DOM is then patched
+//! to match the view of the app at that point.
+//!
+//! The Cmd returned by the app while replaying the msgs are not executed, since their effects
+//! have already been recorded as msgs in the history.
+//! Dispatching a new msg while at an earlier point in the history discards the msgs after it.
+//!
+//! # Example
+//! ```rust,no_run
+//! use sauron::{*, dom::Debugger};
+//!
+//! #[derive(Clone)]
+//! struct App {
+//!     count: i32,
+//! }
+//!
+//! #[derive(Debug, Clone)]
+//! enum Msg {
+//!     Increment,
+//! }
+//!
+//! impl Application for App {
+//!     type MSG = Msg;
+//!
+//!     fn update(&mut self, msg: Msg) -> Cmd<Msg> {
+//!         match msg {
+//!             Msg::Increment => self.count += 1,
+//!         }
+//!         Cmd::none()
+//!     }
+//!
+//!     fn view(&self) -> Node<Msg> {
+//!         button([on_click(|_| Msg::Increment)], [text(self.count)])
+//!     }
+//! }
+//!
+//! Program::mount_to_body(Debugger::new(App { count: 0 }));
+//! ```
+use crate::{
+    dom::{events::on, Application, Cmd, Error, Measurements},
+    html::{
+        attributes::{class, classes_flag, disabled, title},
+        *,
+    },
+    vdom::Node,
+};
+use std::fmt;
+
+/// Wraps the app to record the dispatched msgs and allow stepping through them
+pub struct Debugger<APP>
+where
+    APP: Application,
+{
+    /// the state of the app right after it is initialized
+    initial: APP,
+    /// the app at the current point in the history
+    app: APP,
+    /// all the msgs dispatched to the app in the order they were dispatched
+    history: Vec<APP::MSG>,
+    /// the number of msgs from the history which are applied to the app
+    cursor: usize,
+    /// whether the list of msgs in the overlay is shown
+    expanded: bool,
+}
+
+/// The msgs of the debugger
+#[derive(Debug, Clone)]
+pub enum DebuggerMsg<MSG> {
+    /// a msg dispatched by the app
+    App(MSG),
+    /// go to the state before the current msg
+    StepBack,
+    /// go to the state after the next msg
+    StepForward,
+    /// go to the state after applying this number of msgs from the history
+    JumpTo(usize),
+    /// show or hide the list of msgs
+    ToggleOverlay,
+}
+
+impl<APP> Debugger<APP>
+where
+    APP: Application + Clone,
+    APP::MSG: fmt::Debug + Clone,
+{
+    /// wraps the app with the debugger
+    pub fn new(app: APP) -> Self {
+        Self {
+            initial: app.clone(),
+            app,
+            history: vec![],
+            cursor: 0,
+            expanded: true,
+        }
+    }
+
+    /// the app at the current point in the history
+    pub fn app(&self) -> &APP {
+        &self.app
+    }
+
+    /// the msgs recorded by the debugger
+    pub fn history(&self) -> &[APP::MSG] {
+        &self.history
+    }
+
+    /// the number of msgs from the history which are applied to the app
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// re-run `update` from the initial state with the first `n` msgs from the history
+    fn replay(&mut self, n: usize) {
+        self.app = self.initial.clone();
+        for msg in self.history[..n].iter().cloned() {
+            // the effects of the cmd are already recorded in the history
+            let _ = self.app.update(msg);
+        }
+        self.cursor = n;
+    }
+
+    fn view_overlay(&self) -> Node<DebuggerMsg<APP::MSG>> {
+        let total = self.history.len();
+        aside(
+            [class("sauron-debugger")],
+            [
+                div(
+                    [class("sauron-debugger__controls")],
+                    [
+                        button(
+                            [
+                                title("step back"),
+                                disabled(self.cursor == 0),
+                                on("click", |_| DebuggerMsg::StepBack),
+                            ],
+                            [text("◀")],
+                        ),
+                        span(
+                            [class("sauron-debugger__position")],
+                            [text!("{}/{}", self.cursor, total)],
+                        ),
+                        button(
+                            [
+                                title("step forward"),
+                                disabled(self.cursor == total),
+                                on("click", |_| DebuggerMsg::StepForward),
+                            ],
+                            [text("▶")],
+                        ),
+                        button(
+                            [
+                                title("toggle the msgs"),
+                                on("click", |_| DebuggerMsg::ToggleOverlay),
+                            ],
+                            [text(if self.expanded { "▾" } else { "▸" })],
+                        ),
+                    ],
+                ),
+                view_if(
+                    self.expanded,
+                    ol(
+                        [class("sauron-debugger__msgs")],
+                        std::iter::once("init".to_string())
+                            .chain(self.history.iter().map(|msg| format!("{msg:?}")))
+                            .enumerate()
+                            .map(|(n, msg)| {
+                                li(
+                                    [
+                                        classes_flag([
+                                            ("current", n == self.cursor),
+                                            ("future", n > self.cursor),
+                                        ]),
+                                        on("click", move |_| DebuggerMsg::JumpTo(n)),
+                                    ],
+                                    [text(msg)],
+                                )
+                            }),
+                    ),
+                ),
+            ],
+        )
+    }
+}
+
+impl<APP> Application for Debugger<APP>
+where
+    APP: Application + Clone,
+    APP::MSG: fmt::Debug + Clone,
+{
+    type MSG = DebuggerMsg<APP::MSG>;
+
+    fn init(&mut self) -> Cmd<Self::MSG> {
+        let cmd = self.app.init();
+        self.initial = self.app.clone();
+        cmd.map_msg(DebuggerMsg::App)
+    }
+
+    fn update(&mut self, msg: Self::MSG) -> Cmd<Self::MSG> {
+        match msg {
+            DebuggerMsg::App(msg) => {
+                self.history.truncate(self.cursor);
+                self.history.push(msg.clone());
+                self.cursor += 1;
+                self.app.update(msg).map_msg(DebuggerMsg::App)
+            }
+            DebuggerMsg::StepBack => {
+                if self.cursor > 0 {
+                    self.replay(self.cursor - 1);
+                }
+                Cmd::none()
+            }
+            DebuggerMsg::StepForward => {
+                if let Some(msg) = self.history.get(self.cursor).cloned() {
+                    let _ = self.app.update(msg);
+                    self.cursor += 1;
+                }
+                Cmd::none()
+            }
+            DebuggerMsg::JumpTo(n) => {
+                if n <= self.history.len() {
+                    self.replay(n);
+                }
+                Cmd::none()
+            }
+            DebuggerMsg::ToggleOverlay => {
+                self.expanded = !self.expanded;
+                Cmd::none()
+            }
+        }
+    }
+
+    fn view(&self) -> Node<Self::MSG> {
+        div(
+            [class("sauron-debugger__app")],
+            [
+                self.app.view().map_msg(DebuggerMsg::App),
+                self.view_overlay(),
+            ],
+        )
+    }
+
+    fn stylesheet() -> Vec<String> {
+        let mut stylesheet = APP::stylesheet();
+        stylesheet.push(DEBUGGER_STYLE.to_string());
+        stylesheet
+    }
+
+    fn style(&self) -> Vec<String> {
+        self.app.style()
+    }
+
+    fn measurements(&mut self, measurements: Measurements) {
+        self.app.measurements(measurements)
+    }
+
+    fn on_error(&mut self, error: &Error) {
+        self.app.on_error(error)
+    }
+}
+
+const DEBUGGER_STYLE: &str = r#"
+.sauron-debugger {
+    position: fixed;
+    right: 0;
+    bottom: 0;
+    max-height: 50vh;
+    overflow: auto;
+    z-index: 2147483647;
+    font: 12px monospace;
+    color: #eee;
+    background: rgba(30, 30, 30, 0.9);
+}
+.sauron-debugger__controls {
+    display: flex;
+    gap: 4px;
+    align-items: center;
+    padding: 4px;
+}
+.sauron-debugger__msgs {
+    margin: 0;
+    padding: 4px 4px 4px 32px;
+}
+.sauron-debugger__msgs li {
+    cursor: pointer;
+}
+.sauron-debugger__msgs li.current {
+    font-weight: bold;
+    color: #7fdbff;
+}
+.sauron-debugger__msgs li.future {
+    opacity: 0.5;
+}
+"#;
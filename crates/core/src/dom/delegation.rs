@@ -0,0 +1,308 @@
+//! Event delegation, where the event listeners of the elements are not attached to each
+//! element, instead a single listener for each event type is attached to the delegation roots,
+//! ie: the mount node of the program. The event is then dispatched to the registered callbacks
+//! of the target element and its ancestors.
+use crate::dom::dom_node::{intern, EventClosure};
+use indexmap::{IndexMap, IndexSet};
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    fmt,
+    rc::Rc,
+};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+
+/// the property of the element which holds the id of its delegated listeners
+const LISTENERS_ID: &str = "__sauron_listeners_id";
+/// the property of the event which marks it as already dispatched by a delegation root
+const DISPATCHED: &str = "__sauron_delegated";
+
+/// the mount event is dispatched to elements which may not be attached to a root yet,
+/// so it is always attached directly to the element
+const NON_DELEGATED_EVENTS: &[&str] = &["mount"];
+
+thread_local! {
+    /// whether the event listeners that are created are delegated to the roots
+    static DELEGATING: Cell<bool> = const { Cell::new(false) };
+    /// the id of the next element that registers a delegated listener
+    static NEXT_ID: Cell<u32> = const { Cell::new(0) };
+    /// the delegated listeners of each element, keyed by the id of the element
+    static REGISTRY: RefCell<HashMap<u32, IndexMap<&'static str, Vec<DelegatedListener>>>> =
+        RefCell::new(HashMap::new());
+    /// the event types which have delegated listeners
+    static EVENT_NAMES: RefCell<IndexSet<&'static str>> = RefCell::new(IndexSet::new());
+    /// the targets where the events are listened to
+    static ROOTS: RefCell<Vec<DelegationRoot>> = const { RefCell::new(vec![]) };
+}
+
+/// an event listener which is called from a delegation root instead of being attached
+/// to the element as a closure
+#[derive(Clone)]
+pub struct DelegatedListener(Rc<dyn Fn(web_sys::Event)>);
+
+impl DelegatedListener {
+    /// create a delegated listener from a function
+    pub fn new(f: impl Fn(web_sys::Event) + 'static) -> Self {
+        Self(Rc::new(f))
+    }
+
+    fn call(&self, event: web_sys::Event) {
+        (self.0)(event)
+    }
+}
+
+impl fmt::Debug for DelegatedListener {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "DelegatedListener")
+    }
+}
+
+/// The registration of the delegated listeners of an element,
+/// the listeners are unregistered when this is dropped.
+#[derive(Debug)]
+pub struct DelegatedListeners {
+    id: u32,
+}
+
+impl DelegatedListeners {
+    /// register the element such that the listeners set to it can be found
+    /// when an event is dispatched from this element or from its descendants
+    pub(crate) fn register(element: &web_sys::Element) -> Result<Self, JsValue> {
+        let id = NEXT_ID.with(|next_id| {
+            let id = next_id.get();
+            next_id.set(id.wrapping_add(1));
+            id
+        });
+        js_sys::Reflect::set(
+            element,
+            &JsValue::from_str(LISTENERS_ID),
+            &JsValue::from(id),
+        )?;
+        REGISTRY.with(|registry| registry.borrow_mut().insert(id, IndexMap::new()));
+        Ok(Self { id })
+    }
+
+    /// set the listeners for the event, replacing the previous ones
+    pub(crate) fn set(&self, event_name: &'static str, listeners: Vec<DelegatedListener>) {
+        listen_to(event_name);
+        REGISTRY.with(|registry| {
+            if let Some(registered) = registry.borrow_mut().get_mut(&self.id) {
+                registered.insert(event_name, listeners);
+            }
+        });
+    }
+
+    /// remove the listeners of the event
+    pub(crate) fn remove(&self, event_name: &str) {
+        REGISTRY.with(|registry| {
+            if let Some(registered) = registry.borrow_mut().get_mut(&self.id) {
+                registered.shift_remove(event_name);
+            }
+        });
+    }
+}
+
+impl Drop for DelegatedListeners {
+    fn drop(&mut self) {
+        REGISTRY.with(|registry| registry.borrow_mut().remove(&self.id));
+    }
+}
+
+/// Marks the event listeners created while this is alive as delegated or not.
+/// The previous mode is restored when this is dropped.
+pub(crate) struct DelegationScope {
+    previous: bool,
+}
+
+impl DelegationScope {
+    pub(crate) fn new(delegating: bool) -> Self {
+        let previous = DELEGATING.with(|d| d.replace(delegating));
+        Self { previous }
+    }
+}
+
+impl Drop for DelegationScope {
+    fn drop(&mut self) {
+        DELEGATING.with(|d| d.set(self.previous));
+    }
+}
+
+/// returns true if the event listeners that are created are delegated to the roots
+pub(crate) fn is_delegating() -> bool {
+    DELEGATING.with(|d| d.get())
+}
+
+/// returns true if the listener for this event is to be delegated to the roots
+pub(crate) fn should_delegate(event_name: &str) -> bool {
+    is_delegating() && !NON_DELEGATED_EVENTS.contains(&event_name)
+}
+
+/// A target where the events dispatched from its descendants are listened to
+struct DelegationRoot {
+    target: web_sys::EventTarget,
+    listeners: IndexMap<&'static str, EventClosure>,
+    /// the number of the programs and portals which uses this root
+    count: usize,
+}
+
+impl DelegationRoot {
+    /// listen to the event in this root, the failure is logged since it is not caused by
+    /// any of the elements which has the delegated listeners
+    fn listen_to(&mut self, event_name: &'static str) {
+        if self.listeners.contains_key(event_name) {
+            return;
+        }
+        if let Err(error) = self.add_listeners(event_name) {
+            log::error!("unable to listen to {event_name} in the delegation root: {error:?}");
+        }
+    }
+
+    fn add_listeners(&mut self, event_name: &'static str) -> Result<(), JsValue> {
+        let closure: EventClosure = Closure::new(dispatch);
+        // the events which don't bubble are dispatched in the capture phase
+        self.target.add_event_listener_with_callback_and_bool(
+            intern(event_name),
+            closure.as_ref().unchecked_ref(),
+            true,
+        )?;
+        // the closure is kept as soon as it is attached, so it can be removed when dropped
+        self.listeners.insert(event_name, closure);
+        let closure = &self.listeners[event_name];
+        self.target
+            .add_event_listener_with_callback(intern(event_name), closure.as_ref().unchecked_ref())
+    }
+}
+
+impl Drop for DelegationRoot {
+    fn drop(&mut self) {
+        for (event_name, closure) in self.listeners.iter() {
+            let listener = closure.as_ref().unchecked_ref();
+            if let Err(error) = self
+                .target
+                .remove_event_listener_with_callback_and_bool(event_name, listener, true)
+            {
+                log::error!("unable to remove the capture listener of {event_name}: {error:?}");
+            }
+            if let Err(error) = self
+                .target
+                .remove_event_listener_with_callback(event_name, listener)
+            {
+                log::error!("unable to remove the listener of {event_name}: {error:?}");
+            }
+        }
+    }
+}
+
+/// The use of a delegation root by a program or a portal,
+/// the root stops listening to the events when all of its handles are dropped
+#[derive(Debug)]
+pub(crate) struct RootHandle {
+    target: web_sys::EventTarget,
+}
+
+impl Drop for RootHandle {
+    fn drop(&mut self) {
+        release_root(&self.target);
+    }
+}
+
+/// listen to the events dispatched from the descendants of this target
+/// for as long as the returned handle is alive
+pub(crate) fn add_root(target: &web_sys::EventTarget) -> RootHandle {
+    ROOTS.with(|roots| {
+        let mut roots = roots.borrow_mut();
+        if let Some(root) = roots.iter_mut().find(|root| root.target == *target) {
+            root.count += 1;
+            return;
+        }
+        let mut root = DelegationRoot {
+            target: target.clone(),
+            listeners: IndexMap::new(),
+            count: 1,
+        };
+        EVENT_NAMES.with(|names| {
+            for event_name in names.borrow().iter() {
+                root.listen_to(event_name);
+            }
+        });
+        roots.push(root);
+    });
+    RootHandle {
+        target: target.clone(),
+    }
+}
+
+/// stop listening to the events dispatched from the descendants of this target
+/// when it is no longer used
+fn release_root(target: &web_sys::EventTarget) {
+    let removed = ROOTS.with(|roots| {
+        let mut roots = roots.borrow_mut();
+        let index = roots.iter().position(|root| root.target == *target)?;
+        roots[index].count -= 1;
+        (roots[index].count == 0).then(|| roots.remove(index))
+    });
+    // the listeners are removed outside of the borrow of the roots
+    drop(removed);
+}
+
+/// make sure all the roots listens to this event
+fn listen_to(event_name: &'static str) {
+    let is_new = EVENT_NAMES.with(|names| names.borrow_mut().insert(event_name));
+    if is_new {
+        ROOTS.with(|roots| {
+            for root in roots.borrow_mut().iter_mut() {
+                root.listen_to(event_name);
+            }
+        });
+    }
+}
+
+/// call the listeners of the target and its ancestors, in the same order as the event bubbles
+fn dispatch(event: web_sys::Event) {
+    let bubbles = event.bubbles();
+    if bubbles && event.event_phase() == web_sys::Event::CAPTURING_PHASE {
+        return;
+    }
+    // the same event reaches each of the roots it bubbles through,
+    // but only the first one dispatches it
+    let dispatched = JsValue::from_str(DISPATCHED);
+    if js_sys::Reflect::has(&event, &dispatched).unwrap_or(false) {
+        return;
+    }
+    if let Err(error) = js_sys::Reflect::set(&event, &dispatched, &JsValue::TRUE) {
+        log::error!("unable to mark the event as dispatched: {error:?}");
+    }
+
+    let event_name = event.type_();
+    let mut current: Option<web_sys::Node> =
+        event.target().and_then(|target| target.dyn_into().ok());
+    while let Some(node) = current {
+        let listeners = listeners_of(&node, &event_name);
+        for listener in listeners.iter() {
+            listener.call(event.clone());
+        }
+        if !bubbles || event.cancel_bubble() {
+            break;
+        }
+        current = node.parent_node();
+    }
+}
+
+/// the delegated listeners of the event registered to this node
+fn listeners_of(node: &web_sys::Node, event_name: &str) -> Vec<DelegatedListener> {
+    let Some(id) = js_sys::Reflect::get(node, &JsValue::from_str(LISTENERS_ID))
+        .ok()
+        .and_then(|id| id.as_f64())
+    else {
+        return vec![];
+    };
+    // the listeners are cloned, since calling them may patch the DOM and update the registry
+    REGISTRY.with(|registry| {
+        registry
+            .borrow()
+            .get(&(id as u32))
+            .and_then(|registered| registered.get(event_name))
+            .cloned()
+            .unwrap_or_default()
+    })
+}
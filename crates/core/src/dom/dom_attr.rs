@@ -1,4 +1,7 @@
+use crate::dom::dom_node::EventClosure;
+use crate::dom::DelegatedListener;
 use crate::vdom::AttributeName;
+use crate::vdom::ListenerOptions;
 use crate::vdom::Namespace;
 use crate::vdom::Style;
 use crate::vdom::Value;
@@ -36,8 +39,10 @@ pub enum DomAttrValue {
     Simple(Value),
     /// a style
     Style(Vec<Style>),
-    /// event listeners
-    EventListener(Closure<dyn FnMut(web_sys::Event)>),
+    /// event listeners and the options used when attaching them to the element
+    EventListener(Closure<dyn FnMut(web_sys::Event)>, ListenerOptions),
+    /// event listeners which are called from the delegation root instead of being attached to the element
+    DelegatedListener(DelegatedListener),
     /// an empty value, can also represents null values from JsValue
     Empty,
 }
@@ -46,7 +51,9 @@ pub enum DomAttrValue {
 /// separately
 pub struct GroupedDomAttrValues {
     /// the listeners of the event listeners
-    pub listeners: Vec<Closure<dyn FnMut(web_sys::Event)>>,
+    pub listeners: Vec<(EventClosure, ListenerOptions)>,
+    /// the listeners which are delegated to the root
+    pub delegated_listeners: Vec<DelegatedListener>,
     /// plain attribute values
     pub plain_values: Vec<Value>,
     /// style attribute values
@@ -57,6 +64,7 @@ impl DomAttr {
     /// return the values grouped into listeners, plain, styles and function calls
     pub(crate) fn group_values(self) -> GroupedDomAttrValues {
         let mut listeners = vec![];
+        let mut delegated_listeners = vec![];
         let mut plain_values = vec![];
         let mut styles = vec![];
         for av in self.value {
@@ -67,14 +75,18 @@ impl DomAttr {
                 DomAttrValue::Style(s) => {
                     styles.extend(s);
                 }
-                DomAttrValue::EventListener(cb) => {
-                    listeners.push(cb);
+                DomAttrValue::EventListener(cb, options) => {
+                    listeners.push((cb, options));
+                }
+                DomAttrValue::DelegatedListener(cb) => {
+                    delegated_listeners.push(cb);
                 }
                 DomAttrValue::Empty => (),
             }
         }
         GroupedDomAttrValues {
             listeners,
+            delegated_listeners,
             plain_values,
             styles,
         }
@@ -0,0 +1,87 @@
+//! The operations on the nodes of a DOM which are needed to create and patch it.
+//!
+//! [`DomNode`](crate::dom::DomNode) is the backend for the browser DOM,
+//! while [`HeadlessNode`](crate::dom::HeadlessNode) is the in-memory DOM which runs natively.
+//! Both are created from the vdom and patched by the same
+//! [`convert_patches`](crate::dom::convert_patches) and
+//! [`apply_dom_patches`](crate::dom::apply_dom_patches).
+use crate::{
+    dom::{DomPatch, Error},
+    vdom::{self, Attribute},
+};
+use std::fmt;
+
+/// A node of a DOM which can be created from the vdom and patched
+pub trait DomBackend: Clone + PartialEq + fmt::Debug + Sized + 'static {
+    /// the attribute with its values converted, which is set into the node
+    type Attr: fmt::Debug;
+
+    /// create a node and its descendants from the vdom node,
+    /// the event listeners pass the msg they produced into `ev_callback`
+    fn create_node<Msg, F>(node: &vdom::Node<Msg>, ev_callback: F) -> Result<Self, Error>
+    where
+        Msg: 'static,
+        F: Fn(Msg) + 'static + Clone;
+
+    /// convert the vdom attribute into the attribute of this backend
+    fn convert_attr<Msg, F>(attr: &Attribute<Msg>, ev_callback: F) -> Self::Attr
+    where
+        Msg: 'static,
+        F: Fn(Msg) + 'static + Clone;
+
+    /// return the child at this index
+    fn child_at(&self, index: usize) -> Option<Self>;
+
+    /// the tag of the element, None for the other nodes
+    fn tag(&self) -> Option<String>;
+
+    /// returns true if this is a fragment node
+    fn is_fragment(&self) -> bool;
+
+    /// returns true if this is a stateful component
+    fn is_stateful_component(&self) -> bool;
+
+    /// returns true if this is an element which has enter and leave transitions
+    fn has_transition(&self) -> bool;
+
+    /// append the nodes into the children of this node
+    fn append_children(&self, for_append: Vec<Self>) -> Result<(), Error>;
+
+    /// insert the nodes before the `target_element` child of this node
+    fn insert_before(&self, target_element: &Self, for_insert: Vec<Self>) -> Result<(), Error>;
+
+    /// insert the nodes after the `target_element` child of this node
+    fn insert_after(&self, target_element: &Self, for_insert: Vec<Self>) -> Result<(), Error>;
+
+    /// replace the `target_child` of this node with the `replacement`
+    fn replace_child(&self, target_child: &Self, replacement: Self) -> Result<(), Error>;
+
+    /// remove the nodes from the children of this node
+    fn remove_children(&self, for_remove: &[&Self]) -> Result<(), Error>;
+
+    /// remove the nodes from the children of this node,
+    /// the nodes which has a transition are removed once their leave transition has ended
+    fn remove_children_with_transition(&self, for_remove: &[&Self]) -> Result<(), Error>;
+
+    /// remove all the children of this node
+    fn clear_children(&self) -> Result<(), Error>;
+
+    /// set the attributes of this node
+    fn set_attrs(&self, attrs: Vec<Self::Attr>) -> Result<(), Error>;
+
+    /// remove the attributes, including the event listeners, from this node
+    fn remove_attrs(&self, attrs: &[Self::Attr]) -> Result<(), Error>;
+
+    /// start the enter transition of this node
+    fn enter_transition(&self) -> Result<(), Error>;
+
+    /// record the state of the nodes which are affected by the patches before they are applied,
+    /// the returned closure is called after the patches are applied, ie: to animate the moved
+    /// nodes into their new position
+    fn before_patches(_dom_patches: &[DomPatch<Self>]) -> Box<dyn FnOnce()> {
+        Box::new(|| ())
+    }
+
+    /// render this node into an html string represenation
+    fn render_to_string(&self) -> String;
+}
@@ -11,15 +11,36 @@ use web_sys::{self, Node};
 
 use crate::{
     dom::{
-        component::StatelessModel, document, dom_patch, events::MountEvent, Application, DomAttr,
+        component::{Slots, StatelessModel},
+        delegation::{self, RootHandle},
+        document, dom_patch,
+        events::MountEvent,
+        flip::Flip,
+        Application, DelegatedListeners, DomAttr, DomAttrValue, DomBackend, DomPatch, Error,
         GroupedDomAttrValues, Program, StatefulComponent, StatefulModel,
     },
     html::lookup,
-    vdom::{self, Attribute, Leaf, TreePath},
+    vdom::{self, Attribute, Leaf, ListenerOptions, Portal, PortalTarget, TreePath},
 };
 
 pub(crate) type EventClosure = Closure<dyn FnMut(web_sys::Event)>;
-pub type NamedEventClosures = IndexMap<&'static str, EventClosure>;
+
+/// the callbacks which re-render the views of the stateful components
+type ComponentViews = Vec<Rc<dyn Fn()>>;
+
+thread_local! {
+    /// the views of the stateful components which are changed by the patches being applied,
+    /// None when there are no patches being applied
+    static PENDING_COMPONENT_VIEWS: RefCell<Option<ComponentViews>> = const { RefCell::new(None) };
+}
+pub type NamedEventClosures = IndexMap<&'static str, Vec<AttachedListener>>;
+
+/// An event listener closure attached to an element, together with the options
+/// it was attached with, which are needed when removing it from the element
+pub struct AttachedListener {
+    closure: EventClosure,
+    options: ListenerOptions,
+}
 
 /// A counter part of the vdom Node
 /// This is needed, so that we can
@@ -40,11 +61,16 @@ pub enum DomInner {
         // TODO: put all DomAttr here
         /// the listeners of this element, which we will drop when this element is removed
         listeners: Rc<RefCell<Option<NamedEventClosures>>>,
+        /// the listeners of this element which are dispatched from the delegation root,
+        /// they are unregistered when this element is removed
+        delegated_listeners: Rc<RefCell<Option<DelegatedListeners>>>,
         /// keeps track of the children nodes
         /// this needs to be synced with the actual element children
         children: Rc<RefCell<Vec<DomNode>>>,
         /// determine if this element needs to dispatch a mount event
         has_mount_callback: bool,
+        /// determine if this element has enter and leave transitions
+        has_transition: bool,
     },
     /// text node
     Text(web_sys::Text),
@@ -62,7 +88,35 @@ pub enum DomInner {
     StatefulComponent {
         comp: Rc<RefCell<dyn StatefulComponent>>,
         dom_node: Rc<DomNode>,
+        /// the external children which are passed to the component
+        children: Rc<RefCell<Vec<DomNode>>>,
+        /// re-render the view of the component
+        update_view: Rc<dyn Fn()>,
+        /// places the external children in the slots of the component
+        slots: Rc<RefCell<Slots>>,
     },
+    /// Portal node
+    Portal {
+        /// marks the position of the portal in the parent
+        placeholder: web_sys::Comment,
+        /// the element in the target container which holds the children of the portal
+        host: Rc<PortalHost>,
+    },
+}
+
+/// The element in the target container of a portal which holds the children of the portal.
+/// It is removed from the DOM when the portal node is dropped.
+#[derive(Debug)]
+pub struct PortalHost {
+    dom_node: DomNode,
+    /// the events are listened to in the host when the event listeners are delegated
+    _delegation_root: Option<RootHandle>,
+}
+
+impl Drop for PortalHost {
+    fn drop(&mut self) {
+        self.dom_node.as_element().remove();
+    }
 }
 
 impl fmt::Debug for DomInner {
@@ -92,6 +146,7 @@ impl fmt::Debug for DomInner {
             Self::Comment(_) => write!(f, "Comment"),
             Self::Fragment { .. } => write!(f, "Fragment"),
             Self::StatefulComponent { .. } => write!(f, "StatefulComponent"),
+            Self::Portal { host, .. } => f.debug_tuple("Portal").field(&host.dom_node).finish(),
         }
     }
 }
@@ -113,8 +168,10 @@ impl From<web_sys::Node> for DomNode {
                     inner: DomInner::Element {
                         element,
                         listeners: Rc::new(RefCell::new(None)),
+                        delegated_listeners: Rc::new(RefCell::new(None)),
                         children: Rc::new(RefCell::new(children)),
                         has_mount_callback: false,
+                        has_transition: false,
                     },
                 }
             }
@@ -154,7 +211,13 @@ impl PartialEq for DomNode {
             (DomInner::Text(v), DomInner::Text(o)) => v == o,
             (DomInner::Symbol(v), DomInner::Symbol(o)) => v == o,
             (DomInner::Comment(v), DomInner::Comment(o)) => v == o,
-            (DomInner::StatefulComponent { .. }, DomInner::StatefulComponent { .. }) => todo!(),
+            (
+                DomInner::StatefulComponent { comp: v, .. },
+                DomInner::StatefulComponent { comp: o, .. },
+            ) => Rc::ptr_eq(v, o),
+            (DomInner::Portal { placeholder: v, .. }, DomInner::Portal { placeholder: o, .. }) => {
+                v == o
+            }
             _ => false,
         }
     }
@@ -165,10 +228,82 @@ impl DomNode {
         match &self.inner {
             DomInner::Element { children, .. } => Some(children.borrow()),
             DomInner::Fragment { children, .. } => Some(children.borrow()),
+            DomInner::StatefulComponent { children, .. } => Some(children.borrow()),
+            DomInner::Portal { host, .. } => host.dom_node.children(),
             _ => None,
         }
     }
 
+    /// the tracked children of an element or fragment, or the external children of a stateful component,
+    /// which are inserted, replaced and moved next to their siblings in the DOM
+    fn sibling_children(&self) -> Result<&Rc<RefCell<Vec<DomNode>>>, Error> {
+        match &self.inner {
+            DomInner::Element { children, .. } => Ok(children),
+            DomInner::Fragment { children, .. } => Ok(children),
+            DomInner::StatefulComponent { children, .. } => Ok(children),
+            _ => Err(Error::NotAnElement(format!("{self:?}"))),
+        }
+    }
+
+    /// the index of the `target_child` in the sibling children of this node
+    fn child_index(&self, target_child: &DomNode) -> Result<usize, Error> {
+        self.sibling_children()?
+            .borrow()
+            .iter()
+            .position(|child| child == target_child)
+            .ok_or_else(|| Error::ChildNotFound(format!("{target_child:?}")))
+    }
+
+    /// the stateful component of this node
+    fn stateful_component(&self) -> Option<&Rc<RefCell<dyn StatefulComponent>>> {
+        match &self.inner {
+            DomInner::StatefulComponent { comp, .. } => Some(comp),
+            _ => None,
+        }
+    }
+
+    /// the name of the slot which this element is assigned to, ie: `h1([slot("header")], [])`
+    pub(crate) fn slot_name(&self) -> Option<String> {
+        match &self.inner {
+            DomInner::Element { element, .. } => element.get_attribute(intern("slot")),
+            _ => None,
+        }
+    }
+
+    /// re-render the view of the stateful component after it is changed by the patches,
+    /// the view is re-rendered once after all the patches are applied
+    fn update_component_view(&self) {
+        let DomInner::StatefulComponent { update_view, .. } = &self.inner else {
+            return;
+        };
+        let postponed =
+            PENDING_COMPONENT_VIEWS.with(|pending| match pending.borrow_mut().as_mut() {
+                Some(pending) => {
+                    if !pending.iter().any(|view| Rc::ptr_eq(view, update_view)) {
+                        pending.push(Rc::clone(update_view));
+                    }
+                    true
+                }
+                None => false,
+            });
+        if !postponed {
+            update_view();
+        }
+    }
+
+    /// the node which holds the children of a portal
+    fn portal_host(&self) -> Option<&DomNode> {
+        match &self.inner {
+            DomInner::Portal { host, .. } => Some(&host.dom_node),
+            _ => None,
+        }
+    }
+
+    /// returns true if this is a portal node
+    pub fn is_portal(&self) -> bool {
+        matches!(&self.inner, DomInner::Portal { .. })
+    }
+
     /// returns true if this an element node
     pub fn is_element(&self) -> bool {
         matches!(&self.inner, DomInner::Element { .. })
@@ -199,6 +334,17 @@ impl DomNode {
         matches!(&self.inner, DomInner::StatefulComponent { .. })
     }
 
+    /// returns true if this is an element which has enter and leave transitions
+    pub fn has_transition(&self) -> bool {
+        matches!(
+            &self.inner,
+            DomInner::Element {
+                has_transition: true,
+                ..
+            }
+        )
+    }
+
     pub(crate) fn tag(&self) -> Option<String> {
         match &self.inner {
             DomInner::Element { element, .. } => Some(element.tag_name().to_lowercase()),
@@ -215,6 +361,7 @@ impl DomNode {
             DomInner::Symbol(_) => unreachable!("symbol should be handled separately"),
             DomInner::Comment(comment_node) => comment_node.clone().unchecked_into(),
             DomInner::StatefulComponent { dom_node, .. } => dom_node.as_node(),
+            DomInner::Portal { placeholder, .. } => placeholder.clone().unchecked_into(),
         }
     }
 
@@ -231,6 +378,7 @@ impl DomNode {
             DomInner::Symbol(_) => unreachable!("symbol should be handled separately"),
             DomInner::Comment(comment_node) => comment_node.clone().unchecked_into(),
             DomInner::StatefulComponent { dom_node, .. } => dom_node.as_element(),
+            DomInner::Portal { placeholder, .. } => placeholder.clone().unchecked_into(),
         }
     }
 
@@ -251,21 +399,20 @@ impl DomNode {
     }
 
     /// append the DomNode `child` into this DomNode `self`
-    pub fn append_children(&self, for_append: Vec<DomNode>) {
+    pub fn append_children(&self, for_append: Vec<DomNode>) -> Result<(), Error> {
+        if let Some(host) = self.portal_host() {
+            return host.append_children(for_append);
+        }
         match &self.inner {
             DomInner::Element {
                 element, children, ..
             } => {
                 for child in for_append.into_iter() {
                     if let Some(symbol) = child.as_symbol() {
-                        element
-                            .insert_adjacent_html(intern("beforeend"), symbol)
-                            .expect("must not error");
+                        element.insert_adjacent_html(intern("beforeend"), symbol)?;
                     } else {
-                        element
-                            .append_child(&child.as_node())
-                            .expect("append child");
-                        child.dispatch_mount_event();
+                        element.append_child(&child.as_node())?;
+                        child.dispatch_mount_event()?;
                     }
                     children.borrow_mut().push(child);
                 }
@@ -274,173 +421,296 @@ impl DomNode {
                 fragment, children, ..
             } => {
                 for child in for_append.into_iter() {
-                    fragment
-                        .append_child(&child.as_node())
-                        .expect("append child");
-                    child.dispatch_mount_event();
+                    fragment.append_child(&child.as_node())?;
+                    child.dispatch_mount_event()?;
                     children.borrow_mut().push(child);
                 }
             }
-            _ => unreachable!(
-                "appending should only be called to Element and Fragment, found: {:#?}",
-                self
-            ),
+            // the component appends the children into its own child container
+            DomInner::StatefulComponent {
+                comp,
+                children,
+                slots,
+                ..
+            } => {
+                children.borrow_mut().extend(for_append.iter().cloned());
+                slots.borrow_mut().append_children(comp, for_append)?;
+                self.update_component_view();
+            }
+            _ => return Err(Error::NotAnElement(format!("{self:?}"))),
         }
+        Ok(())
     }
 
     /// Insert the DomNode `for_insert` before `self` DomNode
-    pub(crate) fn insert_before(&self, target_element: &DomNode, for_insert: Vec<DomNode>) {
-        let DomInner::Element { children, .. } = &self.inner else {
-            unreachable!("parent must be an element");
-        };
-
-        let mut target_index = None;
-        for (i, child) in children.borrow().iter().enumerate() {
-            if target_element == child {
-                target_index = Some(i);
-                break;
-            }
+    pub(crate) fn insert_before(
+        &self,
+        target_element: &DomNode,
+        for_insert: Vec<DomNode>,
+    ) -> Result<(), Error> {
+        if let Some(host) = self.portal_host() {
+            return host.insert_before(target_element, for_insert);
         }
+        let children = self.sibling_children()?;
+        let target_index = self.child_index(target_element)?;
         // NOTE: This is not reverse since inserting the last insert_node will always be next
         // before the target element
         for insert_node in for_insert.iter() {
             target_element
                 .as_element()
-                .insert_adjacent_element(intern("beforebegin"), &insert_node.as_element())
-                .expect("must insert before this element");
-            insert_node.dispatch_mount_event();
+                .insert_adjacent_element(intern("beforebegin"), &insert_node.as_element())?;
+            insert_node.dispatch_mount_event()?;
         }
 
         // NOTE: It is important that we reverse the insertion to the wrapper DomNode since it is
         // just a Vec where inserting from the last will preserve the index to insert into
         for insert_node in for_insert.into_iter().rev() {
-            if let Some(target_index) = target_index {
-                children.borrow_mut().insert(target_index, insert_node);
-            } else {
-                unreachable!("should have a self index");
-            }
+            children.borrow_mut().insert(target_index, insert_node);
         }
+        Ok(())
     }
 
     /// Insert the DomNode `for_insert` after `self` DomNode
-    pub(crate) fn insert_after(&self, target_element: &DomNode, for_insert: Vec<DomNode>) {
-        let DomInner::Element { children, .. } = &self.inner else {
-            unreachable!("parent must be an element");
-        };
-        let mut target_index = None;
-        for (i, child) in children.borrow().iter().enumerate() {
-            if target_element == child {
-                target_index = Some(i);
-                break;
-            }
+    pub(crate) fn insert_after(
+        &self,
+        target_element: &DomNode,
+        for_insert: Vec<DomNode>,
+    ) -> Result<(), Error> {
+        if let Some(host) = self.portal_host() {
+            return host.insert_after(target_element, for_insert);
         }
+        let children = self.sibling_children()?;
+        let target_index = self.child_index(target_element)?;
         for insert_node in for_insert.into_iter().rev() {
             target_element
                 .as_element()
-                .insert_adjacent_element(intern("afterend"), &insert_node.as_element())
-                .expect("must insert after this element");
-            insert_node.dispatch_mount_event();
-
-            if let Some(target_index) = target_index {
-                children.borrow_mut().insert(target_index + 1, insert_node);
-            } else {
-                unreachable!("should have a self index");
-            }
+                .insert_adjacent_element(intern("afterend"), &insert_node.as_element())?;
+            insert_node.dispatch_mount_event()?;
+            children.borrow_mut().insert(target_index + 1, insert_node);
         }
+        Ok(())
     }
 
     /// Replace the child `child` DomNode with a replacement DomNode `replacement`
-    pub(crate) fn replace_child(&self, target_child: &DomNode, replacement: DomNode) {
-        match &self.inner {
-            DomInner::Element { children, .. } => {
-                let mut child_index = None;
-                for (i, ch) in children.borrow().iter().enumerate() {
-                    if ch == target_child {
-                        child_index = Some(i);
-                        break;
-                    }
-                }
-                if let Some(child_index) = child_index {
-                    children.borrow_mut().remove(child_index);
-                    target_child
-                        .as_element()
-                        .replace_with_with_node_1(&replacement.as_node())
-                        .expect("must replace child");
-                    replacement.dispatch_mount_event();
-                    children.borrow_mut().insert(child_index, replacement);
-                } else {
-                    log::warn!(
-                        "unable to find target_child: {target_child:#?} with a replacement: {:?}",
-                        replacement
-                    );
-                    // if can not find the child, then must be the root node
-                    unreachable!("must find the child...");
-                }
+    pub(crate) fn replace_child(
+        &self,
+        target_child: &DomNode,
+        replacement: DomNode,
+    ) -> Result<(), Error> {
+        if let Some(host) = self.portal_host() {
+            return host.replace_child(target_child, replacement);
+        }
+        let children = self.sibling_children()?;
+        let child_index = self.child_index(target_child)?;
+        target_child
+            .as_element()
+            .replace_with_with_node_1(&replacement.as_node())?;
+        replacement.dispatch_mount_event()?;
+        children.borrow_mut()[child_index] = replacement;
+        Ok(())
+    }
+
+    /// Replace the fragment `target_fragment`, whose children has been appended into `self`,
+    /// with the `replacement` node, which takes the place of the children of the fragment.
+    /// The tracked fragment is replaced too, if `self` keeps track of it.
+    pub(crate) fn replace_fragment(
+        &self,
+        target_fragment: &DomNode,
+        replacement: DomNode,
+    ) -> Result<(), Error> {
+        let fragment_children: Vec<Node> = target_fragment
+            .children()
+            .map(|children| {
+                children
+                    .iter()
+                    .filter(|child| !child.is_symbol())
+                    .map(DomNode::as_node)
+                    .collect()
+            })
+            .unwrap_or_default();
+        let first_child = fragment_children
+            .first()
+            .and_then(|first| Some((first.parent_node()?, first)));
+        match first_child {
+            Some((parent, first)) => parent.insert_before(&replacement.as_node(), Some(first))?,
+            None => self.as_node().append_child(&replacement.as_node())?,
+        };
+        for child in fragment_children.iter() {
+            remove_from_parent(child)?;
+        }
+        replacement.dispatch_mount_event()?;
+        if let Ok(children) = self.sibling_children() {
+            let mut children = children.borrow_mut();
+            if let Some(child_index) = children.iter().position(|child| child == target_fragment) {
+                children[child_index] = replacement;
             }
-            _ => todo!(),
         }
+        Ok(())
     }
 
     /// Remove the DomNode `child` from the children of `self`
-    pub(crate) fn remove_children(&self, for_remove: &[&DomNode]) {
-        match &self.inner {
-            DomInner::Element {
-                element, children, ..
-            } => {
-                let mut child_indexes = vec![];
-                for (i, ch) in children.borrow().iter().enumerate() {
-                    for remove_node in for_remove.iter() {
-                        if ch == *remove_node {
-                            child_indexes.push(i);
-                            break;
-                        }
-                    }
-                }
-                assert_eq!(child_indexes.len(), for_remove.len(), "must find all");
-
-                // NOTE: It is important to remove from the last, since
-                // vec shifts to the left, while removing from the last
-                // with the rev child index, we remove the correct child_index
-                for child_index in child_indexes.into_iter().rev() {
-                    let child = children.borrow_mut().remove(child_index);
-                    element
-                        .remove_child(&child.as_node())
-                        .expect("remove child");
-                }
+    pub(crate) fn remove_children(&self, for_remove: &[&DomNode]) -> Result<(), Error> {
+        if let Some(host) = self.portal_host() {
+            return host.remove_children(for_remove);
+        }
+        if let Some(comp) = self.stateful_component() {
+            return self.remove_component_children(comp, for_remove);
+        }
+        for child in self.detach_children(for_remove)? {
+            remove_from_parent(&child.as_node())?;
+        }
+        Ok(())
+    }
+
+    /// Remove the DomNode `child` from the children of `self`,
+    /// children that has a transition is only removed from the DOM after their leave transition
+    /// has ended.
+    pub(crate) fn remove_children_with_transition(
+        &self,
+        for_remove: &[&DomNode],
+    ) -> Result<(), Error> {
+        if let Some(host) = self.portal_host() {
+            return host.remove_children_with_transition(for_remove);
+        }
+        if let Some(comp) = self.stateful_component() {
+            return self.remove_component_children(comp, for_remove);
+        }
+        for child in self.detach_children(for_remove)? {
+            if child.has_transition() {
+                child.leave_transition()?;
+            } else {
+                remove_from_parent(&child.as_node())?;
             }
-            _ => todo!(),
         }
+        Ok(())
+    }
+
+    /// Remove the DomNode `child` from the tracked children of `self`
+    /// without removing them from the actual DOM.
+    ///
+    /// Once detached, the node will no longer be counted when traversing this element
+    /// using a TreePath.
+    fn detach_children(&self, for_remove: &[&DomNode]) -> Result<Vec<DomNode>, Error> {
+        let children = self.sibling_children()?;
+        let mut child_indexes = for_remove
+            .iter()
+            .map(|remove_node| self.child_index(remove_node))
+            .collect::<Result<Vec<_>, Error>>()?;
+        child_indexes.sort_unstable();
+
+        // NOTE: It is important to remove from the last, since
+        // vec shifts to the left, while removing from the last
+        // with the rev child index, we remove the correct child_index
+        let mut detached: Vec<DomNode> = child_indexes
+            .into_iter()
+            .rev()
+            .map(|child_index| children.borrow_mut().remove(child_index))
+            .collect();
+        detached.reverse();
+        Ok(detached)
+    }
+
+    /// remove the external children of the stateful component,
+    /// the component removes the children which are not in its slots
+    fn remove_component_children(
+        &self,
+        comp: &Rc<RefCell<dyn StatefulComponent>>,
+        for_remove: &[&DomNode],
+    ) -> Result<(), Error> {
+        let DomInner::StatefulComponent {
+            children, slots, ..
+        } = &self.inner
+        else {
+            unreachable!("expecting a stateful component");
+        };
+        let mut child_indexes: Vec<usize> = children
+            .borrow()
+            .iter()
+            .enumerate()
+            .filter(|(_, ch)| for_remove.contains(ch))
+            .map(|(i, _)| i)
+            .collect();
+        if child_indexes.len() != for_remove.len() {
+            return Err(Error::ChildNotFound(format!("{for_remove:?}")));
+        }
+        // remove from the last, so the index of the remaining children are not shifted
+        child_indexes.reverse();
+        for child_index in child_indexes {
+            let child = children.borrow_mut().remove(child_index);
+            let passed_index = slots.borrow_mut().remove_child(&child)?;
+            if let Some(passed_index) = passed_index {
+                comp.borrow_mut().remove_child(passed_index);
+            }
+        }
+        self.update_component_view();
+        Ok(())
     }
 
     /// remove all the children of this element
-    pub(crate) fn clear_children(&self) {
+    pub(crate) fn clear_children(&self) -> Result<(), Error> {
+        if let Some(host) = self.portal_host() {
+            return host.clear_children();
+        }
+        if let Some(comp) = self.stateful_component() {
+            let children = self.children().map(|c| c.clone()).unwrap_or_default();
+            return self.remove_component_children(comp, &children.iter().collect::<Vec<_>>());
+        }
         match &self.inner {
             DomInner::Element {
                 element, children, ..
             } => {
-                children.borrow_mut().clear();
+                let leaving: Vec<DomNode> = children
+                    .borrow_mut()
+                    .drain(..)
+                    .filter(|child| child.has_transition())
+                    .collect();
                 // NOTE: It is faster to remove from the last
                 // This is removing the children of the actual node
                 // regardless if it is mapped with the DomNode wrapper
-                while let Some(last_child) = element.last_child() {
-                    element
-                        .remove_child(&last_child)
-                        .expect("must remove child");
+                if leaving.is_empty() {
+                    while let Some(last_child) = element.last_child() {
+                        element.remove_child(&last_child)?;
+                    }
+                } else {
+                    // the children with transition stays until their leave transition has ended
+                    let leaving_nodes: Vec<web_sys::Node> =
+                        leaving.iter().map(|child| child.as_node()).collect();
+                    let mut next_child = element.last_child();
+                    while let Some(child) = next_child {
+                        next_child = child.previous_sibling();
+                        if !leaving_nodes.contains(&child) {
+                            element.remove_child(&child)?;
+                        }
+                    }
+                    for child in leaving.iter() {
+                        child.leave_transition()?;
+                    }
+                }
+            }
+            // the children of the fragment may have been moved into the mount node
+            DomInner::Fragment { children, .. } => {
+                for child in children.borrow_mut().drain(..) {
+                    if child.has_transition() {
+                        child.leave_transition()?;
+                    } else {
+                        remove_from_parent(&child.as_node())?;
+                    }
                 }
             }
-            _ => todo!(),
+            _ => return Err(Error::NotAnElement(format!("{self:?}"))),
         }
+        Ok(())
     }
 
-    pub(crate) fn replace_node(&self, replacement: DomNode) {
+    pub(crate) fn replace_node(&self, replacement: DomNode) -> Result<(), Error> {
         //NOTE: This must be replacing a mount node
         self.as_element()
-            .replace_with_with_node_1(&replacement.as_node())
-            .expect("must replace child");
+            .replace_with_with_node_1(&replacement.as_node())?;
+        Ok(())
     }
 
     /// set the attributes of the dom element
-    pub fn set_dom_attrs(&self, attrs: impl IntoIterator<Item = DomAttr>) -> Result<(), JsValue> {
+    pub fn set_dom_attrs(&self, attrs: impl IntoIterator<Item = DomAttr>) -> Result<(), Error> {
         for attr in attrs.into_iter() {
             self.set_dom_attr(attr)?;
         }
@@ -448,33 +718,45 @@ impl DomNode {
     }
 
     /// set the attribute of the dom element
-    pub fn set_dom_attr(&self, attr: DomAttr) -> Result<(), JsValue> {
+    pub fn set_dom_attr(&self, attr: DomAttr) -> Result<(), Error> {
         match &self.inner {
             DomInner::Element {
-                element, listeners, ..
+                element,
+                listeners,
+                delegated_listeners,
+                ..
             } => {
                 let attr_name = intern(attr.name);
                 let attr_namespace = attr.namespace;
 
                 let GroupedDomAttrValues {
                     listeners: event_callbacks,
+                    delegated_listeners: delegated_callbacks,
                     plain_values,
                     styles,
                 } = attr.group_values();
 
-                Self::add_event_dom_listeners(element, attr_name, &event_callbacks)
-                    .expect("event listeners");
-                let is_none = listeners.borrow().is_none();
-                if is_none {
-                    let listener_closures: IndexMap<
-                        &'static str,
-                        Closure<dyn FnMut(web_sys::Event)>,
-                    > = IndexMap::from_iter(event_callbacks.into_iter().map(|c| (attr_name, c)));
-                    *listeners.borrow_mut() = Some(listener_closures);
-                } else if let Some(listeners) = listeners.borrow_mut().as_mut() {
-                    for event_cb in event_callbacks.into_iter() {
-                        listeners.insert(attr_name, event_cb);
-                    }
+                if !event_callbacks.is_empty() || !delegated_callbacks.is_empty() {
+                    // the previous listeners of this event are replaced,
+                    // ie: when the listener options has changed
+                    self.remove_event_listeners(attr_name)?;
+                    self.remove_delegated_listeners(attr_name)?;
+                }
+                if !event_callbacks.is_empty() {
+                    let attached =
+                        Self::add_event_dom_listeners(element, attr_name, event_callbacks)?;
+                    listeners
+                        .borrow_mut()
+                        .get_or_insert_with(IndexMap::new)
+                        .insert(attr_name, attached);
+                }
+                if !delegated_callbacks.is_empty() {
+                    let mut delegated_listeners = delegated_listeners.borrow_mut();
+                    let registered = match delegated_listeners.as_mut() {
+                        Some(registered) => registered,
+                        None => delegated_listeners.insert(DelegatedListeners::register(element)?),
+                    };
+                    registered.set(attr_name, delegated_callbacks);
                 }
 
                 DomAttr::set_element_style(element, attr_name, styles);
@@ -488,32 +770,71 @@ impl DomNode {
             DomInner::StatefulComponent { comp, .. } => {
                 log::info!("applying attribute change for stateful component...{attr:?}");
                 comp.borrow_mut().attribute_changed(attr);
+                self.update_component_view();
             }
-            _ => {
-                log::info!("set the dom attr for {self:?}, with dom_attr: {attr:?}");
-                unreachable!("should only be called for element");
-            }
+            _ => return Err(Error::NotAnElement(format!("{self:?}"))),
         }
         Ok(())
     }
 
-    pub(crate) fn remove_dom_attr(&self, attr: &DomAttr) -> Result<(), JsValue> {
+    /// remove the delegated listeners of the event from this element
+    pub(crate) fn remove_delegated_listeners(&self, event_name: &str) -> Result<(), Error> {
+        let DomInner::Element {
+            delegated_listeners,
+            ..
+        } = &self.inner
+        else {
+            return Err(Error::NotAnElement(format!("{self:?}")));
+        };
+        if let Some(delegated_listeners) = delegated_listeners.borrow().as_ref() {
+            delegated_listeners.remove(event_name);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn remove_dom_attr(&self, attr: &DomAttr) -> Result<(), Error> {
+        if let Some(comp) = self.stateful_component() {
+            comp.borrow_mut().remove_attribute(attr.name);
+            self.update_component_view();
+            return Ok(());
+        }
         let DomInner::Element { element, .. } = &self.inner else {
-            unreachable!("expecting an element");
+            return Err(Error::NotAnElement(format!("{self:?}")));
+        };
+        Ok(DomAttr::remove_element_dom_attr(element, attr)?)
+    }
+
+    /// remove the event listeners of the event from this element
+    pub(crate) fn remove_event_listeners(&self, event_name: &str) -> Result<(), Error> {
+        let DomInner::Element {
+            element, listeners, ..
+        } = &self.inner
+        else {
+            return Err(Error::NotAnElement(format!("{self:?}")));
         };
-        DomAttr::remove_element_dom_attr(element, attr)
+        let removed = listeners
+            .borrow_mut()
+            .as_mut()
+            .and_then(|listeners| listeners.shift_remove(event_name));
+        if let Some(removed) = removed {
+            Self::remove_event_dom_listeners(element, event_name, &removed)?;
+        }
+        Ok(())
     }
 
     /// attach and event listener to an event target
     pub(crate) fn add_event_dom_listeners(
         target: &web_sys::EventTarget,
         attr_name: &'static str,
-        event_listeners: &[EventClosure],
-    ) -> Result<(), JsValue> {
-        for event_cb in event_listeners.iter() {
-            Self::add_event_listener(target, attr_name, event_cb)?;
-        }
-        Ok(())
+        event_listeners: Vec<(EventClosure, ListenerOptions)>,
+    ) -> Result<Vec<AttachedListener>, JsValue> {
+        event_listeners
+            .into_iter()
+            .map(|(closure, options)| {
+                Self::add_event_listener(target, attr_name, &closure, options)?;
+                Ok(AttachedListener { closure, options })
+            })
+            .collect()
     }
 
     /// add a event listener to a target element
@@ -521,11 +842,41 @@ impl DomNode {
         event_target: &web_sys::EventTarget,
         event_name: &str,
         listener: &EventClosure,
+        options: ListenerOptions,
     ) -> Result<(), JsValue> {
-        event_target.add_event_listener_with_callback(
-            intern(event_name),
-            listener.as_ref().unchecked_ref(),
-        )?;
+        if options.is_default() {
+            event_target.add_event_listener_with_callback(
+                intern(event_name),
+                listener.as_ref().unchecked_ref(),
+            )?;
+        } else {
+            let add_options = web_sys::AddEventListenerOptions::new();
+            add_options.set_capture(options.capture);
+            add_options.set_passive(options.passive);
+            add_options.set_once(options.once);
+            event_target.add_event_listener_with_callback_and_add_event_listener_options(
+                intern(event_name),
+                listener.as_ref().unchecked_ref(),
+                &add_options,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// detach the event listeners from the event target
+    fn remove_event_dom_listeners(
+        event_target: &web_sys::EventTarget,
+        event_name: &str,
+        attached: &[AttachedListener],
+    ) -> Result<(), JsValue> {
+        for listener in attached {
+            // only the capture flag is used to match the listener to be removed
+            event_target.remove_event_listener_with_callback_and_bool(
+                intern(event_name),
+                listener.closure.as_ref().unchecked_ref(),
+                listener.options.capture,
+            )?;
+        }
         Ok(())
     }
 
@@ -541,13 +892,12 @@ impl DomNode {
         }
     }
 
-    fn dispatch_mount_event(&self) {
+    fn dispatch_mount_event(&self) -> Result<(), Error> {
         if self.should_dispatch_mount_event() {
             let event_target: web_sys::EventTarget = self.as_element().unchecked_into();
-            event_target
-                .dispatch_event(&MountEvent::create_web_event())
-                .expect("must be ok");
+            event_target.dispatch_event(&MountEvent::create_web_event())?;
         }
+        Ok(())
     }
 
     #[allow(unused)]
@@ -620,14 +970,156 @@ impl DomNode {
                 }
                 Ok(())
             }
-            DomInner::StatefulComponent { comp: _, dom_node } => {
+            DomInner::StatefulComponent { dom_node, .. } => {
                 dom_node.render(buffer)?;
                 Ok(())
             }
+            DomInner::Portal { placeholder, .. } => {
+                write!(buffer, "<!--{}-->", placeholder.data())
+            }
         }
     }
 }
 
+/// Postpones the re-rendering of the views of the stateful components which are changed
+/// by the patches, until it is dropped after the patches are applied or have failed
+struct ComponentViewBatch {
+    previous: Option<ComponentViews>,
+}
+
+impl ComponentViewBatch {
+    fn new() -> Self {
+        let previous = PENDING_COMPONENT_VIEWS.with(|pending| pending.replace(Some(vec![])));
+        Self { previous }
+    }
+}
+
+impl Drop for ComponentViewBatch {
+    fn drop(&mut self) {
+        let pending = PENDING_COMPONENT_VIEWS.with(|pending| pending.replace(self.previous.take()));
+        for update_view in pending.into_iter().flatten() {
+            update_view();
+        }
+    }
+}
+
+impl DomBackend for DomNode {
+    type Attr = DomAttr;
+
+    fn create_node<Msg, F>(node: &vdom::Node<Msg>, ev_callback: F) -> Result<Self, Error>
+    where
+        Msg: 'static,
+        F: Fn(Msg) + 'static + Clone,
+    {
+        create_dom_node(node, ev_callback)
+    }
+
+    fn convert_attr<Msg, F>(attr: &Attribute<Msg>, ev_callback: F) -> DomAttr
+    where
+        Msg: 'static,
+        F: Fn(Msg) + 'static + Clone,
+    {
+        dom_patch::convert_attr(attr, ev_callback)
+    }
+
+    fn child_at(&self, index: usize) -> Option<Self> {
+        self.children()?.get(index).cloned()
+    }
+
+    fn tag(&self) -> Option<String> {
+        DomNode::tag(self)
+    }
+
+    fn is_fragment(&self) -> bool {
+        DomNode::is_fragment(self)
+    }
+
+    fn is_stateful_component(&self) -> bool {
+        DomNode::is_stateful_component(self)
+    }
+
+    fn has_transition(&self) -> bool {
+        DomNode::has_transition(self)
+    }
+
+    fn append_children(&self, for_append: Vec<Self>) -> Result<(), Error> {
+        DomNode::append_children(self, for_append)
+    }
+
+    fn insert_before(&self, target_element: &Self, for_insert: Vec<Self>) -> Result<(), Error> {
+        DomNode::insert_before(self, target_element, for_insert)
+    }
+
+    fn insert_after(&self, target_element: &Self, for_insert: Vec<Self>) -> Result<(), Error> {
+        DomNode::insert_after(self, target_element, for_insert)
+    }
+
+    fn replace_child(&self, target_child: &Self, replacement: Self) -> Result<(), Error> {
+        DomNode::replace_child(self, target_child, replacement)
+    }
+
+    fn remove_children(&self, for_remove: &[&Self]) -> Result<(), Error> {
+        DomNode::remove_children(self, for_remove)
+    }
+
+    fn remove_children_with_transition(&self, for_remove: &[&Self]) -> Result<(), Error> {
+        DomNode::remove_children_with_transition(self, for_remove)
+    }
+
+    fn clear_children(&self) -> Result<(), Error> {
+        DomNode::clear_children(self)
+    }
+
+    fn set_attrs(&self, attrs: Vec<DomAttr>) -> Result<(), Error> {
+        self.set_dom_attrs(attrs)
+    }
+
+    fn remove_attrs(&self, attrs: &[DomAttr]) -> Result<(), Error> {
+        // the component is notified of the removed attributes, regardless of their values
+        if self.is_stateful_component() {
+            for attr in attrs.iter() {
+                self.remove_dom_attr(attr)?;
+            }
+            return Ok(());
+        }
+        for attr in attrs.iter() {
+            for att_value in attr.value.iter() {
+                match att_value {
+                    DomAttrValue::Simple(_) | DomAttrValue::Style(_) => {
+                        self.remove_dom_attr(attr)?;
+                    }
+                    // it is an event listener
+                    DomAttrValue::EventListener(..) => {
+                        self.remove_event_listeners(attr.name)?;
+                    }
+                    DomAttrValue::DelegatedListener(_) => {
+                        self.remove_delegated_listeners(attr.name)?;
+                    }
+                    DomAttrValue::Empty => (),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn enter_transition(&self) -> Result<(), Error> {
+        Ok(DomNode::enter_transition(self)?)
+    }
+
+    fn before_patches(dom_patches: &[DomPatch]) -> Box<dyn FnOnce()> {
+        let component_views = ComponentViewBatch::new();
+        let flip = Flip::first(dom_patches);
+        Box::new(move || {
+            drop(component_views);
+            flip.play()
+        })
+    }
+
+    fn render_to_string(&self) -> String {
+        DomNode::render_to_string(self)
+    }
+}
+
 #[cfg(feature = "with-interning")]
 #[inline(always)]
 pub fn intern(s: &str) -> &str {
@@ -645,7 +1137,8 @@ where
     APP: Application + 'static,
 {
     /// Create a dom node
-    pub fn create_dom_node(&self, node: &vdom::Node<APP::MSG>) -> DomNode {
+    pub fn create_dom_node(&self, node: &vdom::Node<APP::MSG>) -> Result<DomNode, Error> {
+        let _scope = self.delegation_scope();
         let ev_callback = self.create_ev_callback();
         create_dom_node(node, ev_callback)
     }
@@ -661,7 +1154,7 @@ where
 }
 
 /// Create a dom node
-pub fn create_dom_node<Msg, F>(node: &vdom::Node<Msg>, ev_callback: F) -> DomNode
+pub fn create_dom_node<Msg, F>(node: &vdom::Node<Msg>, ev_callback: F) -> Result<DomNode, Error>
 where
     Msg: 'static,
     F: Fn(Msg) + 'static + Clone,
@@ -672,20 +1165,16 @@ where
     }
 }
 
-fn create_element_node<Msg, F>(elm: &vdom::Element<Msg>, ev_callback: F) -> DomNode
+fn create_element_node<Msg, F>(elm: &vdom::Element<Msg>, ev_callback: F) -> Result<DomNode, Error>
 where
     Msg: 'static,
     F: Fn(Msg) + 'static + Clone,
 {
     let document = document();
     let element = if let Some(namespace) = elm.namespace() {
-        document
-            .create_element_ns(Some(intern(namespace)), intern(elm.tag()))
-            .expect("Unable to create element")
+        document.create_element_ns(Some(intern(namespace)), intern(elm.tag()))?
     } else {
-        document
-            .create_element(intern(elm.tag()))
-            .expect("create element")
+        document.create_element(intern(elm.tag()))?
     };
     // TODO: dispatch the mount event recursively after the dom node is mounted into
     // the root node
@@ -695,38 +1184,40 @@ where
         inner: DomInner::Element {
             element,
             listeners: Rc::new(RefCell::new(None)),
+            delegated_listeners: Rc::new(RefCell::new(None)),
             children: Rc::new(RefCell::new(vec![])),
             has_mount_callback: elm.has_mount_callback(),
+            has_transition: elm.has_transition(),
         },
     };
     let dom_attrs = attrs
         .iter()
         .map(|a| dom_patch::convert_attr(a, ev_callback.clone()));
-    dom_node.set_dom_attrs(dom_attrs).expect("set dom attrs");
-    let children: Vec<DomNode> = elm
+    dom_node.set_dom_attrs(dom_attrs)?;
+    let children = elm
         .children()
         .iter()
         .map(|child| create_dom_node(child, ev_callback.clone()))
-        .collect();
-    dom_node.append_children(children);
-    dom_node
+        .collect::<Result<Vec<_>, Error>>()?;
+    dom_node.append_children(children)?;
+    Ok(dom_node)
 }
 
-fn create_leaf_node<Msg, F>(leaf: &vdom::Leaf<Msg>, ev_callback: F) -> DomNode
+fn create_leaf_node<Msg, F>(leaf: &vdom::Leaf<Msg>, ev_callback: F) -> Result<DomNode, Error>
 where
     Msg: 'static,
     F: Fn(Msg) + 'static + Clone,
 {
     match leaf {
-        Leaf::Text(txt) => DomNode {
+        Leaf::Text(txt) => Ok(DomNode {
             inner: DomInner::Text(document().create_text_node(txt)),
-        },
-        Leaf::Symbol(symbol) => DomNode {
+        }),
+        Leaf::Symbol(symbol) => Ok(DomNode {
             inner: DomInner::Symbol(symbol.clone()),
-        },
-        Leaf::Comment(comment) => DomNode {
+        }),
+        Leaf::Comment(comment) => Ok(DomNode {
             inner: DomInner::Comment(document().create_comment(comment)),
-        },
+        }),
         Leaf::Fragment(nodes) => create_fragment_node(nodes, ev_callback),
 
         // NodeList that goes here is only possible when it is the root_node,
@@ -734,28 +1225,76 @@ where
         // We need to wrap this node_list into doc_fragment since root_node is only 1 element
         Leaf::NodeList(nodes) => create_fragment_node(nodes, ev_callback),
 
-        Leaf::StatefulComponent(comp) => {
-            //TODO: also put the children and attributes here
-            DomNode {
-                inner: DomInner::StatefulComponent {
-                    comp: Rc::clone(&comp.comp),
-                    dom_node: Rc::new(create_stateful_component(comp, ev_callback)),
-                },
-            }
-        }
+        Leaf::StatefulComponent(comp) => create_stateful_component(comp, ev_callback),
         Leaf::StatelessComponent(comp) => create_stateless_component(comp, ev_callback),
 
         Leaf::TemplatedView(view) => {
             unreachable!("template view should not be created: {:#?}", view)
         }
         Leaf::DocType(_) => unreachable!("doc type is never converted"),
+        Leaf::Portal(portal) => create_portal_node(portal, ev_callback),
     }
 }
 
+/// create the host element of the portal in the target container,
+/// the portal node itself is just a placeholder comment in the parent
+fn create_portal_node<Msg, F>(portal: &Portal<Msg>, ev_callback: F) -> Result<DomNode, Error>
+where
+    Msg: 'static,
+    F: Fn(Msg) + 'static + Clone,
+{
+    let document = document();
+    let container = match &portal.target {
+        PortalTarget::Selector(selector) => document.query_selector(selector).ok().flatten(),
+        PortalTarget::Element(element) => Some(element.clone()),
+    };
+    let container = match container {
+        Some(container) => container,
+        None => {
+            log::warn!(
+                "unable to find the portal target: {:?}, using the body instead",
+                portal.target
+            );
+            document.body().ok_or(Error::NoBody)?.unchecked_into()
+        }
+    };
+    let host_element = document.create_element("div")?;
+    // the host element doesn't affect the layout of the container
+    host_element.set_attribute("style", "display: contents")?;
+    container.append_child(&host_element)?;
+    // the target container may not be inside the mount node of the program
+    let delegation_root = delegation::is_delegating().then(|| delegation::add_root(&host_element));
+    let host = DomNode {
+        inner: DomInner::Element {
+            element: host_element,
+            listeners: Rc::new(RefCell::new(None)),
+            delegated_listeners: Rc::new(RefCell::new(None)),
+            children: Rc::new(RefCell::new(vec![])),
+            has_mount_callback: false,
+            has_transition: false,
+        },
+    };
+    let children = portal
+        .children
+        .iter()
+        .map(|child| create_dom_node(child, ev_callback.clone()))
+        .collect::<Result<Vec<_>, Error>>()?;
+    host.append_children(children)?;
+    Ok(DomNode {
+        inner: DomInner::Portal {
+            placeholder: document.create_comment("portal"),
+            host: Rc::new(PortalHost {
+                dom_node: host,
+                _delegation_root: delegation_root,
+            }),
+        },
+    })
+}
+
 fn create_fragment_node<'a, Msg, F>(
     nodes: impl IntoIterator<Item = &'a vdom::Node<Msg>>,
     ev_callback: F,
-) -> DomNode
+) -> Result<DomNode, Error>
 where
     Msg: 'static,
     F: Fn(Msg) + 'static + Clone,
@@ -770,74 +1309,30 @@ where
     let children = nodes
         .into_iter()
         .map(|node| create_dom_node(node, ev_callback.clone()))
-        .collect();
-    dom_node.append_children(children);
-    dom_node
-}
-
-/// render the underlying real dom node into string
-pub fn render_real_dom_to_string(node: &web_sys::Node) -> String {
-    let mut f = String::new();
-    render_real_dom(node, &mut f).expect("must not error");
-    f
+        .collect::<Result<Vec<_>, Error>>()?;
+    dom_node.append_children(children)?;
+    Ok(dom_node)
 }
 
-/// render the underlying real dom structure
-pub fn render_real_dom(node: &web_sys::Node, buffer: &mut dyn fmt::Write) -> fmt::Result {
-    match node.node_type() {
-        Node::TEXT_NODE => {
-            let text_node: &web_sys::Text = node.unchecked_ref();
-            let text = text_node.whole_text().expect("whole text");
-            write!(buffer, "{text}")?;
-            Ok(())
-        }
-        Node::COMMENT_NODE => {
-            let comment: &web_sys::Comment = node.unchecked_ref();
-            write!(buffer, "<!--{}-->", comment.data())
-        }
-        Node::ELEMENT_NODE => {
-            let element: &web_sys::Element = node.unchecked_ref();
-            let tag = element.tag_name().to_lowercase();
-            let is_self_closing = lookup::is_self_closing(&tag);
-
-            write!(buffer, "<{tag}")?;
-            let attrs = element.attributes();
-            let attrs_len = attrs.length();
-            for i in 0..attrs_len {
-                let attr = attrs.item(i).expect("attr");
-                write!(buffer, " {}=\"{}\"", attr.local_name(), attr.value())?;
-            }
-            if is_self_closing {
-                write!(buffer, "/>")?;
-            } else {
-                write!(buffer, ">")?;
-            }
-
-            let child_nodes = element.child_nodes();
-            let child_count = child_nodes.length();
-            for i in 0..child_count {
-                let child = child_nodes.get(i).unwrap();
-                render_real_dom(&child, buffer)?;
-            }
-            if !is_self_closing {
-                write!(buffer, "</{tag}>")?;
-            }
-            Ok(())
-        }
-        _ => todo!("for other else"),
+/// remove the node from its parent in the DOM, which is not the fragment it was created in
+/// when the fragment has been appended into the mount node
+fn remove_from_parent(node: &web_sys::Node) -> Result<(), JsValue> {
+    if let Some(parent) = node.parent_node() {
+        parent.remove_child(node)?;
     }
+    Ok(())
 }
 
 #[allow(unused)]
 pub(crate) fn create_stateless_component<Msg, F>(
     comp: &StatelessModel<Msg>,
     ev_callback: F,
-) -> DomNode
+) -> Result<DomNode, Error>
 where
     Msg: 'static,
     F: Fn(Msg) + 'static + Clone,
 {
-    let comp_view = &comp.view;
+    let comp_view = comp.view();
     let real_comp_view = comp_view.unwrap_template_ref();
     create_dom_node(real_comp_view, ev_callback)
 }
@@ -854,7 +1349,10 @@ where
 /// The attributes affects the Stateful component state.
 /// The attributes can be diff and send the patches to the StatefulComponent
 ///  - Changes to the attributes will call on attribute_changed of the StatefulComponent
-fn create_stateful_component<Msg, F>(comp: &StatefulModel<Msg>, ev_callback: F) -> DomNode
+fn create_stateful_component<Msg, F>(
+    comp: &StatefulModel<Msg>,
+    ev_callback: F,
+) -> Result<DomNode, Error>
 where
     Msg: 'static,
     F: Fn(Msg) + 'static + Clone,
@@ -867,7 +1365,7 @@ where
             [],
         ),
         ev_callback.clone(),
-    );
+    )?;
 
     let dom_attrs: Vec<DomAttr> = comp
         .attrs
@@ -887,7 +1385,17 @@ where
         .children
         .iter()
         .map(|child| create_dom_node(child, ev_callback.clone()))
-        .collect();
-    comp.comp.borrow_mut().append_children(created_children);
-    comp_node
+        .collect::<Result<Vec<_>, Error>>()?;
+    comp.slots
+        .borrow_mut()
+        .append_children(&comp.comp, created_children.clone())?;
+    Ok(DomNode {
+        inner: DomInner::StatefulComponent {
+            comp: Rc::clone(&comp.comp),
+            dom_node: Rc::new(comp_node),
+            children: Rc::new(RefCell::new(created_children)),
+            update_view: Rc::clone(&comp.update_view),
+            slots: Rc::clone(&comp.slots),
+        },
+    })
 }
@@ -2,11 +2,11 @@ use std::{cell::RefCell, rc::Rc};
 
 use indexmap::IndexMap;
 use wasm_bindgen::closure::Closure;
-use wasm_bindgen::JsValue;
 
 use crate::{
     dom::{
-        self, dom_node, dom_node::DomInner, Application, DomAttr, DomAttrValue, DomNode, Program,
+        self, delegation, Application, DelegatedListener, DomAttr, DomAttrValue, DomBackend,
+        DomNode, Error, Program,
     },
     vdom::{
         Attribute, AttributeValue, ComponentEventCallback, EventCallback, Patch, PatchType,
@@ -19,49 +19,49 @@ use crate::{
 /// as opposed to Patch which contains reference to the vdom, which makes it hard
 /// to be included in a struct
 #[derive(Debug)]
-pub struct DomPatch {
+pub struct DomPatch<N: DomBackend = DomNode> {
     /// The path to traverse to get to the target_element
     pub patch_path: TreePath,
     /// the target node
-    pub target_element: DomNode,
+    pub target_element: N,
     /// the parent element of the target node
-    pub target_parent: DomNode,
+    pub target_parent: N,
     /// the patch variant
-    pub patch_variant: PatchVariant,
+    pub patch_variant: PatchVariant<N>,
 }
 
 /// patch variant
 #[derive(Debug)]
-pub enum PatchVariant {
+pub enum PatchVariant<N: DomBackend = DomNode> {
     /// Insert nodes before the target node
     InsertBeforeNode {
         /// nodes to be inserted before the target node
-        nodes: Vec<DomNode>,
+        nodes: Vec<N>,
     },
     /// Insert nodes after the target node
     InsertAfterNode {
         /// the nodes to be inserted after the target node
-        nodes: Vec<DomNode>,
+        nodes: Vec<N>,
     },
     /// Append nodes into the target node
     AppendChildren {
         /// the children nodes to be appended into the target node
-        children: Vec<DomNode>,
+        children: Vec<N>,
     },
     /// Add attributes to the target node
     AddAttributes {
         /// the attributes to be added to the target node
-        attrs: Vec<DomAttr>,
+        attrs: Vec<N::Attr>,
     },
     /// Remove attributes from the target node
     RemoveAttributes {
         /// the attributes names to be removed
-        attrs: Vec<DomAttr>,
+        attrs: Vec<N::Attr>,
     },
     /// Replace the target node with the replacement node
     ReplaceNode {
         /// the replacement node
-        replacement: Vec<DomNode>,
+        replacement: Vec<N>,
     },
     /// Remove the target node
     RemoveNode,
@@ -70,77 +70,54 @@ pub enum PatchVariant {
     /// Move the target node before the node specified in the path location
     MoveBeforeNode {
         /// before the node at this location
-        for_moving: Vec<DomNode>,
+        for_moving: Vec<N>,
     },
     /// Move the target node after the node specified in the path location
     MoveAfterNode {
         /// after the node at this location
-        for_moving: Vec<DomNode>,
+        for_moving: Vec<N>,
     },
 }
 
-impl DomNode {
-    pub(crate) fn find_node(&self, path: &mut TreePath) -> Option<DomNode> {
-        match &self.inner {
-            DomInner::StatefulComponent { .. } => {
-                log::info!(
-                    "This is a stateful component, should return the element
-                inside relative to the child container at this path: {:?}",
-                    path
-                );
-                // just return self and handle its own patches
-                Some(self.clone())
-            }
-            _ => {
-                if path.is_empty() {
-                    Some(self.clone())
-                } else {
-                    let idx = path.remove_first();
-                    if let Some(children) = self.children() {
-                        if let Some(child) = children.get(idx) {
-                            child.find_node(path)
-                        } else {
-                            log::warn!("There is no child at index: {idx}");
-                            None
-                        }
-                    } else {
-                        log::warn!("Traversing to a childless node..");
-                        None
-                    }
-                }
-            }
+/// traverse the children of the node using the path
+fn find_node<N: DomBackend>(node: &N, path: &mut TreePath) -> Option<N> {
+    if path.is_empty() {
+        Some(node.clone())
+    } else {
+        let idx = path.remove_first();
+        if let Some(child) = node.child_at(idx) {
+            find_node(&child, path)
+        } else {
+            log::warn!("There is no child at index: {idx}");
+            None
         }
     }
+}
 
-    pub(crate) fn find_all_nodes(
-        &self,
-        nodes_to_find: &[(&TreePath, Option<&&'static str>)],
-    ) -> IndexMap<TreePath, (DomNode, DomNode)> {
-        let mut nodes_to_patch = IndexMap::with_capacity(nodes_to_find.len());
-        for (path, tag) in nodes_to_find {
-            let mut traverse_path: TreePath = (*path).clone();
-            if let Some(found) = self.find_node(&mut traverse_path) {
-                let mut parent_path = path.backtrack();
-                let target_parent = self
-                    .find_node(&mut parent_path)
-                    .expect("must find the parent");
-                nodes_to_patch.insert((*path).clone(), (found, target_parent));
-            } else {
-                log::warn!(
-                    "can not find: {:?} {:?} target_node: {:?}",
-                    path,
-                    tag,
-                    &self
-                );
-                log::info!(
-                    "real entire dom: {:#?}",
-                    dom_node::render_real_dom_to_string(&self.as_node())
-                );
-                log::warn!("entire dom: {}", self.render_to_string());
-            }
+/// find the nodes and their parent nodes at each of the paths
+fn find_all_nodes<N: DomBackend>(
+    root_node: &N,
+    nodes_to_find: &[(&TreePath, Option<&&'static str>)],
+) -> Result<IndexMap<TreePath, (N, N)>, Error> {
+    let mut nodes_to_patch = IndexMap::with_capacity(nodes_to_find.len());
+    for (path, tag) in nodes_to_find {
+        let mut traverse_path: TreePath = (*path).clone();
+        if let Some(found) = find_node(root_node, &mut traverse_path) {
+            let parent_path = path.backtrack();
+            let target_parent = find_node(root_node, &mut parent_path.clone())
+                .ok_or(Error::NodeNotFound(parent_path))?;
+            nodes_to_patch.insert((*path).clone(), (found, target_parent));
+        } else {
+            log::warn!(
+                "can not find: {:?} {:?} target_node: {:?}",
+                path,
+                tag,
+                root_node
+            );
+            log::warn!("entire dom: {}", root_node.render_to_string());
         }
-        nodes_to_patch
     }
+    Ok(nodes_to_patch)
 }
 
 impl<APP> Program<APP>
@@ -152,7 +129,8 @@ where
         &self,
         target_node: &DomNode,
         patches: &[Patch<APP::MSG>],
-    ) -> Result<Vec<DomPatch>, JsValue> {
+    ) -> Result<Vec<DomPatch>, Error> {
+        let _scope = self.delegation_scope();
         convert_patches(target_node, patches, self.create_ev_callback())
     }
 
@@ -163,7 +141,8 @@ where
         target_element: &DomNode,
         target_parent: &DomNode,
         patch: &Patch<APP::MSG>,
-    ) -> DomPatch {
+    ) -> Result<DomPatch, Error> {
+        let _scope = self.delegation_scope();
         convert_patch(
             nodes_lookup,
             target_element,
@@ -175,12 +154,13 @@ where
 }
 
 /// get the real DOM target node and make a DomPatch object for each of the Patch
-pub fn convert_patches<Msg, F>(
-    target_node: &DomNode,
+pub fn convert_patches<N, Msg, F>(
+    target_node: &N,
     patches: &[Patch<Msg>],
     ev_callback: F,
-) -> Result<Vec<DomPatch>, JsValue>
+) -> Result<Vec<DomPatch<N>>, Error>
 where
+    N: DomBackend,
     Msg: 'static,
     F: Fn(Msg) + 'static + Clone,
 {
@@ -195,38 +175,44 @@ where
         )
         .collect();
 
-    let nodes_lookup = target_node.find_all_nodes(&nodes_to_find);
+    let nodes_lookup = find_all_nodes(target_node, &nodes_to_find)?;
 
-    let dom_patches:Vec<DomPatch> = patches.iter().map(|patch|{
-        let patch_path = patch.path();
-        let patch_tag = patch.tag();
-        if let Some((target_node, target_parent)) = nodes_lookup.get(patch_path) {
-            let target_tag = target_node.tag();
-            if let (Some(patch_tag), Some(target_tag)) = (patch_tag, target_tag) {
-                if **patch_tag != target_tag{
-                    panic!(
-                        "expecting a tag: {patch_tag:?}, but found: {target_tag:?}"
-                    );
+    patches
+        .iter()
+        .map(|patch| {
+            let patch_path = patch.path();
+            let (target_node, target_parent) = nodes_lookup
+                .get(patch_path)
+                .ok_or_else(|| Error::NodeNotFound(patch_path.clone()))?;
+            if let (Some(patch_tag), Some(target_tag)) = (patch.tag(), target_node.tag()) {
+                if **patch_tag != target_tag {
+                    return Err(Error::TagMismatch {
+                        expected: patch_tag.to_string(),
+                        found: target_tag,
+                    });
                 }
             }
-            convert_patch(&nodes_lookup, target_node, target_parent, patch, ev_callback.clone())
-        } else {
-            unreachable!("Getting here means we didn't find the element of next node that we are supposed to patch, patch_path: {:?}, with tag: {:?}", patch_path, patch_tag);
-        }
-    }).collect();
-
-    Ok(dom_patches)
+            convert_patch(
+                &nodes_lookup,
+                target_node,
+                target_parent,
+                patch,
+                ev_callback.clone(),
+            )
+        })
+        .collect()
 }
 
 /// convert a virtual DOM Patch into a created DOM node Patch
-pub fn convert_patch<Msg, F>(
-    nodes_lookup: &IndexMap<TreePath, (DomNode, DomNode)>,
-    target_element: &DomNode,
-    target_parent: &DomNode,
+pub fn convert_patch<N, Msg, F>(
+    nodes_lookup: &IndexMap<TreePath, (N, N)>,
+    target_element: &N,
+    target_parent: &N,
     patch: &Patch<Msg>,
     ev_callback: F,
-) -> DomPatch
+) -> Result<DomPatch<N>, Error>
 where
+    N: DomBackend,
     Msg: 'static,
     F: Fn(Msg) + 'static + Clone,
 {
@@ -240,12 +226,12 @@ where
 
     let patch_path = patch_path.clone();
 
-    match patch_type {
+    let dom_patch = match patch_type {
         PatchType::InsertBeforeNode { nodes } => {
             let nodes = nodes
                 .iter()
-                .map(|for_insert| dom::create_dom_node(for_insert, ev_callback.clone()))
-                .collect();
+                .map(|for_insert| N::create_node(for_insert, ev_callback.clone()))
+                .collect::<Result<_, _>>()?;
             DomPatch {
                 patch_path,
                 target_element,
@@ -256,8 +242,8 @@ where
         PatchType::InsertAfterNode { nodes } => {
             let nodes = nodes
                 .iter()
-                .map(|for_insert| dom::create_dom_node(for_insert, ev_callback.clone()))
-                .collect();
+                .map(|for_insert| N::create_node(for_insert, ev_callback.clone()))
+                .collect::<Result<_, _>>()?;
             DomPatch {
                 patch_path,
                 target_element,
@@ -276,7 +262,7 @@ where
                 patch_variant: PatchVariant::AddAttributes {
                     attrs: attrs
                         .iter()
-                        .map(|a| convert_attr(a, ev_callback.clone()))
+                        .map(|a| N::convert_attr(a, ev_callback.clone()))
                         .collect(),
                 },
             }
@@ -288,7 +274,7 @@ where
             patch_variant: PatchVariant::RemoveAttributes {
                 attrs: attrs
                     .iter()
-                    .map(|a| convert_attr(a, ev_callback.clone()))
+                    .map(|a| N::convert_attr(a, ev_callback.clone()))
                     .collect(),
             },
         },
@@ -296,8 +282,8 @@ where
         PatchType::ReplaceNode { replacement } => {
             let replacement = replacement
                 .iter()
-                .map(|node| dom::create_dom_node(node, ev_callback.clone()))
-                .collect();
+                .map(|node| N::create_node(node, ev_callback.clone()))
+                .collect::<Result<_, _>>()?;
             DomPatch {
                 patch_path,
                 target_element,
@@ -321,10 +307,12 @@ where
             let for_moving = nodes_path
                 .iter()
                 .map(|path| {
-                    let (node, _) = nodes_lookup.get(path).expect("must have found the node");
-                    node.clone()
+                    nodes_lookup
+                        .get(path)
+                        .map(|(node, _)| node.clone())
+                        .ok_or_else(|| Error::NodeNotFound(path.clone()))
                 })
-                .collect();
+                .collect::<Result<_, _>>()?;
             DomPatch {
                 patch_path,
                 target_element,
@@ -336,10 +324,12 @@ where
             let for_moving = nodes_path
                 .iter()
                 .map(|path| {
-                    let (node, _) = nodes_lookup.get(path).expect("must have found the node");
-                    node.clone()
+                    nodes_lookup
+                        .get(path)
+                        .map(|(node, _)| node.clone())
+                        .ok_or_else(|| Error::NodeNotFound(path.clone()))
                 })
-                .collect();
+                .collect::<Result<_, _>>()?;
             DomPatch {
                 patch_path,
                 target_element,
@@ -350,8 +340,8 @@ where
         PatchType::AppendChildren { children } => {
             let children = children
                 .iter()
-                .map(|for_insert| dom::create_dom_node(for_insert, ev_callback.clone()))
-                .collect();
+                .map(|for_insert| N::create_node(for_insert, ev_callback.clone()))
+                .collect::<Result<_, _>>()?;
 
             DomPatch {
                 patch_path,
@@ -360,7 +350,8 @@ where
                 patch_variant: PatchVariant::AppendChildren { children },
             }
         }
-    }
+    };
+    Ok(dom_patch)
 }
 
 pub(crate) fn convert_attr<Msg, F>(attr: &Attribute<Msg>, ev_callback: F) -> DomAttr
@@ -374,12 +365,13 @@ where
         value: attr
             .value
             .iter()
-            .filter_map(|v| convert_attr_value(v, ev_callback.clone()))
+            .filter_map(|v| convert_attr_value(attr.name, v, ev_callback.clone()))
             .collect(),
     }
 }
 
 fn convert_attr_value<Msg, F>(
+    attr_name: &str,
     attr_value: &AttributeValue<Msg>,
     ev_callback: F,
 ) -> Option<DomAttrValue>
@@ -390,11 +382,14 @@ where
     match attr_value {
         AttributeValue::Simple(v) => Some(DomAttrValue::Simple(v.clone())),
         AttributeValue::Style(v) => Some(DomAttrValue::Style(v.clone())),
-        AttributeValue::EventListener(v) => Some(DomAttrValue::EventListener(
-            convert_event_listener(v, ev_callback),
+        AttributeValue::EventListener(v) => Some(convert_event_listener(
+            v,
+            ev_callback,
+            delegation::should_delegate(attr_name),
         )),
-        AttributeValue::ComponentEventListener(v) => Some(DomAttrValue::EventListener(
-            convert_component_event_listener(v),
+        AttributeValue::ComponentEventListener(v) => Some(convert_component_event_listener(
+            v,
+            delegation::should_delegate(attr_name),
         )),
         AttributeValue::Empty => None,
     }
@@ -403,40 +398,51 @@ where
 fn convert_event_listener<F, Msg>(
     event_listener: &EventCallback<Msg>,
     callback: F,
-) -> Closure<dyn FnMut(web_sys::Event)>
+    delegated: bool,
+) -> DomAttrValue
 where
     Msg: 'static,
     F: Fn(Msg) + 'static,
 {
     let event_listener = event_listener.clone();
-    let closure: Closure<dyn FnMut(web_sys::Event)> = Closure::new(move |event: web_sys::Event| {
-        let msg = event_listener.emit(dom::Event::from(event));
-        callback(msg);
-    });
-    closure
+    let options = event_listener.options();
+    let listener = move |event: web_sys::Event| {
+        if let Some(msg) = event_listener.try_emit(dom::Event::from(event)) {
+            callback(msg);
+        }
+    };
+    // the delegation roots only listens with the default options
+    if delegated && options.is_default() {
+        DomAttrValue::DelegatedListener(DelegatedListener::new(listener))
+    } else {
+        DomAttrValue::EventListener(Closure::new(listener), options)
+    }
 }
 
 /// TODO: this should not have access to root_node, so it can generically
 /// apply patch to any dom node
-pub fn apply_dom_patches(
-    root_node: Rc<RefCell<Option<DomNode>>>,
-    mount_node: Rc<RefCell<Option<DomNode>>>,
-    dom_patches: impl IntoIterator<Item = DomPatch>,
-) -> Result<(), JsValue> {
+pub fn apply_dom_patches<N: DomBackend>(
+    root_node: Rc<RefCell<Option<N>>>,
+    mount_node: Rc<RefCell<Option<N>>>,
+    dom_patches: impl IntoIterator<Item = DomPatch<N>>,
+) -> Result<(), Error> {
+    let dom_patches: Vec<DomPatch<N>> = dom_patches.into_iter().collect();
+    let after_patches = N::before_patches(&dom_patches);
     for dom_patch in dom_patches {
         apply_dom_patch(Rc::clone(&root_node), Rc::clone(&mount_node), dom_patch)?;
     }
+    after_patches();
     Ok(())
 }
 
 /// apply a dom patch to this root node,
 /// return a new root_node if it would replace the original root_node
 /// TODO: this should have no access to root_node, so it can be used in general sense
-pub(crate) fn apply_dom_patch(
-    root_node: Rc<RefCell<Option<DomNode>>>,
-    mount_node: Rc<RefCell<Option<DomNode>>>,
-    dom_patch: DomPatch,
-) -> Result<(), JsValue> {
+pub(crate) fn apply_dom_patch<N: DomBackend>(
+    root_node: Rc<RefCell<Option<N>>>,
+    mount_node: Rc<RefCell<Option<N>>>,
+    dom_patch: DomPatch<N>,
+) -> Result<(), Error> {
     let DomPatch {
         patch_path,
         target_element,
@@ -446,42 +452,27 @@ pub(crate) fn apply_dom_patch(
 
     match patch_variant {
         PatchVariant::InsertBeforeNode { nodes } => {
-            target_parent.insert_before(&target_element, nodes);
+            let entering = with_transition(&nodes);
+            target_parent.insert_before(&target_element, nodes)?;
+            enter_transitions(&entering)?;
         }
 
         PatchVariant::InsertAfterNode { nodes } => {
-            target_parent.insert_after(&target_element, nodes);
+            let entering = with_transition(&nodes);
+            target_parent.insert_after(&target_element, nodes)?;
+            enter_transitions(&entering)?;
         }
         PatchVariant::AppendChildren { children } => {
-            target_element.append_children(children);
+            let entering = with_transition(&children);
+            target_element.append_children(children)?;
+            enter_transitions(&entering)?;
         }
 
         PatchVariant::AddAttributes { attrs } => {
-            target_element.set_dom_attrs(attrs).unwrap();
+            target_element.set_attrs(attrs)?;
         }
         PatchVariant::RemoveAttributes { attrs } => {
-            for attr in attrs.iter() {
-                for att_value in attr.value.iter() {
-                    match att_value {
-                        DomAttrValue::Simple(_) => {
-                            target_element.remove_dom_attr(attr)?;
-                        }
-                        // it is an event listener
-                        DomAttrValue::EventListener(_) => {
-                            let DomInner::Element { listeners, .. } = &target_element.inner else {
-                                unreachable!("must be an element");
-                            };
-                            if let Some(listener) = listeners.borrow_mut().as_mut() {
-                                listener.retain(|event, _| *event != attr.name)
-                            }
-                        }
-                        DomAttrValue::Style(_) => {
-                            target_element.remove_dom_attr(attr)?;
-                        }
-                        DomAttrValue::Empty => (),
-                    }
-                }
-            }
+            target_element.remove_attrs(&attrs)?;
         }
 
         // This also removes the associated closures and event listeners to the node being replaced
@@ -489,6 +480,7 @@ pub(crate) fn apply_dom_patch(
         // before it is actully replaced in the DOM
         // TODO: make root node a Vec
         PatchVariant::ReplaceNode { mut replacement } => {
+            let entering = with_transition(&replacement);
             let first_node = replacement.remove(0);
 
             if target_element.is_fragment() {
@@ -497,49 +489,81 @@ pub(crate) fn apply_dom_patch(
                     "this should only happen to root node"
                 );
                 let mut mount_node = mount_node.borrow_mut();
-                let mount_node = mount_node.as_mut().expect("must have a mount node");
-                mount_node.append_children(vec![first_node.clone()]);
-                mount_node.append_children(replacement);
+                let mount_node = mount_node.as_mut().ok_or(Error::NotMounted)?;
+                mount_node.append_children(vec![first_node.clone()])?;
+                mount_node.append_children(replacement)?;
             } else {
                 if patch_path.path.is_empty() {
                     let mut mount_node = mount_node.borrow_mut();
-                    let mount_node = mount_node.as_mut().expect("must have a mount node");
-                    mount_node.replace_child(&target_element, first_node.clone());
+                    let mount_node = mount_node.as_mut().ok_or(Error::NotMounted)?;
+                    mount_node.replace_child(&target_element, first_node.clone())?;
+                    //insert the rest
+                    mount_node.insert_after(&first_node, replacement)?;
                 } else {
-                    target_parent.replace_child(&target_element, first_node.clone());
+                    if target_element.has_transition() {
+                        // the replaced node stays until its leave transition has ended
+                        target_parent.insert_before(&target_element, vec![first_node.clone()])?;
+                        target_parent.remove_children_with_transition(&[&target_element])?;
+                    } else {
+                        target_parent.replace_child(&target_element, first_node.clone())?;
+                    }
+                    //insert the rest
+                    target_parent.insert_after(&first_node, replacement)?;
                 }
-                //insert the rest
-                target_parent.insert_after(&first_node, replacement);
             }
+            enter_transitions(&entering)?;
             if patch_path.path.is_empty() {
                 *root_node.borrow_mut() = Some(first_node);
             }
         }
         PatchVariant::RemoveNode => {
-            target_parent.remove_children(&[&target_element]);
+            target_parent.remove_children_with_transition(&[&target_element])?;
         }
         PatchVariant::ClearChildren => {
-            target_element.clear_children();
+            target_element.clear_children()?;
         }
         PatchVariant::MoveBeforeNode { for_moving } => {
-            target_parent.remove_children(&for_moving.iter().collect::<Vec<_>>());
-            target_parent.insert_before(&target_element, for_moving);
+            target_parent.remove_children(&for_moving.iter().collect::<Vec<_>>())?;
+            target_parent.insert_before(&target_element, for_moving)?;
         }
 
         PatchVariant::MoveAfterNode { for_moving } => {
-            target_parent.remove_children(&for_moving.iter().collect::<Vec<_>>());
-            target_parent.insert_after(&target_element, for_moving);
+            target_parent.remove_children(&for_moving.iter().collect::<Vec<_>>())?;
+            target_parent.insert_after(&target_element, for_moving)?;
         }
     }
     Ok(())
 }
 
+/// return the nodes which has enter and leave transitions
+fn with_transition<N: DomBackend>(nodes: &[N]) -> Vec<N> {
+    nodes
+        .iter()
+        .filter(|node| node.has_transition())
+        .cloned()
+        .collect()
+}
+
+/// start the enter transition of the nodes
+fn enter_transitions<N: DomBackend>(entering: &[N]) -> Result<(), Error> {
+    for node in entering {
+        node.enter_transition()?;
+    }
+    Ok(())
+}
+
 fn convert_component_event_listener(
     component_callback: &ComponentEventCallback,
-) -> Closure<dyn FnMut(web_sys::Event)> {
+    delegated: bool,
+) -> DomAttrValue {
+    let options = component_callback.options();
     let component_callback = component_callback.clone();
-    let closure: Closure<dyn FnMut(web_sys::Event)> = Closure::new(move |event: web_sys::Event| {
-        component_callback.emit(dom::Event::from(event));
-    });
-    closure
+    let listener = move |event: web_sys::Event| {
+        component_callback.try_emit(dom::Event::from(event));
+    };
+    if delegated && options.is_default() {
+        DomAttrValue::DelegatedListener(DelegatedListener::new(listener))
+    } else {
+        DomAttrValue::EventListener(Closure::new(listener), options)
+    }
 }
@@ -0,0 +1,48 @@
+use crate::vdom::TreePath;
+use wasm_bindgen::JsValue;
+
+/// Errors which can occur in the runtime while mounting the app and patching the DOM
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum Error {
+    /// the node at this path can not be found in the DOM
+    #[error("unable to find the node at path: {0:?}")]
+    NodeNotFound(TreePath),
+    /// the tag of the node in the DOM does not match the tag of the node in the patch
+    #[error("expecting a tag: {expected:?}, but found: {found:?}")]
+    TagMismatch {
+        /// the tag of the node in the patch
+        expected: String,
+        /// the tag of the node found in the DOM
+        found: String,
+    },
+    /// the node is not a child of the parent node where it is patched
+    #[error("unable to find the child node: {0}")]
+    ChildNotFound(String),
+    /// the operation can only be done to an element, ie: setting an attribute
+    #[error("expecting an element, but found: {0}")]
+    NotAnElement(String),
+    /// the program is not mounted yet, so there is no root node or mount node
+    #[error("the program is not mounted")]
+    NotMounted,
+    /// the document has no head element where the style can be injected
+    #[error("the document has no head element")]
+    NoHead,
+    /// the document has no body element where the portal is rendered
+    #[error("the document has no body element")]
+    NoBody,
+    /// the mount node which is to be replaced by the app has no parent
+    #[error("the mount node has no parent to be replaced in")]
+    NoParent,
+    /// unable to attach a shadow root to the mount node
+    #[error("unable to attach a shadow root: {0:?}")]
+    AttachShadow(JsValue),
+    /// an error thrown by a javascript call
+    #[error("javascript error: {0:?}")]
+    JsError(JsValue),
+}
+
+impl From<JsValue> for Error {
+    fn from(value: JsValue) -> Self {
+        Self::JsError(value)
+    }
+}
@@ -1,11 +1,12 @@
 //! Create [events][0] Object
 //!
 //! [0]: https://developer.mozilla.org/en-US/docs/Web/Events
-use crate::dom::DomNode;
 use crate::dom::{document, window, Event};
+use crate::dom::{DomNode, HeadlessEvent};
 use crate::vdom;
 use crate::vdom::ComponentEventCallback;
 use crate::vdom::{Attribute, AttributeValue, EventCallback};
+use serde::de::DeserializeOwned;
 use wasm_bindgen::JsCast;
 pub use web_sys::ClipboardEvent;
 pub use web_sys::{
@@ -65,6 +66,12 @@ impl From<MountEvent> for Event {
     }
 }
 
+impl From<HeadlessEvent> for Event {
+    fn from(headless_event: HeadlessEvent) -> Self {
+        Event::HeadlessEvent(headless_event)
+    }
+}
+
 impl From<web_sys::Event> for Event {
     fn from(web_event: web_sys::Event) -> Self {
         Event::WebEvent(web_event)
@@ -92,13 +99,54 @@ where
     )
 }
 
+/// an event builder of a listener which is only called for the events it accepts,
+/// ie: the typed listeners are not called when their typed event can not be created
+/// from the event
+pub(crate) fn on_accepted<F, MSG>(event_name: &'static str, f: F) -> Attribute<MSG>
+where
+    F: FnMut(Event) -> Option<MSG> + 'static,
+    MSG: 'static,
+{
+    vdom::attr(
+        event_name,
+        AttributeValue::EventListener(EventCallback::from_partial(f)),
+    )
+}
+
 /// on click event
 pub fn on_click<F, MSG>(mut f: F) -> Attribute<MSG>
 where
     F: FnMut(MouseEvent) -> MSG + 'static,
     MSG: 'static,
 {
-    on("click", move |event: Event| f(to_mouse_event(event)))
+    on_accepted("click", move |event: Event| {
+        to_mouse_event(event).map(&mut f)
+    })
+}
+
+/// Listen to a `CustomEvent` and deserialize its `detail` into `T`,
+/// such as the events dispatched with [`Cmd::emit_custom_event`](crate::dom::Cmd::emit_custom_event)
+/// or by non-sauron code.
+/// The callback is passed the error when the detail can not be deserialized into `T`.
+/// # Example
+/// ```rust
+/// use sauron::{*, dom::events::on_custom};
+/// enum Msg { DateChanged(String), InvalidDate }
+/// let attr: Attribute<Msg> = on_custom("date-changed", |date: Result<String, _>| match date {
+///     Ok(date) => Msg::DateChanged(date),
+///     Err(_) => Msg::InvalidDate,
+/// });
+/// ```
+pub fn on_custom<T, F, MSG>(event_name: &'static str, mut f: F) -> Attribute<MSG>
+where
+    T: DeserializeOwned + 'static,
+    F: FnMut(Result<T, serde_wasm_bindgen::Error>) -> MSG + 'static,
+    MSG: 'static,
+{
+    on_accepted(event_name, move |event: Event| {
+        let custom_event: web_sys::CustomEvent = event.as_web()?.dyn_into().ok()?;
+        Some(f(serde_wasm_bindgen::from_value(custom_event.detail())))
+    })
 }
 
 /// attach callback to the scroll event
@@ -107,18 +155,17 @@ where
     F: FnMut((i32, i32)) -> MSG + 'static,
     MSG: 'static,
 {
-    on("scroll", move |event: Event| {
-        let web_event = event.as_web().expect("must be a web event");
-        let target = web_event.target().expect("can't get target");
+    on_accepted("scroll", move |event: Event| {
+        let target = event.as_web()?.target()?;
         if let Some(element) = target.dyn_ref::<web_sys::Element>() {
             let scroll_top = element.scroll_top();
             let scroll_left = element.scroll_left();
-            f((scroll_top, scroll_left))
+            Some(f((scroll_top, scroll_left)))
         } else {
             let window = window();
-            let scroll_top = window.page_y_offset().expect("must get page offset") as i32;
-            let scroll_left = window.page_x_offset().expect("must get page offset") as i32;
-            f((scroll_top, scroll_left))
+            let scroll_top = window.page_y_offset().ok()? as i32;
+            let scroll_left = window.page_x_offset().ok()? as i32;
+            Some(f((scroll_top, scroll_left)))
         }
     })
 }
@@ -144,14 +191,13 @@ where
     F: FnMut(MountEvent) -> MSG + 'static,
     MSG: 'static,
 {
-    on("mount", move |event: Event| {
-        let web_event = event.as_web().expect("must be a web event");
-        let event_target = web_event.target().expect("must have a target");
+    on_accepted("mount", move |event: Event| {
+        let event_target = event.as_web()?.target()?;
         let target_node: web_sys::Node = event_target.unchecked_into();
         let me = MountEvent {
             target_node: DomNode::from(target_node),
         };
-        f(me)
+        Some(f(me))
     })
 }
 
@@ -162,17 +208,17 @@ where
     MSG: 'static,
 {
     let cb = move |event: Event| {
-        let web_event = event.as_web().expect("must be a web event");
-        let event_target = web_event.target().expect("must have a target");
+        let event_target = event.as_web()?.target()?;
         let target_node: web_sys::Node = event_target.unchecked_into();
         let me = MountEvent {
             target_node: DomNode::from(target_node),
         };
         f(me);
+        Some(())
     };
     vdom::attr(
         "mount",
-        AttributeValue::ComponentEventListener(ComponentEventCallback::from(cb)),
+        AttributeValue::ComponentEventListener(ComponentEventCallback::from_partial(cb)),
     )
 }
 
@@ -192,8 +238,8 @@ macro_rules! declare_events {
                     where CB: FnMut($ret) -> MSG + 'static,
                           MSG: 'static,
                     {
-                        on(stringify!($event), move|event:Event|{
-                            cb($mapper(event))
+                        on_accepted(stringify!($event), move|event:Event|{
+                            $mapper(event).map(&mut cb)
                         })
                 }
             }
@@ -219,55 +265,75 @@ macro_rules! declare_html_events{
     }
 }
 
+/// cast the event into the specific web_sys event,
+/// returns None if it is not a web_sys event of that type.
+/// The events dispatched in the headless in-memory DOM are passed as a synthetic event.
+fn cast_web_event<T>(event: Event) -> Option<T>
+where
+    T: JsCast,
+{
+    match event {
+        Event::WebEvent(web_event) => web_event.dyn_into().ok(),
+        Event::HeadlessEvent(headless_event) => Some(synthetic_web_event(&headless_event)),
+        Event::MountEvent(_) => None,
+    }
+}
+
+/// the web_sys event which is passed to the typed listeners of the events dispatched in the
+/// headless in-memory DOM.
+/// Natively, there is no web_sys event, so the fields of the synthetic event can not be read.
+#[cfg(target_arch = "wasm32")]
+fn synthetic_web_event<T>(headless_event: &HeadlessEvent) -> T
+where
+    T: JsCast,
+{
+    web_sys::Event::new(&headless_event.name)
+        .map(JsCast::unchecked_into)
+        .unwrap_or_else(|_| wasm_bindgen::JsValue::UNDEFINED.unchecked_into())
+}
+
+/// the web_sys event which is passed to the typed listeners of the events dispatched in the
+/// headless in-memory DOM.
+/// Natively, there is no web_sys event, so the fields of the synthetic event can not be read.
+#[cfg(not(target_arch = "wasm32"))]
+fn synthetic_web_event<T>(_headless_event: &HeadlessEvent) -> T
+where
+    T: JsCast,
+{
+    wasm_bindgen::JsValue::UNDEFINED.unchecked_into()
+}
+
 /// convert a generic event to MouseEvent
-fn to_mouse_event(event: Event) -> MouseEvent {
-    let web_event = event.as_web().expect("must be a web_sys event");
-    web_event.dyn_into().expect("Unable to cast to mouse event")
+fn to_mouse_event(event: Event) -> Option<MouseEvent> {
+    cast_web_event(event)
 }
 
-fn to_focus_event(event: Event) -> FocusEvent {
-    let web_event = event.as_web().expect("must be a web_sys event");
-    web_event.dyn_into().expect("Unable to cast to focus event")
+fn to_focus_event(event: Event) -> Option<FocusEvent> {
+    cast_web_event(event)
 }
 
-fn to_keyboard_event(event: Event) -> KeyboardEvent {
-    let web_event = event.as_web().expect("must be a web_sys event");
-    web_event
-        .dyn_into()
-        .expect("unable to cast to keyboard event")
+fn to_keyboard_event(event: Event) -> Option<KeyboardEvent> {
+    cast_web_event(event)
 }
 
-fn to_animation_event(event: Event) -> AnimationEvent {
-    let web_event = event.as_web().expect("must be a web_sys event");
-    web_event
-        .dyn_into()
-        .expect("unable to cast to animation event")
+fn to_animation_event(event: Event) -> Option<AnimationEvent> {
+    cast_web_event(event)
 }
 
-fn to_transition_event(event: Event) -> TransitionEvent {
-    let web_event = event.as_web().expect("must be a web_sys event");
-    web_event
-        .dyn_into()
-        .expect("unable to cast to transition event")
+fn to_transition_event(event: Event) -> Option<TransitionEvent> {
+    cast_web_event(event)
 }
 
-fn to_touch_event(event: Event) -> TouchEvent {
-    let web_event = event.as_web().expect("must be web sys event");
-    web_event.dyn_into().expect("unable to cast to touch event")
+fn to_touch_event(event: Event) -> Option<TouchEvent> {
+    cast_web_event(event)
 }
 
-fn to_webevent(event: Event) -> web_sys::Event {
-    match event {
-        Event::WebEvent(event) => event,
-        _ => panic!("not a web_event"),
-    }
+fn to_webevent(event: Event) -> Option<web_sys::Event> {
+    cast_web_event(event)
 }
 
-fn to_hashchange_event(event: Event) -> HashChangeEvent {
-    let web_event = event.as_web().expect("must be a web_sys event");
-    web_event
-        .dyn_into()
-        .expect("unable to cast to hashchange event")
+fn to_hashchange_event(event: Event) -> Option<HashChangeEvent> {
+    cast_web_event(event)
 }
 
 /// TODO: expand this much farther by getting the InputEvent data, data_transfer, event_type,
@@ -275,30 +341,56 @@ fn to_hashchange_event(event: Event) -> HashChangeEvent {
 /// a custom InputEvent to contain the input string value
 #[derive(Debug)]
 pub struct InputEvent {
-    /// the actual dom event
-    pub event: web_sys::Event,
+    /// the actual dom event, None when dispatched in the headless in-memory DOM
+    pub event: Option<web_sys::Event>,
+    /// the value of the target element when dispatched in the headless in-memory DOM
+    headless_value: Option<String>,
 }
 
 impl InputEvent {
     fn new(event: web_sys::Event) -> Self {
-        InputEvent { event }
+        InputEvent {
+            event: Some(event),
+            headless_value: None,
+        }
+    }
+
+    fn from_headless(headless_event: &HeadlessEvent) -> Self {
+        InputEvent {
+            event: None,
+            headless_value: Some(
+                headless_event
+                    .target
+                    .get_attribute("value")
+                    .unwrap_or_default(),
+            ),
+        }
     }
 
     /// call prevent default on the underlying event
     pub fn prevent_default(&self) {
-        self.event.prevent_default()
+        if let Some(event) = &self.event {
+            event.prevent_default()
+        }
     }
 
     /// call stop_propagation on the underlying event
     pub fn stop_propagation(&self) {
-        self.event.stop_propagation()
+        if let Some(event) = &self.event {
+            event.stop_propagation()
+        }
     }
 
-    /// the input value
+    /// the input value, empty if the event has no target or the target has no value
     /// TODO: this should be optional since there will be custom component
     /// aside from `input`, `textarea`, `select`
     pub fn value(&self) -> String {
-        let target: EventTarget = self.event.target().expect("Unable to get event target");
+        let Some(event) = &self.event else {
+            return self.headless_value.clone().unwrap_or_default();
+        };
+        let Some(target): Option<EventTarget> = event.target() else {
+            return String::new();
+        };
         if let Some(input) = target.dyn_ref::<HtmlInputElement>() {
             input.value()
         } else if let Some(textarea) = target.dyn_ref::<HtmlTextAreaElement>() {
@@ -320,7 +412,7 @@ impl InputEvent {
                 }
             }
         } else {
-            panic!("fail in mapping event into input event");
+            String::new()
         }
     }
 
@@ -337,47 +429,60 @@ impl InputEvent {
     }
 }
 
-fn to_input_event(event: Event) -> InputEvent {
-    let web_event = event.as_web().expect("must be a web event");
-    InputEvent::new(web_event)
+fn to_input_event(event: Event) -> Option<InputEvent> {
+    match event {
+        Event::WebEvent(web_event) => Some(InputEvent::new(web_event)),
+        Event::HeadlessEvent(headless_event) => Some(InputEvent::from_headless(&headless_event)),
+        Event::MountEvent(_) => None,
+    }
+}
+
+/// returns true if the attribute of the headless event target is set to true
+fn headless_flag(event: &Event, name: &str) -> Option<bool> {
+    match event {
+        Event::HeadlessEvent(headless_event) => Some(
+            headless_event
+                .target
+                .get_attribute(name)
+                .is_some_and(|value| value == "true"),
+        ),
+        _ => None,
+    }
 }
 
-fn to_checked(event: Event) -> bool {
-    let web_event = event.as_web().expect("must be a web event");
-    let target: EventTarget = web_event.target().expect("Unable to get event target");
-    if let Some(input) = target.dyn_ref::<HtmlInputElement>() {
-        input.checked()
-    } else {
-        panic!("must be a html input element");
+fn to_checked(event: Event) -> Option<bool> {
+    if let Some(is_checked) = headless_flag(&event, "checked") {
+        return Some(is_checked);
     }
+    let target: EventTarget = event.as_web()?.target()?;
+    target
+        .dyn_ref::<HtmlInputElement>()
+        .map(|input| input.checked())
 }
 
-fn to_open(event: Event) -> bool {
-    let web_event = event.as_web().expect("must be a web event");
-    let target: EventTarget = web_event.target().expect("Unable to get event target");
-    if let Some(details) = target.dyn_ref::<HtmlDetailsElement>() {
-        details.open()
-    } else {
-        panic!("must be a html input element");
+fn to_open(event: Event) -> Option<bool> {
+    if let Some(is_open) = headless_flag(&event, "open") {
+        return Some(is_open);
     }
+    let target: EventTarget = event.as_web()?.target()?;
+    target
+        .dyn_ref::<HtmlDetailsElement>()
+        .map(|details| details.open())
 }
 
 /// Note: paste event happens before the data is inserted into the target element
 /// therefore trying to access the data on the target element triggered from paste will get an
 /// empty text
-fn to_clipboard_event(event: Event) -> ClipboardEvent {
-    event
-        .as_web()
-        .expect("must be a web event")
-        .dyn_into()
-        .expect("unable to cast to clipboard event")
+fn to_clipboard_event(event: Event) -> Option<ClipboardEvent> {
+    cast_web_event(event)
 }
 
-fn to_selection(_event: Event) -> Option<Selection> {
-    if let Ok(Some(selection)) = document().get_selection() {
-        Some(selection)
+/// there is no selection in the headless in-memory DOM
+fn to_selection(event: Event) -> Option<Option<Selection>> {
+    if let Event::HeadlessEvent(_) = event {
+        Some(None)
     } else {
-        None
+        Some(document().get_selection().ok().flatten())
     }
 }
 
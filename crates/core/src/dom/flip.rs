@@ -0,0 +1,110 @@
+//! FLIP animation of the reordered children of an element that has the `animate_moves` attribute
+//!
+//! First: the bounding rect of each children is recorded before the patches are applied
+//! Last: the bounding rect of each children is recorded after the patches are applied
+//! Invert: the children are translated back to their first position
+//! Play: the translation is removed and the children are transitioned into their last position
+use crate::{
+    dom::{
+        dom_node::intern,
+        transition::{on_transition_end, request_next_frame},
+        DomNode, DomPatch, PatchVariant,
+    },
+    vdom::ANIMATE_MOVES,
+};
+use wasm_bindgen::JsCast;
+
+/// The first positions of the children of the elements which animates its moves
+pub(crate) struct Flip {
+    /// the element children, the duration and their first position
+    first: Vec<(web_sys::HtmlElement, u32, f64, f64)>,
+}
+
+impl DomNode {
+    /// return the duration of the move animation of the children of this element
+    pub(crate) fn animate_moves_duration(&self) -> Option<u32> {
+        if self.is_element() {
+            self.as_element()
+                .get_attribute(intern(ANIMATE_MOVES))
+                .and_then(|duration| duration.parse().ok())
+        } else {
+            None
+        }
+    }
+}
+
+impl Flip {
+    /// record the position of the children of the parent of moved nodes
+    /// which has the `animate_moves` attribute
+    pub(crate) fn first(dom_patches: &[DomPatch]) -> Self {
+        let mut parents: Vec<(&DomNode, u32)> = vec![];
+        for dom_patch in dom_patches {
+            let is_move = matches!(
+                dom_patch.patch_variant,
+                PatchVariant::MoveBeforeNode { .. } | PatchVariant::MoveAfterNode { .. }
+            );
+            if !is_move || parents.iter().any(|(p, _)| *p == &dom_patch.target_parent) {
+                continue;
+            }
+            if let Some(duration) = dom_patch.target_parent.animate_moves_duration() {
+                parents.push((&dom_patch.target_parent, duration));
+            }
+        }
+        let first = parents
+            .into_iter()
+            .flat_map(|(parent, duration)| {
+                parent
+                    .children()
+                    .map(|children| {
+                        children
+                            .iter()
+                            .filter(|child| child.is_element())
+                            .filter_map(|child| child.as_element().dyn_into().ok())
+                            .map(|element: web_sys::HtmlElement| {
+                                let rect = element.get_bounding_client_rect();
+                                (element, duration, rect.left(), rect.top())
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                    .unwrap_or_default()
+            })
+            .collect();
+        Self { first }
+    }
+
+    /// translate the children back to their first position,
+    /// then transition them into their last position
+    pub(crate) fn play(self) {
+        for (element, duration, first_left, first_top) in self.first {
+            // the node has been removed
+            if !element.is_connected() {
+                continue;
+            }
+            let last = element.get_bounding_client_rect();
+            let dx = first_left - last.left();
+            let dy = first_top - last.top();
+            if dx == 0.0 && dy == 0.0 {
+                continue;
+            }
+            let style = element.style();
+            style
+                .set_property("transition", "none")
+                .expect("must set transition");
+            style
+                .set_property("transform", &format!("translate({dx}px, {dy}px)"))
+                .expect("must set transform");
+
+            request_next_frame(move || {
+                let style = element.style();
+                style.set_property("transition", &format!("transform {duration}ms ease"))?;
+                style.remove_property("transform")?;
+                let target: web_sys::Element = element.clone().unchecked_into();
+                on_transition_end(&target, move || {
+                    element.style().remove_property("transition")?;
+                    Ok(())
+                })
+            })
+            .expect("must request animation frame");
+        }
+    }
+}
@@ -0,0 +1,60 @@
+//! A headless runtime where the DOM is an in-memory tree of nodes.
+//!
+//! The lifecycle of an Application: the dispatching of msgs, updating the app, diffing and
+//! patching the DOM and calling the event listeners are all executed natively
+//! without the need of a browser, which allows the app to be tested using plain `cargo test`.
+//!
+//! # Example
+//! ```rust
+//! use sauron::{*, dom::HeadlessProgram};
+//!
+//! struct App {
+//!     count: i32,
+//! }
+//!
+//! enum Msg {
+//!     Increment,
+//! }
+//!
+//! impl Application for App {
+//!     type MSG = Msg;
+//!
+//!     fn update(&mut self, msg: Msg) -> Cmd<Msg> {
+//!         match msg {
+//!             Msg::Increment => self.count += 1,
+//!         }
+//!         Cmd::none()
+//!     }
+//!
+//!     fn view(&self) -> Node<Msg> {
+//!         div([], [
+//!             button([on("click", |_| Msg::Increment)], [text("+")]),
+//!             span([], [text(self.count)]),
+//!         ])
+//!     }
+//! }
+//!
+//! let program = HeadlessProgram::new(App { count: 0 });
+//! let button = program.root_node().unwrap().children()[0].clone();
+//! button.dispatch_event("click");
+//! button.dispatch_event("click");
+//! assert_eq!(program.app().count, 2);
+//! assert_eq!(
+//!     program.render_to_string(),
+//!     "<div><button>+</button><span>2</span></div>"
+//! );
+//! ```
+pub use headless_node::{HeadlessAttr, HeadlessNode};
+pub use headless_program::HeadlessProgram;
+
+mod headless_node;
+mod headless_program;
+
+/// An event dispatched to an element in the in-memory DOM
+#[derive(Debug, Clone)]
+pub struct HeadlessEvent {
+    /// the name of the event, ie: `click`, `input`
+    pub name: String,
+    /// the element where the event is dispatched
+    pub target: HeadlessNode,
+}
@@ -0,0 +1,805 @@
+use crate::{
+    dom::{
+        DomAttr, DomAttrValue, DomBackend, Error, Event, HeadlessEvent, StatefulComponent,
+        StatefulModel,
+    },
+    html::lookup,
+    vdom::{
+        self, Attribute, AttributeName, GroupedAttributeValues, Leaf, Portal, Style, TreePath,
+        Value,
+    },
+};
+use indexmap::IndexMap;
+use std::{
+    cell::RefCell,
+    fmt,
+    rc::{Rc, Weak},
+};
+
+/// an event listener attached to an element of the in-memory DOM
+pub(crate) type HeadlessListener = Rc<dyn Fn(Event)>;
+
+/// the events which are only dispatched to their target, and don't bubble up to its ancestors
+const NON_BUBBLING_EVENTS: &[&str] = &[
+    "blur",
+    "error",
+    "focus",
+    "load",
+    "mount",
+    "mouseenter",
+    "mouseleave",
+    "scroll",
+    "toggle",
+    "unload",
+];
+
+/// A node in the in-memory DOM.
+/// This is the counter part of the DomNode, except that it is backed by plain rust values
+/// instead of the browser DOM, so it can be created, patched and have its listeners called
+/// natively.
+#[derive(Clone)]
+pub struct HeadlessNode {
+    inner: Rc<HeadlessCell>,
+}
+
+struct HeadlessCell {
+    node: RefCell<HeadlessInner>,
+    /// the node which has this node in its children, used for bubbling up the events
+    parent: RefCell<Weak<HeadlessCell>>,
+}
+
+enum HeadlessInner {
+    /// an element node
+    Element {
+        /// the tag of the element
+        tag: String,
+        /// the attributes of the element, the values are already merged into a string
+        attributes: IndexMap<AttributeName, String>,
+        /// the event listeners of the element
+        listeners: IndexMap<AttributeName, Vec<HeadlessListener>>,
+        /// the child nodes of the element
+        children: Vec<HeadlessNode>,
+        /// determine if this element has enter and leave transitions
+        has_transition: bool,
+    },
+    /// text node
+    Text(String),
+    /// html entities such as `&nbsp;`
+    Symbol(String),
+    /// comment node
+    Comment(String),
+    /// Fragment node
+    Fragment {
+        /// the child nodes of the fragment
+        children: Vec<HeadlessNode>,
+    },
+    /// Portal node, which is just a placeholder comment in its parent
+    Portal {
+        /// the element which holds the children of the portal,
+        /// it is outside of the tree since there is no other container in the in-memory DOM
+        host: HeadlessNode,
+    },
+    /// Stateful component, which view is created and patched by its own [`HeadlessProgram`](crate::dom::HeadlessProgram)
+    StatefulComponent {
+        /// the element which has the attributes of the component
+        host: HeadlessNode,
+        /// the view of the component, rendered before the external children in the host element
+        view: HeadlessNode,
+        /// the external children of the component
+        children: Vec<HeadlessNode>,
+        /// the component which is notified of the changes to its attributes
+        comp: Rc<RefCell<dyn StatefulComponent>>,
+        /// update the view of the component, this also keeps the program of the component alive
+        update_view: Rc<dyn Fn()>,
+    },
+}
+
+/// An attribute of an element in the in-memory DOM
+#[derive(Clone)]
+pub struct HeadlessAttr {
+    name: AttributeName,
+    /// the plain values or the styles merged into a string
+    value: Option<String>,
+    /// the event listeners of the attribute
+    listeners: Vec<HeadlessListener>,
+}
+
+impl fmt::Debug for HeadlessAttr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("HeadlessAttr")
+            .field("name", &self.name)
+            .field("value", &self.value)
+            .field("listeners", &self.listeners.len())
+            .finish()
+    }
+}
+
+impl fmt::Debug for HeadlessNode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &*self.inner.node.borrow() {
+            HeadlessInner::Element { tag, children, .. } => f
+                .debug_struct("Element")
+                .field("tag", tag)
+                .field(
+                    "children",
+                    &children.iter().map(|c| c.tag()).collect::<Vec<_>>(),
+                )
+                .finish(),
+            HeadlessInner::Text(text) => f.debug_tuple("Text").field(text).finish(),
+            HeadlessInner::Symbol(symbol) => f.debug_tuple("Symbol").field(symbol).finish(),
+            HeadlessInner::Comment(_) => write!(f, "Comment"),
+            HeadlessInner::Fragment { .. } => write!(f, "Fragment"),
+            HeadlessInner::Portal { host } => f.debug_tuple("Portal").field(host).finish(),
+            HeadlessInner::StatefulComponent { .. } => write!(f, "StatefulComponent"),
+        }
+    }
+}
+
+/// nodes are equal when they are the same node in the tree
+impl PartialEq for HeadlessNode {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl HeadlessNode {
+    /// create the in-memory node of the vdom node, which can not fail
+    pub(crate) fn create<MSG, F>(node: &vdom::Node<MSG>, ev_callback: F) -> Self
+    where
+        MSG: 'static,
+        F: Fn(MSG) + 'static + Clone,
+    {
+        match node {
+            vdom::Node::Element(elm) => {
+                let mut attributes = IndexMap::new();
+                let mut listeners = IndexMap::new();
+                let attrs = Attribute::merge_attributes_of_same_name(elm.attributes().iter())
+                    .iter()
+                    .map(|attr| Self::convert_attr(attr, ev_callback.clone()))
+                    .collect();
+                insert_attrs(&mut attributes, &mut listeners, attrs);
+                HeadlessNode::new_parent(HeadlessInner::Element {
+                    tag: elm.tag().to_string(),
+                    attributes,
+                    listeners,
+                    children: elm
+                        .children()
+                        .iter()
+                        .map(|child| Self::create(child, ev_callback.clone()))
+                        .collect(),
+                    has_transition: elm.has_transition(),
+                })
+            }
+            vdom::Node::Leaf(leaf) => create_headless_leaf(leaf, ev_callback),
+        }
+    }
+
+    fn new(inner: HeadlessInner) -> Self {
+        Self {
+            inner: Rc::new(HeadlessCell {
+                node: RefCell::new(inner),
+                parent: RefCell::new(Weak::new()),
+            }),
+        }
+    }
+
+    /// create the node, and set it as the parent of its children
+    fn new_parent(inner: HeadlessInner) -> Self {
+        let headless_node = Self::new(inner);
+        match &*headless_node.inner.node.borrow() {
+            HeadlessInner::Element { children, .. } | HeadlessInner::Fragment { children } => {
+                for child in children.iter() {
+                    child.set_parent(Some(&headless_node));
+                }
+            }
+            HeadlessInner::StatefulComponent { view, children, .. } => {
+                for child in [view].into_iter().chain(children.iter()) {
+                    child.set_parent(Some(&headless_node));
+                }
+            }
+            HeadlessInner::Portal { host } => host.set_parent(Some(&headless_node)),
+            HeadlessInner::Text(_) | HeadlessInner::Symbol(_) | HeadlessInner::Comment(_) => (),
+        }
+        headless_node
+    }
+
+    /// return the node which has this node in its children
+    pub fn parent(&self) -> Option<HeadlessNode> {
+        self.inner
+            .parent
+            .borrow()
+            .upgrade()
+            .map(|inner| HeadlessNode { inner })
+    }
+
+    fn set_parent(&self, parent: Option<&HeadlessNode>) {
+        *self.inner.parent.borrow_mut() =
+            parent.map_or_else(Weak::new, |parent| Rc::downgrade(&parent.inner));
+    }
+
+    /// create an empty fragment
+    pub(crate) fn fragment() -> Self {
+        Self::new(HeadlessInner::Fragment { children: vec![] })
+    }
+
+    /// returns true if this an element node
+    pub fn is_element(&self) -> bool {
+        matches!(&*self.inner.node.borrow(), HeadlessInner::Element { .. })
+    }
+
+    /// returns true if this a text node
+    pub fn is_text_node(&self) -> bool {
+        matches!(&*self.inner.node.borrow(), HeadlessInner::Text(_))
+    }
+
+    /// returns true if this a fragment node
+    pub fn is_fragment(&self) -> bool {
+        matches!(&*self.inner.node.borrow(), HeadlessInner::Fragment { .. })
+    }
+
+    /// returns true if this is a portal node
+    pub fn is_portal(&self) -> bool {
+        matches!(&*self.inner.node.borrow(), HeadlessInner::Portal { .. })
+    }
+
+    /// returns true if this is a stateful component
+    pub fn is_stateful_component(&self) -> bool {
+        matches!(
+            &*self.inner.node.borrow(),
+            HeadlessInner::StatefulComponent { .. }
+        )
+    }
+
+    /// return the tag of this element
+    pub fn tag(&self) -> Option<String> {
+        match &*self.inner.node.borrow() {
+            HeadlessInner::Element { tag, .. } => Some(tag.clone()),
+            _ => None,
+        }
+    }
+
+    /// return the child nodes of this element or fragment,
+    /// the children of the portal which are in its host element,
+    /// or the external children of the stateful component
+    pub fn children(&self) -> Vec<HeadlessNode> {
+        match &*self.inner.node.borrow() {
+            HeadlessInner::Element { children, .. }
+            | HeadlessInner::Fragment { children }
+            | HeadlessInner::StatefulComponent { children, .. } => children.clone(),
+            HeadlessInner::Portal { host } => host.children(),
+            _ => vec![],
+        }
+    }
+
+    /// the element which holds the children of the portal
+    fn portal_host(&self) -> Option<HeadlessNode> {
+        match &*self.inner.node.borrow() {
+            HeadlessInner::Portal { host } => Some(host.clone()),
+            _ => None,
+        }
+    }
+
+    /// the host element of the stateful component, which has the attributes of the component
+    fn component_host(&self) -> Option<HeadlessNode> {
+        match &*self.inner.node.borrow() {
+            HeadlessInner::StatefulComponent { host, .. } => Some(host.clone()),
+            _ => None,
+        }
+    }
+
+    /// return the value of the attribute of this element
+    pub fn get_attribute(&self, name: &str) -> Option<String> {
+        if let Some(host) = self.component_host() {
+            return host.get_attribute(name);
+        }
+        match &*self.inner.node.borrow() {
+            HeadlessInner::Element { attributes, .. } => attributes.get(name).cloned(),
+            _ => None,
+        }
+    }
+
+    /// return all the attributes of this element
+    pub fn attributes(&self) -> Vec<(AttributeName, String)> {
+        if let Some(host) = self.component_host() {
+            return host.attributes();
+        }
+        match &*self.inner.node.borrow() {
+            HeadlessInner::Element { attributes, .. } => attributes
+                .iter()
+                .map(|(name, value)| (*name, value.clone()))
+                .collect(),
+            _ => vec![],
+        }
+    }
+
+    /// set the attribute of this element
+    pub fn set_attribute(&self, name: AttributeName, value: impl ToString) -> Result<(), Error> {
+        if let Some(host) = self.component_host() {
+            return host.set_attribute(name, value);
+        }
+        if let HeadlessInner::Element { attributes, .. } = &mut *self.inner.node.borrow_mut() {
+            attributes.insert(name, value.to_string());
+            return Ok(());
+        }
+        Err(Error::NotAnElement(format!("{self:?}")))
+    }
+
+    /// remove the attribute of this element
+    pub fn remove_attribute(&self, name: &str) {
+        if let Some(host) = self.component_host() {
+            return host.remove_attribute(name);
+        }
+        if let HeadlessInner::Element { attributes, .. } = &mut *self.inner.node.borrow_mut() {
+            attributes.shift_remove(name);
+        }
+    }
+
+    /// returns true if this element has a listener to the event
+    pub fn has_listener(&self, event_name: &str) -> bool {
+        !self.listeners(event_name).is_empty()
+    }
+
+    /// the listeners of this element to the event
+    fn listeners(&self, event_name: &str) -> Vec<HeadlessListener> {
+        if let Some(host) = self.component_host() {
+            return host.listeners(event_name);
+        }
+        match &*self.inner.node.borrow() {
+            HeadlessInner::Element { listeners, .. } => {
+                listeners.get(event_name).cloned().unwrap_or_default()
+            }
+            _ => vec![],
+        }
+    }
+
+    /// return the text content of this node and all its descendants
+    pub fn text_content(&self) -> String {
+        match &*self.inner.node.borrow() {
+            HeadlessInner::Text(text) => text.clone(),
+            HeadlessInner::Symbol(symbol) => symbol.clone(),
+            HeadlessInner::Comment(_) | HeadlessInner::Portal { .. } => String::new(),
+            HeadlessInner::Element { children, .. } | HeadlessInner::Fragment { children } => {
+                children.iter().map(|c| c.text_content()).collect()
+            }
+            HeadlessInner::StatefulComponent { view, children, .. } => [view]
+                .into_iter()
+                .chain(children.iter())
+                .map(|c| c.text_content())
+                .collect(),
+        }
+    }
+
+    /// call the listeners of this element that listens to `event_name`,
+    /// then the listeners of its ancestors if the event bubbles.
+    ///
+    /// Note: the typed listeners such as `on_click` are passed a synthetic web_sys event,
+    /// which fields can not be read when running natively,
+    /// since there is no web_sys event outside of the browser.
+    pub fn dispatch_event(&self, event_name: &str) {
+        let bubbles = !NON_BUBBLING_EVENTS.contains(&event_name);
+        let mut current = Some(self.clone());
+        while let Some(node) = current {
+            // the listeners are cloned, since the listeners may update the program
+            // which then patches this same node
+            for listener in node.listeners(event_name) {
+                listener(Event::from(HeadlessEvent {
+                    name: event_name.to_string(),
+                    target: self.clone(),
+                }));
+            }
+            current = if bubbles { node.parent() } else { None };
+        }
+    }
+
+    /// traverse the children of this node using the path
+    pub fn find_node(&self, path: &mut TreePath) -> Option<HeadlessNode> {
+        if path.is_empty() {
+            Some(self.clone())
+        } else {
+            let idx = path.remove_first();
+            self.child_at(idx)?.find_node(path)
+        }
+    }
+
+    /// return the index of the child in the children of this node
+    fn child_index(&self, target_child: &HeadlessNode) -> Result<usize, Error> {
+        self.children()
+            .iter()
+            .position(|child| child == target_child)
+            .ok_or_else(|| Error::ChildNotFound(format!("{target_child:?}")))
+    }
+
+    /// mutate the children of this node, the parent of the children is updated accordingly
+    fn with_children<R>(&self, f: impl FnOnce(&mut Vec<HeadlessNode>) -> R) -> Result<R, Error> {
+        if let Some(host) = self.portal_host() {
+            return host.with_children(f);
+        }
+        let mut inner = self.inner.node.borrow_mut();
+        let (HeadlessInner::Element { children, .. }
+        | HeadlessInner::Fragment { children }
+        | HeadlessInner::StatefulComponent { children, .. }) = &mut *inner
+        else {
+            drop(inner);
+            return Err(Error::NotAnElement(format!("{self:?}")));
+        };
+        for child in children.iter() {
+            child.set_parent(None);
+        }
+        let result = f(children);
+        for child in children.iter() {
+            child.set_parent(Some(self));
+        }
+        Ok(result)
+    }
+
+    /// render this node into an html string represenation
+    pub fn render_to_string(&self) -> String {
+        let mut buffer = String::new();
+        self.render(&mut buffer).expect("must render");
+        buffer
+    }
+
+    fn render(&self, buffer: &mut dyn fmt::Write) -> fmt::Result {
+        match &*self.inner.node.borrow() {
+            HeadlessInner::Text(text) => write!(buffer, "{text}"),
+            HeadlessInner::Symbol(symbol) => write!(buffer, "{symbol}"),
+            HeadlessInner::Comment(comment) => write!(buffer, "<!--{comment}-->"),
+            HeadlessInner::Element {
+                tag,
+                attributes,
+                children,
+                ..
+            } => render_element(buffer, tag, attributes, children.iter()),
+            HeadlessInner::Fragment { children } => {
+                for child in children.iter() {
+                    child.render(buffer)?;
+                }
+                Ok(())
+            }
+            HeadlessInner::Portal { .. } => write!(buffer, "<!--portal-->"),
+            HeadlessInner::StatefulComponent {
+                host,
+                view,
+                children,
+                ..
+            } => {
+                let HeadlessInner::Element {
+                    tag, attributes, ..
+                } = &*host.inner.node.borrow()
+                else {
+                    return Err(fmt::Error);
+                };
+                render_element(
+                    buffer,
+                    tag,
+                    attributes,
+                    [view].into_iter().chain(children.iter()),
+                )
+            }
+        }
+    }
+}
+
+fn render_element<'a>(
+    buffer: &mut dyn fmt::Write,
+    tag: &str,
+    attributes: &IndexMap<AttributeName, String>,
+    children: impl Iterator<Item = &'a HeadlessNode>,
+) -> fmt::Result {
+    let is_self_closing = lookup::is_self_closing(tag);
+    write!(buffer, "<{tag}")?;
+    for (name, value) in attributes.iter() {
+        write!(buffer, " {name}=\"{value}\"")?;
+    }
+    if is_self_closing {
+        write!(buffer, "/>")?;
+    } else {
+        write!(buffer, ">")?;
+    }
+    for child in children {
+        child.render(buffer)?;
+    }
+    if !is_self_closing {
+        write!(buffer, "</{tag}>")?;
+    }
+    Ok(())
+}
+
+impl DomBackend for HeadlessNode {
+    type Attr = HeadlessAttr;
+
+    fn create_node<MSG, F>(node: &vdom::Node<MSG>, ev_callback: F) -> Result<Self, Error>
+    where
+        MSG: 'static,
+        F: Fn(MSG) + 'static + Clone,
+    {
+        Ok(HeadlessNode::create(node, ev_callback))
+    }
+
+    fn convert_attr<MSG, F>(attr: &Attribute<MSG>, ev_callback: F) -> HeadlessAttr
+    where
+        MSG: 'static,
+        F: Fn(MSG) + 'static + Clone,
+    {
+        let GroupedAttributeValues {
+            listeners: event_callbacks,
+            plain_values,
+            styles,
+            ..
+        } = Attribute::group_values(attr);
+        let listeners = event_callbacks
+            .into_iter()
+            .map(|event_callback| {
+                let event_callback = event_callback.clone();
+                let ev_callback = ev_callback.clone();
+                let listener: HeadlessListener = Rc::new(move |event: Event| {
+                    if let Some(msg) = event_callback.try_emit(event) {
+                        ev_callback(msg);
+                    }
+                });
+                listener
+            })
+            .collect();
+        HeadlessAttr {
+            name: attr.name,
+            value: Style::merge_to_string(styles).or_else(|| Value::merge_to_string(plain_values)),
+            listeners,
+        }
+    }
+
+    fn child_at(&self, index: usize) -> Option<Self> {
+        self.children().get(index).cloned()
+    }
+
+    fn tag(&self) -> Option<String> {
+        HeadlessNode::tag(self)
+    }
+
+    fn is_fragment(&self) -> bool {
+        HeadlessNode::is_fragment(self)
+    }
+
+    fn is_stateful_component(&self) -> bool {
+        HeadlessNode::is_stateful_component(self)
+    }
+
+    fn has_transition(&self) -> bool {
+        matches!(
+            &*self.inner.node.borrow(),
+            HeadlessInner::Element {
+                has_transition: true,
+                ..
+            }
+        )
+    }
+
+    fn append_children(&self, for_append: Vec<Self>) -> Result<(), Error> {
+        self.with_children(|children| children.extend(for_append))
+    }
+
+    fn insert_before(&self, target_element: &Self, for_insert: Vec<Self>) -> Result<(), Error> {
+        let target_index = self.child_index(target_element)?;
+        self.with_children(|children| {
+            children.splice(target_index..target_index, for_insert);
+        })
+    }
+
+    fn insert_after(&self, target_element: &Self, for_insert: Vec<Self>) -> Result<(), Error> {
+        let target_index = self.child_index(target_element)? + 1;
+        self.with_children(|children| {
+            children.splice(target_index..target_index, for_insert);
+        })
+    }
+
+    fn replace_child(&self, target_child: &Self, replacement: Self) -> Result<(), Error> {
+        let child_index = self.child_index(target_child)?;
+        self.with_children(|children| children[child_index] = replacement)
+    }
+
+    fn remove_children(&self, for_remove: &[&Self]) -> Result<(), Error> {
+        for child in for_remove {
+            self.child_index(child)?;
+        }
+        self.with_children(|children| children.retain(|child| !for_remove.contains(&child)))
+    }
+
+    /// there are no css transitions in the in-memory DOM,
+    /// so the leave transition ends right away
+    fn remove_children_with_transition(&self, for_remove: &[&Self]) -> Result<(), Error> {
+        self.remove_children(for_remove)
+    }
+
+    fn clear_children(&self) -> Result<(), Error> {
+        self.with_children(|children| children.clear())
+    }
+
+    fn set_attrs(&self, attrs: Vec<HeadlessAttr>) -> Result<(), Error> {
+        self.set_headless_attrs(attrs)
+    }
+
+    fn remove_attrs(&self, attrs: &[HeadlessAttr]) -> Result<(), Error> {
+        if let Some(host) = self.component_host() {
+            host.remove_attrs(attrs)?;
+            // the component is notified of the removed attributes, regardless of their values
+            return self.with_component(|comp| {
+                for attr in attrs {
+                    comp.borrow_mut().remove_attribute(attr.name);
+                }
+            });
+        }
+        let HeadlessInner::Element {
+            attributes,
+            listeners,
+            ..
+        } = &mut *self.inner.node.borrow_mut()
+        else {
+            return Err(Error::NotAnElement(format!("{self:?}")));
+        };
+        for attr in attrs {
+            if attr.listeners.is_empty() {
+                attributes.shift_remove(attr.name);
+            } else {
+                listeners.shift_remove(attr.name);
+            }
+        }
+        Ok(())
+    }
+
+    /// there are no css transitions in the in-memory DOM
+    fn enter_transition(&self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn render_to_string(&self) -> String {
+        HeadlessNode::render_to_string(self)
+    }
+}
+
+impl HeadlessNode {
+    /// set the attributes and the listeners of this element
+    fn set_headless_attrs(&self, attrs: Vec<HeadlessAttr>) -> Result<(), Error> {
+        if let Some(host) = self.component_host() {
+            let changed: Vec<DomAttr> = attrs.iter().map(HeadlessAttr::to_dom_attr).collect();
+            host.set_headless_attrs(attrs)?;
+            return self.with_component(|comp| {
+                for attr in changed {
+                    comp.borrow_mut().attribute_changed(attr);
+                }
+            });
+        }
+        let mut inner = self.inner.node.borrow_mut();
+        let HeadlessInner::Element {
+            attributes,
+            listeners,
+            ..
+        } = &mut *inner
+        else {
+            drop(inner);
+            return Err(Error::NotAnElement(format!("{self:?}")));
+        };
+        insert_attrs(attributes, listeners, attrs);
+        Ok(())
+    }
+
+    /// pass the stateful component to `f`, then update the view of the component
+    fn with_component(&self, f: impl FnOnce(&RefCell<dyn StatefulComponent>)) -> Result<(), Error> {
+        let (comp, update_view) = match &*self.inner.node.borrow() {
+            HeadlessInner::StatefulComponent {
+                comp, update_view, ..
+            } => (Rc::clone(comp), Rc::clone(update_view)),
+            _ => return Err(Error::NotAnElement(format!("{self:?}"))),
+        };
+        f(&comp);
+        update_view();
+        Ok(())
+    }
+}
+
+impl HeadlessAttr {
+    /// the attribute which is passed to the stateful component when its attributes are changed
+    fn to_dom_attr(&self) -> DomAttr {
+        DomAttr {
+            namespace: None,
+            name: self.name,
+            value: match &self.value {
+                Some(value) => vec![DomAttrValue::Simple(Value::from(value))],
+                None => vec![DomAttrValue::Empty],
+            },
+        }
+    }
+}
+
+/// insert the attributes and the listeners of an element
+fn insert_attrs(
+    attributes: &mut IndexMap<AttributeName, String>,
+    listeners: &mut IndexMap<AttributeName, Vec<HeadlessListener>>,
+    attrs: Vec<HeadlessAttr>,
+) {
+    for attr in attrs {
+        if !attr.listeners.is_empty() {
+            listeners.insert(attr.name, attr.listeners);
+        }
+        match attr.value {
+            Some(value) => attributes.insert(attr.name, value),
+            None => attributes.shift_remove(attr.name),
+        };
+    }
+}
+
+fn create_headless_leaf<MSG, F>(leaf: &Leaf<MSG>, ev_callback: F) -> HeadlessNode
+where
+    MSG: 'static,
+    F: Fn(MSG) + 'static + Clone,
+{
+    let create_nodes = |nodes: &[vdom::Node<MSG>]| -> Vec<HeadlessNode> {
+        nodes
+            .iter()
+            .map(|node| HeadlessNode::create(node, ev_callback.clone()))
+            .collect()
+    };
+    match leaf {
+        Leaf::Text(txt) => HeadlessNode::new(HeadlessInner::Text(txt.to_string())),
+        Leaf::Symbol(symbol) => HeadlessNode::new(HeadlessInner::Symbol(symbol.to_string())),
+        Leaf::Comment(comment) => HeadlessNode::new(HeadlessInner::Comment(comment.to_string())),
+        Leaf::Fragment(nodes) | Leaf::NodeList(nodes) => {
+            HeadlessNode::new_parent(HeadlessInner::Fragment {
+                children: create_nodes(nodes),
+            })
+        }
+        Leaf::Portal(Portal { children, .. }) => {
+            let host = HeadlessNode::new_parent(HeadlessInner::Element {
+                tag: "div".to_string(),
+                attributes: IndexMap::new(),
+                listeners: IndexMap::new(),
+                children: create_nodes(children),
+                has_transition: false,
+            });
+            HeadlessNode::new_parent(HeadlessInner::Portal { host })
+        }
+        Leaf::StatefulComponent(comp) => create_headless_component(comp, ev_callback),
+        Leaf::StatelessComponent(comp) => {
+            HeadlessNode::create(comp.view().unwrap_template_ref(), ev_callback)
+        }
+        Leaf::TemplatedView(view) => {
+            unreachable!("template view should not be created: {:#?}", view)
+        }
+        Leaf::DocType(_) => unreachable!("doc type is never converted"),
+    }
+}
+
+/// create the stateful component, which view is created and updated by its own program.
+///
+/// The external children of the component are placed after its view,
+/// since the components are only passed the `DomNode` of the browser DOM
+/// in [`StatefulComponent::append_children`] and [`StatefulComponent::slot_container`].
+fn create_headless_component<MSG, F>(comp: &StatefulModel<MSG>, ev_callback: F) -> HeadlessNode
+where
+    MSG: 'static,
+    F: Fn(MSG) + 'static + Clone,
+{
+    let host = HeadlessNode::create(
+        &crate::html::div(
+            [crate::html::attributes::class("component")]
+                .into_iter()
+                .chain(comp.attrs.clone()),
+            [],
+        ),
+        ev_callback.clone(),
+    );
+    for attr in comp.attrs.iter() {
+        let attr = HeadlessNode::convert_attr(attr, ev_callback.clone());
+        comp.comp.borrow_mut().attribute_changed(attr.to_dom_attr());
+    }
+    let (view, update_view) = (comp.create_headless)();
+    HeadlessNode::new_parent(HeadlessInner::StatefulComponent {
+        host,
+        view,
+        children: comp
+            .children
+            .iter()
+            .map(|child| HeadlessNode::create(child, ev_callback.clone()))
+            .collect(),
+        comp: Rc::clone(&comp.comp),
+        update_view,
+    })
+}
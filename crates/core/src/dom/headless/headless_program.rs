@@ -0,0 +1,286 @@
+use crate::{
+    dom::{
+        apply_dom_patches, component::ViewCache, convert_patches, Application, Cmd, DomBackend,
+        Error,
+    },
+    vdom::{self, diff},
+};
+use futures::{executor::LocalPool, task::LocalSpawnExt};
+use std::{
+    cell::{Ref, RefCell},
+    collections::VecDeque,
+    rc::{Rc, Weak},
+};
+
+use super::HeadlessNode;
+
+/// HeadlessProgram handle the lifecycle of the APP using an in-memory DOM.
+///
+/// The msgs are dispatched, the app is updated and the DOM is patched synchronously,
+/// while the Cmd returned from the app are executed in a local executor
+/// until there is no more progress that can be made.
+pub struct HeadlessProgram<APP>
+where
+    APP: Application,
+{
+    /// holds the user application
+    app: Rc<RefCell<APP>>,
+    /// the current vdom representation
+    current_vdom: Rc<RefCell<vdom::Node<APP::MSG>>>,
+    /// the in-memory DOM of the app view
+    root_node: Rc<RefCell<Option<HeadlessNode>>>,
+    /// the fragment which contains the root node
+    mount_node: Rc<RefCell<Option<HeadlessNode>>>,
+    /// The MSG that hasn't been applied to the APP yet
+    pending_msgs: Rc<RefCell<VecDeque<APP::MSG>>>,
+    /// executes the futures of the Cmd
+    executor: Rc<RefCell<LocalPool>>,
+    /// the views of the components in the previous view of the app
+    views: Rc<RefCell<ViewCache>>,
+}
+
+struct WeakHeadlessProgram<APP>
+where
+    APP: Application,
+{
+    app: Weak<RefCell<APP>>,
+    current_vdom: Weak<RefCell<vdom::Node<APP::MSG>>>,
+    root_node: Weak<RefCell<Option<HeadlessNode>>>,
+    mount_node: Weak<RefCell<Option<HeadlessNode>>>,
+    pending_msgs: Weak<RefCell<VecDeque<APP::MSG>>>,
+    executor: Weak<RefCell<LocalPool>>,
+    views: Weak<RefCell<ViewCache>>,
+}
+
+impl<APP> WeakHeadlessProgram<APP>
+where
+    APP: Application,
+{
+    fn upgrade(&self) -> Option<HeadlessProgram<APP>> {
+        Some(HeadlessProgram {
+            app: self.app.upgrade()?,
+            current_vdom: self.current_vdom.upgrade()?,
+            root_node: self.root_node.upgrade()?,
+            mount_node: self.mount_node.upgrade()?,
+            pending_msgs: self.pending_msgs.upgrade()?,
+            executor: self.executor.upgrade()?,
+            views: self.views.upgrade()?,
+        })
+    }
+}
+
+impl<APP> Clone for WeakHeadlessProgram<APP>
+where
+    APP: Application,
+{
+    fn clone(&self) -> Self {
+        WeakHeadlessProgram {
+            app: Weak::clone(&self.app),
+            current_vdom: Weak::clone(&self.current_vdom),
+            root_node: Weak::clone(&self.root_node),
+            mount_node: Weak::clone(&self.mount_node),
+            pending_msgs: Weak::clone(&self.pending_msgs),
+            executor: Weak::clone(&self.executor),
+            views: Weak::clone(&self.views),
+        }
+    }
+}
+
+impl<APP> Clone for HeadlessProgram<APP>
+where
+    APP: Application,
+{
+    fn clone(&self) -> Self {
+        HeadlessProgram {
+            app: Rc::clone(&self.app),
+            current_vdom: Rc::clone(&self.current_vdom),
+            root_node: Rc::clone(&self.root_node),
+            mount_node: Rc::clone(&self.mount_node),
+            pending_msgs: Rc::clone(&self.pending_msgs),
+            executor: Rc::clone(&self.executor),
+            views: Rc::clone(&self.views),
+        }
+    }
+}
+
+impl<APP> HeadlessProgram<APP>
+where
+    APP: Application,
+{
+    /// create the in-memory DOM of the app view, then execute the app init
+    pub fn new(app: APP) -> Self {
+        Self::from_rc_app(Rc::new(RefCell::new(app)))
+    }
+
+    /// create a program from Rc<RefCell<APP>>
+    pub fn from_rc_app(app: Rc<RefCell<APP>>) -> Self {
+        Self::create(app).0
+    }
+
+    /// create the view of a stateful component, which is updated by its own program,
+    /// along with the callback which updates the view after the component is changed.
+    pub(crate) fn create_component(app: Rc<RefCell<APP>>) -> (HeadlessNode, Rc<dyn Fn()>) {
+        let (program, mount_node) = Self::create(app);
+        let update_view = Rc::new(move || {
+            let mut program = program.clone();
+            if let Err(error) = program.update_dom() {
+                program.app.borrow_mut().on_error(&error);
+            }
+        });
+        (mount_node, update_view)
+    }
+
+    /// create the program and its in-memory DOM, which is contained in the returned fragment,
+    /// then execute the app init
+    fn create(app: Rc<RefCell<APP>>) -> (Self, HeadlessNode) {
+        let views = Rc::new(RefCell::new(ViewCache::default()));
+        let view = {
+            let _views = ViewCache::enter(&views);
+            app.borrow().view()
+        };
+        let mut program = HeadlessProgram {
+            app,
+            current_vdom: Rc::new(RefCell::new(view)),
+            root_node: Rc::new(RefCell::new(None)),
+            mount_node: Rc::new(RefCell::new(None)),
+            pending_msgs: Rc::new(RefCell::new(VecDeque::new())),
+            executor: Rc::new(RefCell::new(LocalPool::new())),
+            views,
+        };
+        let root_node = HeadlessNode::create(
+            program.current_vdom.borrow().unwrap_template_ref(),
+            program.create_ev_callback(),
+        );
+        let mount_node = HeadlessNode::fragment();
+        if let Err(error) = mount_node.append_children(vec![root_node.clone()]) {
+            program.app.borrow_mut().on_error(&error);
+        }
+        *program.root_node.borrow_mut() = Some(root_node);
+        *program.mount_node.borrow_mut() = Some(mount_node.clone());
+
+        let cmd = program.app.borrow_mut().init();
+        program.spawn_cmd(cmd);
+        program.dispatch_pending_msgs();
+        (program, mount_node)
+    }
+
+    fn downgrade(&self) -> WeakHeadlessProgram<APP> {
+        WeakHeadlessProgram {
+            app: Rc::downgrade(&self.app),
+            current_vdom: Rc::downgrade(&self.current_vdom),
+            root_node: Rc::downgrade(&self.root_node),
+            mount_node: Rc::downgrade(&self.mount_node),
+            pending_msgs: Rc::downgrade(&self.pending_msgs),
+            executor: Rc::downgrade(&self.executor),
+            views: Rc::downgrade(&self.views),
+        }
+    }
+
+    fn create_ev_callback(&self) -> impl Fn(APP::MSG) + Clone {
+        let program = self.downgrade();
+        move |msg| {
+            if let Some(mut program) = program.upgrade() {
+                program.dispatch(msg);
+            }
+        }
+    }
+
+    /// returns a reference to the app
+    pub fn app(&self) -> Ref<'_, APP> {
+        self.app.borrow()
+    }
+
+    /// return the root node of the in-memory DOM
+    pub fn root_node(&self) -> Result<HeadlessNode, Error> {
+        self.root_node.borrow().clone().ok_or(Error::NotMounted)
+    }
+
+    /// render the in-memory DOM into an html string
+    pub fn render_to_string(&self) -> String {
+        self.root_node
+            .borrow()
+            .as_ref()
+            .map(|root_node| root_node.render_to_string())
+            .unwrap_or_default()
+    }
+
+    /// dispatch multiple MSG
+    pub fn dispatch_multiple(&mut self, msgs: impl IntoIterator<Item = APP::MSG>) {
+        self.pending_msgs.borrow_mut().extend(msgs);
+        self.dispatch_pending_msgs();
+    }
+
+    /// dispatch a single msg
+    pub fn dispatch(&mut self, msg: APP::MSG) {
+        self.dispatch_multiple([msg])
+    }
+
+    /// update the app with each of the pending msgs, then update the DOM.
+    /// This is repeated until the Cmd executed no longer produces msgs.
+    fn dispatch_pending_msgs(&mut self) {
+        loop {
+            let pending_msg = self.pending_msgs.borrow_mut().pop_front();
+            if let Some(pending_msg) = pending_msg {
+                let cmd = self.app.borrow_mut().update(pending_msg);
+                self.spawn_cmd(cmd);
+                continue;
+            }
+            if let Err(error) = self.update_dom() {
+                self.app.borrow_mut().on_error(&error);
+            }
+            self.executor.borrow_mut().run_until_stalled();
+            if self.pending_msgs.borrow().is_empty() {
+                break;
+            }
+        }
+    }
+
+    /// spawn the commands into the executor, the resulting msgs are put into the pending msgs
+    fn spawn_cmd(&self, cmd: Cmd<APP::MSG>) {
+        let spawner = self.executor.borrow().spawner();
+        for mut command in cmd.commands.into_iter() {
+            let pending_msgs = Rc::downgrade(&self.pending_msgs);
+            let spawned = spawner.spawn_local(async move {
+                while let Some(msg) = command.next().await {
+                    if let Some(pending_msgs) = pending_msgs.upgrade() {
+                        pending_msgs.borrow_mut().push_back(msg);
+                    }
+                }
+            });
+            if let Err(error) = spawned {
+                log::error!("unable to spawn the cmd: {error}");
+            }
+        }
+    }
+
+    /// patch the in-memory DOM to reflect the App's view
+    pub fn update_dom(&mut self) -> Result<(), Error> {
+        let view = {
+            let _views = ViewCache::enter(&self.views);
+            self.app.borrow().view()
+        };
+        self.update_dom_with_vdom(view)?;
+        Ok(())
+    }
+
+    /// patch the in-memory DOM with the new vdom, returns the number of patches applied
+    pub fn update_dom_with_vdom(&mut self, new_vdom: vdom::Node<APP::MSG>) -> Result<usize, Error> {
+        let dom_patches = {
+            let current_vdom = self.current_vdom.borrow();
+            let patches = diff(&current_vdom, &new_vdom);
+            convert_patches(
+                self.root_node.borrow().as_ref().ok_or(Error::NotMounted)?,
+                &patches,
+                self.create_ev_callback(),
+            )?
+        };
+        let total_patches = dom_patches.len();
+        apply_dom_patches(
+            Rc::clone(&self.root_node),
+            Rc::clone(&self.mount_node),
+            dom_patches,
+        )?;
+        *self.current_vdom.borrow_mut() = new_vdom;
+        Ok(total_patches)
+    }
+}
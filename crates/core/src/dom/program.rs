@@ -8,38 +8,39 @@ use std::{
     rc::Weak,
 };
 
-use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen::JsCast;
 
 use crate::{
     dom::{
-        document, dom_patch, now, program::app_context::WeakContext, util::body,
-        AnimationFrameHandle, Application, DomNode, DomPatch, IdleCallbackHandle, IdleDeadline,
-        Measurements, SkipDiff, SkipPath,
+        component::ViewCache,
+        delegation::{self, DelegationScope, RootHandle},
+        document, dom_patch, now,
+        program::app_context::WeakContext,
+        style_registry::{ComponentChanges, ComponentStyles, DynamicStyle},
+        util::body,
+        AnimationFrameHandle, Application, Context, DomNode, DomPatch, Error, IdleCallbackHandle,
+        IdleDeadline, Measurements, SkipDiff, SkipPath,
     },
-    html::{self, attributes::class, text},
+    html::{self, text},
     vdom::{self, diff, diff_recursive, Patch},
 };
 
-#[cfg(feature = "with-raf")]
-use crate::dom::request_animation_frame;
-
-#[cfg(feature = "with-ric")]
-use crate::dom::request_idle_callback;
-
-thread_local! {
-    static CANCEL_CNT: RefCell<i32> = RefCell::new(0);
-}
-
-thread_local! {
-    static UPDATE_CNT: RefCell<i32> = RefCell::new(0);
-}
+use crate::dom::{
+    request_animation_frame, request_idle_callback, request_timeout_callback, TimeoutCallbackHandle,
+};
 
 mod app_context;
 use self::app_context::AppContext;
 
+mod middleware;
+pub use self::middleware::Middleware;
+
 mod mount_procedure;
 pub use self::mount_procedure::{MountAction, MountProcedure, MountTarget};
 
+mod scheduler;
+pub use self::scheduler::{SchedulerConfig, UpdateStrategy};
+
 /// Program handle the lifecycle of the APP
 pub struct Program<APP>
 where
@@ -66,6 +67,24 @@ where
 
     /// keep track of the time when the dom is last updated
     pub(crate) last_update: Rc<RefCell<Option<f64>>>,
+
+    /// how the msgs are dispatched and the dom is updated
+    pub(crate) scheduler_config: Rc<RefCell<SchedulerConfig>>,
+
+    /// the postponed update of the dom when debouncing
+    pub(crate) debounce_handle: Rc<RefCell<Option<TimeoutCallbackHandle>>>,
+
+    /// whether the event listeners are delegated to the mount node
+    pub(crate) event_delegation: Rc<RefCell<bool>>,
+
+    /// the stylesheets of the components in the current view
+    pub(crate) component_styles: Rc<RefCell<ComponentStyles>>,
+
+    /// the style element of the dynamic style of the app
+    pub(crate) dynamic_style: Rc<RefCell<DynamicStyle>>,
+
+    /// the use of the delegation root where the events of this program are listened to
+    pub(crate) delegation_root: Rc<RefCell<Option<RootHandle>>>,
 }
 
 pub struct WeakProgram<APP>
@@ -79,6 +98,12 @@ where
     idle_callback_handles: Weak<RefCell<Vec<IdleCallbackHandle>>>,
     animation_frame_handles: Weak<RefCell<Vec<AnimationFrameHandle>>>,
     last_update: Weak<RefCell<Option<f64>>>,
+    scheduler_config: Weak<RefCell<SchedulerConfig>>,
+    debounce_handle: Weak<RefCell<Option<TimeoutCallbackHandle>>>,
+    event_delegation: Weak<RefCell<bool>>,
+    component_styles: Weak<RefCell<ComponentStyles>>,
+    dynamic_style: Weak<RefCell<DynamicStyle>>,
+    delegation_root: Weak<RefCell<Option<RootHandle>>>,
 }
 
 impl<APP> WeakProgram<APP>
@@ -94,6 +119,12 @@ where
         let idle_callback_handles = self.idle_callback_handles.upgrade()?;
         let animation_frame_handles = self.animation_frame_handles.upgrade()?;
         let last_update = self.last_update.upgrade()?;
+        let scheduler_config = self.scheduler_config.upgrade()?;
+        let debounce_handle = self.debounce_handle.upgrade()?;
+        let event_delegation = self.event_delegation.upgrade()?;
+        let component_styles = self.component_styles.upgrade()?;
+        let dynamic_style = self.dynamic_style.upgrade()?;
+        let delegation_root = self.delegation_root.upgrade()?;
         Some(Program {
             app_context,
             root_node,
@@ -102,6 +133,12 @@ where
             idle_callback_handles,
             animation_frame_handles,
             last_update,
+            scheduler_config,
+            debounce_handle,
+            event_delegation,
+            component_styles,
+            dynamic_style,
+            delegation_root,
         })
     }
 }
@@ -119,6 +156,12 @@ where
             idle_callback_handles: Weak::clone(&self.idle_callback_handles),
             animation_frame_handles: Weak::clone(&self.animation_frame_handles),
             last_update: Weak::clone(&self.last_update),
+            scheduler_config: Weak::clone(&self.scheduler_config),
+            debounce_handle: Weak::clone(&self.debounce_handle),
+            event_delegation: Weak::clone(&self.event_delegation),
+            component_styles: Weak::clone(&self.component_styles),
+            dynamic_style: Weak::clone(&self.dynamic_style),
+            delegation_root: Weak::clone(&self.delegation_root),
         }
     }
 }
@@ -137,6 +180,12 @@ where
             idle_callback_handles: Rc::downgrade(&self.idle_callback_handles),
             animation_frame_handles: Rc::downgrade(&self.animation_frame_handles),
             last_update: Rc::downgrade(&self.last_update),
+            scheduler_config: Rc::downgrade(&self.scheduler_config),
+            debounce_handle: Rc::downgrade(&self.debounce_handle),
+            event_delegation: Rc::downgrade(&self.event_delegation),
+            component_styles: Rc::downgrade(&self.component_styles),
+            dynamic_style: Rc::downgrade(&self.dynamic_style),
+            delegation_root: Rc::downgrade(&self.delegation_root),
         }
     }
 }
@@ -154,6 +203,12 @@ where
             idle_callback_handles: Rc::clone(&self.idle_callback_handles),
             animation_frame_handles: Rc::clone(&self.animation_frame_handles),
             last_update: Rc::clone(&self.last_update),
+            scheduler_config: Rc::clone(&self.scheduler_config),
+            debounce_handle: Rc::clone(&self.debounce_handle),
+            event_delegation: Rc::clone(&self.event_delegation),
+            component_styles: Rc::clone(&self.component_styles),
+            dynamic_style: Rc::clone(&self.dynamic_style),
+            delegation_root: Rc::clone(&self.delegation_root),
         }
     }
 }
@@ -171,6 +226,19 @@ where
     pub fn app_mut(&self) -> RefMut<'_, APP> {
         self.app_context.app.borrow_mut()
     }
+
+    /// register a middleware which hooks are called as the msgs are dispatched to the APP
+    /// and the DOM is patched, the middlewares are called in the order they are added.
+    pub fn add_middleware(&self, middleware: impl Middleware<APP> + 'static) {
+        self.app_context.add_middleware(middleware);
+    }
+
+    /// register a middleware, this is meant to be called before the program is mounted
+    /// so the middleware also observes the Cmd returned by the app `init`
+    pub fn with_middleware(self, middleware: impl Middleware<APP> + 'static) -> Self {
+        self.add_middleware(middleware);
+        self
+    }
 }
 
 impl<APP> Program<APP>
@@ -185,13 +253,20 @@ where
 
     /// create a program from Rc<RefCell<APP>>
     pub fn from_rc_app(app: Rc<RefCell<APP>>) -> Self {
-        let app_view = app.borrow().view();
+        let views = Rc::new(RefCell::new(ViewCache::default()));
+        let app_view = {
+            let _views = ViewCache::enter(&views);
+            app.borrow().view()
+        };
         Program {
             app_context: AppContext {
                 app,
                 current_vdom: Rc::new(RefCell::new(app_view)),
                 pending_msgs: Rc::new(RefCell::new(VecDeque::new())),
                 pending_dispatches: Rc::new(RefCell::new(VecDeque::new())),
+                middlewares: Rc::new(RefCell::new(vec![])),
+                context: Rc::new(RefCell::new(Context::current())),
+                views,
             },
             root_node: Rc::new(RefCell::new(None)),
             mount_node: Rc::new(RefCell::new(None)),
@@ -199,11 +274,59 @@ where
             idle_callback_handles: Rc::new(RefCell::new(vec![])),
             animation_frame_handles: Rc::new(RefCell::new(vec![])),
             last_update: Rc::new(RefCell::new(None)),
+            scheduler_config: Rc::new(RefCell::new(SchedulerConfig::default())),
+            debounce_handle: Rc::new(RefCell::new(None)),
+            event_delegation: Rc::new(RefCell::new(false)),
+            component_styles: Rc::new(RefCell::new(ComponentStyles::default())),
+            dynamic_style: Rc::new(RefCell::new(DynamicStyle::default())),
+            delegation_root: Rc::new(RefCell::new(None)),
         }
     }
 
+    /// use the scheduler config for dispatching the msgs and updating the dom,
+    /// this is meant to be called before the program is mounted
+    pub fn with_scheduler(self, scheduler_config: SchedulerConfig) -> Self {
+        self.set_scheduler(scheduler_config);
+        self
+    }
+
+    /// change the scheduler config used for the succeeding dispatches
+    pub fn set_scheduler(&self, scheduler_config: SchedulerConfig) {
+        *self.scheduler_config.borrow_mut() = scheduler_config;
+    }
+
+    /// returns the current scheduler config
+    pub fn scheduler(&self) -> SchedulerConfig {
+        *self.scheduler_config.borrow()
+    }
+
+    /// Instead of attaching a closure to each element for each of its event listeners,
+    /// use a single listener for each event type on the mount node, which dispatches the event
+    /// to the listeners of the target element and its ancestors.
+    /// This uses less memory and speeds up the creation of views with a lot of event listeners,
+    /// ie: large lists.
+    ///
+    /// Note: the `current_target` of the events is the mount node and not the element
+    /// where the listener is set.
+    /// This is meant to be called before the program is mounted
+    pub fn with_event_delegation(self) -> Self {
+        *self.event_delegation.borrow_mut() = true;
+        self
+    }
+
+    /// returns true if the event listeners are delegated to the mount node
+    pub fn is_event_delegated(&self) -> bool {
+        *self.event_delegation.borrow()
+    }
+
+    /// the event listeners created while the returned scope is alive
+    /// are delegated if the event delegation is enabled for this program
+    pub(crate) fn delegation_scope(&self) -> DelegationScope {
+        DelegationScope::new(self.is_event_delegated())
+    }
+
     /// executed after the program has been mounted
-    fn after_mounted(&mut self) {
+    fn after_mounted(&mut self) -> Result<(), Error> {
         // call the init of the component
         let init_cmd = self.app_context.init_app();
 
@@ -214,11 +337,12 @@ where
         init_cmd.emit(self.clone());
 
         // inject the app's dynamic style after the emitting the init function and it's effects
-        self.inject_dynamic_style();
+        self.update_dynamic_style()?;
 
         // first dispatch call to ensure the template is patched with the
         // new app real view
         //self.dispatch_multiple([]);
+        Ok(())
     }
 
     fn app_hash() -> u64 {
@@ -228,20 +352,35 @@ where
         hasher.finish()
     }
 
-    fn inject_stylesheet(&mut self) {
-        let static_style = self.app_context.static_style();
-        if !static_style.is_empty() {
-            let class_names = format!("static {}", Self::app_hash());
-            self.inject_style(class_names, &static_style);
-        }
+    /// register the stylesheet of the app, which is injected once
+    /// into the document head or the shadow root where the app is mounted
+    fn inject_stylesheet(&mut self) -> Result<(), Error> {
+        let container = self.style_container()?;
+        self.component_styles.borrow_mut().mount(
+            container.as_node(),
+            TypeId::of::<APP>(),
+            APP::stylesheet,
+        )
+    }
+
+    /// register the stylesheets of the components in the current view,
+    /// and remove the stylesheets of the components which are gone
+    fn update_component_styles(&self) -> Result<(), Error> {
+        self.component_styles
+            .borrow_mut()
+            .update(&self.app_context.current_vdom())
     }
 
-    fn inject_dynamic_style(&mut self) {
+    /// inject the dynamic style of the app, the style element is updated in place
+    /// when the style is changed after an update
+    fn update_dynamic_style(&self) -> Result<(), Error> {
         let dynamic_style = self.app_context.dynamic_style();
-        if !dynamic_style.is_empty() {
-            let class_names = format!("dynamic {}", Self::app_hash());
-            self.inject_style(class_names, &dynamic_style);
-        }
+        let class_names = format!("dynamic {}", Self::app_hash());
+        self.dynamic_style.borrow_mut().update(
+            &self.style_container()?.as_node(),
+            &class_names,
+            dynamic_style,
+        )
     }
 
     ///  Instantiage an app and append the view to the root_node
@@ -263,7 +402,7 @@ where
     /// ```
     pub fn append_to_mount(app: APP, mount_node: &web_sys::Node) -> ManuallyDrop<Self> {
         let mut program = Self::new(app);
-        program.mount(mount_node, MountProcedure::append());
+        program.mount_or_report(mount_node, MountProcedure::append());
         ManuallyDrop::new(program)
     }
 
@@ -286,14 +425,14 @@ where
     /// ```
     pub fn replace_mount(app: APP, mount_node: &web_sys::Node) -> ManuallyDrop<Self> {
         let mut program = Self::new(app);
-        program.mount(mount_node, MountProcedure::replace());
+        program.mount_or_report(mount_node, MountProcedure::replace());
         ManuallyDrop::new(program)
     }
 
     /// clear the existing children of the mount before mounting the app
     pub fn clear_append_to_mount(app: APP, mount_node: &web_sys::Node) -> ManuallyDrop<Self> {
         let mut program = Self::new(app);
-        program.mount(mount_node, MountProcedure::clear_append());
+        program.mount_or_report(mount_node, MountProcedure::clear_append());
         ManuallyDrop::new(program)
     }
 
@@ -328,14 +467,14 @@ where
     }
 
     /// executed right before the app is mounted to the dom
-    pub fn pre_mount(&mut self) {
-        self.inject_stylesheet();
+    pub fn pre_mount(&mut self) -> Result<(), Error> {
+        self.inject_stylesheet()
     }
 
     #[allow(unused)]
     /// create initial dom node generated
     /// from template and patched by the difference of vdom_template and current app view.
-    pub(crate) fn create_initial_view(&self) -> DomNode {
+    pub(crate) fn create_initial_view(&self) -> Result<DomNode, Error> {
         let current_view = self.app_context.current_vdom();
         let real_view = current_view.unwrap_template_ref();
         self.create_dom_node(real_view)
@@ -343,69 +482,102 @@ where
 
     /// each element and it's descendant in the vdom is created into
     /// an actual DOM node.
-    pub fn mount(&mut self, mount_node: &web_sys::Node, mount_procedure: MountProcedure) {
+    pub fn mount(
+        &mut self,
+        mount_node: &web_sys::Node,
+        mount_procedure: MountProcedure,
+    ) -> Result<(), Error> {
         let mount_node = DomNode::from(mount_node.clone());
-        *self.mount_node.borrow_mut() = Some(mount_node);
-        self.pre_mount();
-
-        let created_node = self.create_initial_view();
+        *self.mount_node.borrow_mut() = Some(mount_node.clone());
 
         let mount_node: DomNode = match mount_procedure.target {
-            MountTarget::MountNode => self
-                .mount_node
-                .borrow()
-                .as_ref()
-                .expect("mount node")
-                .clone(),
+            MountTarget::MountNode => mount_node,
             MountTarget::ShadowRoot => {
-                let mount_element: web_sys::Element = self
-                    .mount_node
-                    .borrow()
-                    .as_ref()
-                    .expect("mount node")
-                    .as_element();
-
-                mount_element
-                    .attach_shadow(&web_sys::ShadowRootInit::new(web_sys::ShadowRootMode::Open))
-                    .expect("unable to attached shadow");
-                let mount_shadow = mount_element.shadow_root().expect("must have a shadow");
+                let mount_element: web_sys::Element = mount_node.as_element();
+
+                // the shadow root is reused when the app is mounted again, ie: in a custom element
+                let mount_shadow = match mount_element.shadow_root() {
+                    Some(mount_shadow) => mount_shadow,
+                    None => mount_element
+                        .attach_shadow(&web_sys::ShadowRootInit::new(web_sys::ShadowRootMode::Open))
+                        .map_err(Error::AttachShadow)?,
+                };
                 let shadow_node: web_sys::Node = mount_shadow.unchecked_into();
+                let shadow_node = DomNode::from(shadow_node);
 
-                *self.mount_node.borrow_mut() = Some(DomNode::from(shadow_node));
-                self.mount_node
-                    .borrow()
-                    .as_ref()
-                    .expect("mount_node")
-                    .clone()
+                *self.mount_node.borrow_mut() = Some(shadow_node.clone());
+                shadow_node
             }
         };
+        // the styles are injected after the shadow root is attached, since it is where they apply
+        self.pre_mount()?;
+
+        let created_node = self.create_initial_view()?;
+        self.update_component_styles()?;
+
+        if self.is_event_delegated() {
+            // the mount node itself is replaced, so the events are listened to in its parent
+            let delegation_root = match mount_procedure.action {
+                MountAction::Replace => {
+                    mount_node.as_node().parent_node().ok_or(Error::NoParent)?
+                }
+                _ => mount_node.as_node(),
+            };
+            *self.delegation_root.borrow_mut() = Some(delegation::add_root(&delegation_root));
+        }
 
         match mount_procedure.action {
             MountAction::Append => {
-                mount_node.append_children(vec![created_node.clone()]);
+                mount_node.append_children(vec![created_node.clone()])?;
             }
             MountAction::ClearAppend => {
-                mount_node.clear_children();
-                mount_node.append_children(vec![created_node.clone()]);
+                mount_node.clear_children()?;
+                mount_node.append_children(vec![created_node.clone()])?;
             }
             MountAction::Replace => {
-                mount_node.replace_node(created_node.clone());
+                mount_node.replace_node(created_node.clone())?;
             }
         }
         *self.root_node.borrow_mut() = Some(created_node);
-        self.after_mounted();
+        self.after_mounted()
     }
 
-    #[cfg(feature = "with-ric")]
-    fn dispatch_pending_msgs_with_ric(&mut self) -> Result<(), JsValue> {
-        let program = Program::downgrade(&self);
+    /// remove the view of the app from the DOM, the program can be dropped afterwards
+    pub(crate) fn unmount(&self) -> Result<(), Error> {
+        let Some(root_node) = self.root_node.borrow_mut().take() else {
+            return Ok(());
+        };
+        if root_node.is_fragment() {
+            // the children of the fragment are directly in the mount node
+            return root_node.clear_children();
+        }
+        let root = root_node.as_node();
+        if let Some(parent) = root.parent_node() {
+            parent.remove_child(&root)?;
+        }
+        Ok(())
+    }
+
+    /// mount the program, any error is reported to the app through [`Application::on_error`]
+    pub(crate) fn mount_or_report(
+        &mut self,
+        mount_node: &web_sys::Node,
+        mount_procedure: MountProcedure,
+    ) {
+        if let Err(error) = self.mount(mount_node, mount_procedure) {
+            self.app_mut().on_error(&error);
+        }
+    }
+
+    fn dispatch_pending_msgs_with_ric(&mut self) -> Result<(), Error> {
+        let program = Program::downgrade(self);
         let handle = request_idle_callback(move |deadline| {
-            let mut program = program.upgrade().expect("must upgrade");
-            program
-                .dispatch_pending_msgs(Some(deadline))
-                .expect("must execute")
-        })
-        .expect("must execute");
+            if let Some(mut program) = program.upgrade() {
+                if let Err(error) = program.dispatch_pending_msgs(Some(deadline)) {
+                    program.recover_from_error(error);
+                }
+            }
+        })?;
         self.idle_callback_handles.borrow_mut().push(handle);
         Ok(())
     }
@@ -413,7 +585,7 @@ where
     /// executes pending msgs by calling the app update method with the msgs
     /// as parameters.
     /// If there is no deadline specified all the pending messages are executed
-    fn dispatch_pending_msgs(&mut self, deadline: Option<IdleDeadline>) -> Result<(), JsValue> {
+    fn dispatch_pending_msgs(&mut self, deadline: Option<IdleDeadline>) -> Result<(), Error> {
         if !self.app_context.has_pending_msgs() {
             return Ok(());
         }
@@ -428,45 +600,46 @@ where
             }
         }
         if !did_complete {
-            log::info!("did not complete pending msgs in time.. dispatching the rest");
-            #[cfg(feature = "with-ric")]
-            self.dispatch_pending_msgs_with_ric()
-                .expect("must complete");
+            self.dispatch_pending_msgs_with_ric()?;
         }
         Ok(())
     }
 
-    /// execute DOM changes in order to reflect the APP's view into the browser representation
-    pub fn update_dom(&mut self) -> Result<(), JsValue> {
-        let t1 = now();
-        //#[cfg(all(feature = "with-measure", feature = "with-debug"))]
-        if let Some(last_update) = self.last_update.borrow().as_ref() {
-            let frame_time = (1000_f64 / 60_f64).floor(); // 1s in 60 frames
-            let time_delta = t1 - last_update;
-            let remaining = frame_time - time_delta;
-            if time_delta < frame_time {
-                log::warn!("update is {remaining} too soon!... time_delta: {time_delta}, frame_time: {frame_time}");
-                let mut program = self.clone();
-                //#[cfg(feature = "with-debounce")]
-                crate::dom::request_timeout_callback(
-                    move || {
-                        program.update_dom().unwrap();
-                    },
-                    remaining.round() as i32,
-                )
-                .unwrap();
-                log::info!("update is cancelled..");
-                CANCEL_CNT.with_borrow_mut(|c| *c += 1);
-                return Ok(());
+    /// execute DOM changes in order to reflect the APP's view into the browser representation.
+    ///
+    /// When debounce is enabled in the scheduler config and the last update of the DOM
+    /// is within the frame budget, the update is postponed until the frame budget has elapsed.
+    pub fn update_dom(&mut self) -> Result<(), Error> {
+        let scheduler_config = self.scheduler();
+        if scheduler_config.debounce {
+            if let Some(last_update) = *self.last_update.borrow() {
+                let remaining = scheduler_config.frame_budget - (now() - last_update);
+                if remaining > 0.0 {
+                    let program = Program::downgrade(self);
+                    let handle = request_timeout_callback(
+                        move || {
+                            if let Some(mut program) = program.upgrade() {
+                                if let Err(error) = program.update_dom_now() {
+                                    program.recover_from_error(error);
+                                }
+                            }
+                        },
+                        remaining.ceil() as i32,
+                    )?;
+                    // replacing the handle cancels the previously postponed update
+                    *self.debounce_handle.borrow_mut() = Some(handle);
+                    return Ok(());
+                }
             }
         }
-        log::info!("Doing and update...");
-        UPDATE_CNT.with_borrow_mut(|c| *c += 1);
-        log::info!(
-            "ratio(cancelled/update): {}/{}",
-            CANCEL_CNT.with_borrow(|c| *c),
-            UPDATE_CNT.with_borrow(|c| *c)
-        );
+        self.update_dom_now()
+    }
+
+    /// update the DOM right away, regardless of the scheduler config
+    fn update_dom_now(&mut self) -> Result<(), Error> {
+        // the postponed update is no longer needed
+        self.debounce_handle.borrow_mut().take();
+        let t1 = now();
         // a new view is created due to the app update
         let view = self.app_context.view();
         let t2 = now();
@@ -474,41 +647,48 @@ where
         let node_count = view.node_count();
         let skip_diff = view.skip_diff();
 
-        let dom_patches = if let Some(skip_diff) = skip_diff {
+        let (dom_patches, component_changes) = {
             let current_vdom = self.app_context.current_vdom();
             let real_current_vdom = current_vdom.unwrap_template_ref();
             let real_view = view.unwrap_template_ref();
-            let patches =
-                self.create_patches_with_skip_diff(real_current_vdom, real_view, &skip_diff);
+            let patches = if let Some(skip_diff) = skip_diff {
+                self.create_patches_with_skip_diff(real_current_vdom, real_view, &skip_diff)
+            } else {
+                diff(real_current_vdom, real_view)
+            };
             #[cfg(all(feature = "with-debug", feature = "log-patches"))]
             {
                 log::info!("There are {} patches", patches.len());
                 log::info!("patches: {patches:#?}");
             }
-            self.convert_patches(
-                self.root_node
-                    .borrow()
-                    .as_ref()
-                    .expect("must have a root node"),
+            // only the components in the nodes which are changed by the patches are counted
+            let component_changes =
+                ComponentChanges::from_patches(real_current_vdom, real_view, &patches);
+            let dom_patches = self.convert_patches(
+                self.root_node.borrow().as_ref().ok_or(Error::NotMounted)?,
                 &patches,
-            )
-            .expect("must convert patches")
-        } else {
-            let current_vdom = self.app_context.current_vdom();
-            create_dom_patch(
-                &self.root_node,
-                &current_vdom,
-                &view,
-                self.create_ev_callback(),
-            )
+            )?;
+            (dom_patches, component_changes)
         };
 
         let total_patches = dom_patches.len();
 
+        for middleware in self.app_context.middlewares.borrow_mut().iter_mut() {
+            middleware.on_patches(&dom_patches);
+        }
+
         // update the last DOM node tree with this new view
-        self.queue_dom_patches(dom_patches).expect("must not error");
+        self.queue_dom_patches(dom_patches)?;
         // set the current dom
         self.app_context.set_current_dom(view);
+        match component_changes {
+            Some(component_changes) => self
+                .component_styles
+                .borrow_mut()
+                .apply(component_changes)?,
+            None => self.update_component_styles()?,
+        }
+        self.update_dynamic_style()?;
         let t3 = now();
 
         let strong_count = self.app_context.strong_count();
@@ -541,16 +721,14 @@ where
     /// patch the DOM to reflect the App's view
     ///
     /// Note: This is in another function so as to allow tests to use this shared code
-    pub fn queue_dom_patches(&mut self, dom_patches: Vec<DomPatch>) -> Result<(), JsValue> {
+    pub fn queue_dom_patches(&mut self, dom_patches: Vec<DomPatch>) -> Result<(), Error> {
         self.pending_patches.borrow_mut().extend(dom_patches);
 
-        #[cfg(feature = "with-raf")]
-        self.apply_pending_patches_with_raf().expect("raf");
-
-        #[cfg(not(feature = "with-raf"))]
-        self.apply_pending_patches().expect("raf");
-
-        Ok(())
+        if self.scheduler().strategy == UpdateStrategy::AnimationFrame {
+            self.apply_pending_patches_with_raf()
+        } else {
+            self.apply_pending_patches()
+        }
     }
 
     pub(crate) fn create_patches_with_skip_diff<'a>(
@@ -569,20 +747,21 @@ where
         )
     }
 
-    #[cfg(feature = "with-raf")]
-    fn apply_pending_patches_with_raf(&mut self) -> Result<(), JsValue> {
-        let program = Program::downgrade(&self);
+    fn apply_pending_patches_with_raf(&mut self) -> Result<(), Error> {
+        let program = Program::downgrade(self);
         let handle = request_animation_frame(move || {
-            let mut program = program.upgrade().expect("must upgrade");
-            program.apply_pending_patches().expect("must not error");
-        })
-        .expect("must execute");
+            if let Some(mut program) = program.upgrade() {
+                if let Err(error) = program.apply_pending_patches() {
+                    program.recover_from_error(error);
+                }
+            }
+        })?;
         self.animation_frame_handles.borrow_mut().push(handle);
         Ok(())
     }
 
     /// apply the pending patches into the DOM
-    fn apply_pending_patches(&mut self) -> Result<(), JsValue> {
+    fn apply_pending_patches(&mut self) -> Result<(), Error> {
         if self.pending_patches.borrow().is_empty() {
             return Ok(());
         }
@@ -596,56 +775,77 @@ where
         Ok(())
     }
 
-    #[cfg(feature = "with-ric")]
     fn dispatch_inner_with_ric(&self) {
-        let program = Program::downgrade(&self);
+        let program = Program::downgrade(self);
         let handle = request_idle_callback(move |deadline| {
             if let Some(mut program) = program.upgrade() {
                 program.dispatch_inner(Some(deadline));
             } else {
                 log::warn!("unable to upgrade program.. maybe try again next time..");
             }
-        })
-        .expect("must execute");
-        self.idle_callback_handles.borrow_mut().push(handle);
+        });
+        match handle {
+            Ok(handle) => self.idle_callback_handles.borrow_mut().push(handle),
+            Err(error) => self.dispatch_inner_after_error(error.into()),
+        }
     }
 
-    #[allow(unused)]
-    #[cfg(feature = "with-raf")]
     fn dispatch_inner_with_raf(&self) {
-        let program = Program::downgrade(&self);
+        let program = Program::downgrade(self);
         let handle = request_animation_frame(move || {
-            let mut program = program.upgrade().expect("must upgrade");
-            program.dispatch_inner(None);
-        })
-        .expect("must execute");
-        self.animation_frame_handles.borrow_mut().push(handle);
+            if let Some(mut program) = program.upgrade() {
+                program.dispatch_inner(None);
+            } else {
+                log::warn!("unable to upgrade program here, in dispatch_inner_with_raf");
+            }
+        });
+        match handle {
+            Ok(handle) => self.animation_frame_handles.borrow_mut().push(handle),
+            Err(error) => self.dispatch_inner_after_error(error.into()),
+        }
     }
 
-    fn dispatch_inner_with_priority_ric(&self) {
-        #[cfg(feature = "with-ric")]
-        self.dispatch_inner_with_ric();
-        #[cfg(not(feature = "with-ric"))]
-        {
-            #[cfg(feature = "with-raf")]
-            self.dispatch_inner_with_raf();
+    /// the callback to dispatch the pending msgs can not be requested,
+    /// so they are dispatched right away instead
+    fn dispatch_inner_after_error(&self, error: Error) {
+        self.app_mut().on_error(&error);
+        self.clone().dispatch_inner(None);
+    }
 
-            #[cfg(not(feature = "with-raf"))]
-            {
-                let program = Program::downgrade(self);
-                wasm_bindgen_futures::spawn_local(async move {
-                    if let Some(mut program) = program.upgrade() {
-                        program.dispatch_inner(None);
-                    } else {
-                        log::warn!(
-                            "unable to upgrade program here, in dispatch_inner_with_priority_ric"
-                        );
-                    }
-                })
+    fn dispatch_inner_with_microtask(&self) {
+        let program = Program::downgrade(self);
+        wasm_bindgen_futures::spawn_local(async move {
+            if let Some(mut program) = program.upgrade() {
+                program.dispatch_inner(None);
+            } else {
+                log::warn!("unable to upgrade program here, in dispatch_inner_with_microtask");
             }
+        })
+    }
+
+    /// dispatch the pending msgs and update the dom using the strategy in the scheduler config
+    fn dispatch_inner_with_scheduler(&mut self) {
+        match self.scheduler().strategy {
+            UpdateStrategy::Sync => self.dispatch_inner(None),
+            UpdateStrategy::Microtask => self.dispatch_inner_with_microtask(),
+            UpdateStrategy::AnimationFrame => self.dispatch_inner_with_raf(),
+            UpdateStrategy::IdleCallback => self.dispatch_inner_with_ric(),
         }
     }
 
+    /// dispatch all the pending msgs, then update the dom and apply all the pending patches
+    /// synchronously, regardless of the scheduler config.
+    ///
+    /// This is useful in tests where the DOM is inspected right after dispatching msgs.
+    pub fn update_dom_sync(&mut self) -> Result<(), Error> {
+        while self.app_context.dispatch_pending_msg() {}
+        let cmd = self.app_context.batch_pending_cmds();
+        self.update_dom_now()?;
+        self.apply_pending_patches()?;
+        cmd.emit(self.clone());
+        Ok(())
+    }
+
     /// This is called when an event is triggered in the html DOM.
     /// The sequence of things happening here:
     /// - The app component update is executed.
@@ -653,8 +853,9 @@ where
     /// - The view is reconstructed with the new state of the app.
     /// - The dom is updated with the newly reconstructed view.
     fn dispatch_inner(&mut self, deadline: Option<IdleDeadline>) {
-        self.dispatch_pending_msgs(deadline)
-            .expect("must dispatch msgs");
+        if let Err(error) = self.dispatch_pending_msgs(deadline) {
+            self.recover_from_error(error);
+        }
         // ensure that all pending msgs are all dispatched already
         #[cfg(feature = "ensure-check")]
         if self.app_context.has_pending_msgs() {
@@ -662,8 +863,9 @@ where
                 "There are still: {} pending msgs",
                 self.app_context.pending_msgs_count()
             );
-            self.dispatch_pending_msgs(None)
-                .expect("must dispatch all pending msgs");
+            if let Err(error) = self.dispatch_pending_msgs(None) {
+                self.recover_from_error(error);
+            }
         }
         #[cfg(feature = "ensure-check")]
         if self.app_context.has_pending_msgs() {
@@ -679,13 +881,16 @@ where
             );
         }
 
-        self.update_dom().expect("must update dom");
+        if let Err(error) = self.update_dom() {
+            self.recover_from_error(error);
+        }
 
         // Ensure all pending patches are applied before emiting the Cmd from update
         #[cfg(feature = "ensure-check")]
         if !self.pending_patches.borrow().is_empty() {
-            self.apply_pending_patches()
-                .expect("applying pending patches..");
+            if let Err(error) = self.apply_pending_patches() {
+                self.recover_from_error(error);
+            }
         }
 
         #[cfg(feature = "ensure-check")]
@@ -704,33 +909,72 @@ where
         cmd.emit(self.clone());
     }
 
-    /// Inject a style to the global document
-    fn inject_style(&mut self, class_names: String, style: &str) {
-        let style_node = html::tags::style([class(class_names)], [text(style)]);
-        let created_node = self.create_dom_node(&style_node);
+    /// report the error to the app, then re-render the whole view of the app
+    /// since the DOM may no longer be in sync with the current vdom
+    pub(crate) fn recover_from_error(&mut self, error: Error) {
+        self.app_mut().on_error(&error);
+        if let Err(error) = self.rerender() {
+            log::error!("unable to recover by re-rendering the app: {error}");
+        }
+    }
+
+    /// discard the pending patches and replace the root node with a newly created node
+    /// from the view of the app
+    fn rerender(&mut self) -> Result<(), Error> {
+        self.pending_patches.borrow_mut().clear();
+        let view = self.app_context.view();
+        let created_node = self.create_dom_node(view.unwrap_template_ref())?;
 
-        let head = document().head().expect("must have a head");
-        let head_node: web_sys::Node = head.unchecked_into();
-        let dom_head = DomNode::from(head_node);
-        dom_head.append_children(vec![created_node]);
+        let root_node = self.root_node.borrow().clone().ok_or(Error::NotMounted)?;
+        if root_node.is_fragment() {
+            // the children of the fragment are directly in the mount node
+            let mount_node = self.mount_node.borrow().clone().ok_or(Error::NotMounted)?;
+            mount_node.replace_fragment(&root_node, created_node.clone())?;
+        } else {
+            let root = root_node.as_node();
+            let parent = root.parent_node().ok_or(Error::NotMounted)?;
+            parent.replace_child(&created_node.as_node(), &root)?;
+        }
+        *self.root_node.borrow_mut() = Some(created_node);
+        self.app_context.set_current_dom(view);
+        self.update_component_styles()?;
+        self.update_dynamic_style()
+    }
+
+    /// the shadow root if the app is mounted in a shadow root, otherwise the document head
+    fn style_container(&self) -> Result<DomNode, Error> {
+        let shadow_root = self
+            .mount_node
+            .borrow()
+            .as_ref()
+            .filter(|mount_node| mount_node.as_node().has_type::<web_sys::ShadowRoot>())
+            .cloned();
+        match shadow_root {
+            Some(shadow_root) => Ok(shadow_root),
+            None => {
+                let head = document().head().ok_or(Error::NoHead)?;
+                let head_node: web_sys::Node = head.unchecked_into();
+                Ok(DomNode::from(head_node))
+            }
+        }
     }
 
     /// inject style element to the mount node
-    pub fn inject_style_to_mount(&mut self, style: &str) {
+    pub fn inject_style_to_mount(&mut self, style: &str) -> Result<(), Error> {
         let style_node = html::tags::style([], [text(style)]);
-        let created_node = self.create_dom_node(&style_node);
+        let created_node = self.create_dom_node(&style_node)?;
 
         self.mount_node
             .borrow_mut()
             .as_mut()
-            .expect("mount node")
-            .append_children(vec![created_node]);
+            .ok_or(Error::NotMounted)?
+            .append_children(vec![created_node])
     }
 
     /// dispatch multiple MSG
     pub fn dispatch_multiple(&mut self, msgs: impl IntoIterator<Item = APP::MSG>) {
         self.app_context.push_msgs(msgs);
-        self.dispatch_inner_with_priority_ric();
+        self.dispatch_inner_with_scheduler();
     }
 
     /// dispatch a single msg
@@ -741,7 +985,11 @@ where
     /// patch the DOM to reflect the App's view
     ///
     /// Note: This is in another function so as to allow tests to use this shared code
-    pub fn create_dom_patch(&self, new_vdom: &vdom::Node<APP::MSG>) -> Vec<DomPatch> {
+    pub fn create_dom_patch(
+        &self,
+        new_vdom: &vdom::Node<APP::MSG>,
+    ) -> Result<Vec<DomPatch>, Error> {
+        let _scope = self.delegation_scope();
         create_dom_patch(
             &self.root_node,
             &self.app_context.current_vdom(),
@@ -756,7 +1004,7 @@ fn create_dom_patch<Msg, F>(
     current_vdom: &vdom::Node<Msg>,
     new_vdom: &vdom::Node<Msg>,
     ev_callback: F,
-) -> Vec<DomPatch>
+) -> Result<Vec<DomPatch>, Error>
 where
     Msg: 'static,
     F: Fn(Msg) + 'static + Clone,
@@ -770,11 +1018,10 @@ where
     }
 
     dom_patch::convert_patches(
-        root_node.borrow().as_ref().expect("must have a root node"),
+        root_node.borrow().as_ref().ok_or(Error::NotMounted)?,
         &patches,
         ev_callback,
     )
-    .expect("must convert patches")
 }
 
 impl<APP> Program<APP>
@@ -785,17 +1032,15 @@ where
     ///
     /// Note: This is in another function so as to allow tests to use this shared code
     #[cfg(feature = "test-fixtures")]
-    pub fn update_dom_with_vdom(
-        &mut self,
-        new_vdom: vdom::Node<APP::MSG>,
-    ) -> Result<usize, JsValue> {
-        let dom_patches = self.create_dom_patch(&new_vdom);
+    pub fn update_dom_with_vdom(&mut self, new_vdom: vdom::Node<APP::MSG>) -> Result<usize, Error> {
+        let dom_patches = self.create_dom_patch(&new_vdom)?;
         let total_patches = dom_patches.len();
         self.pending_patches.borrow_mut().extend(dom_patches);
 
-        self.apply_pending_patches().expect("raf");
+        self.apply_pending_patches()?;
 
         self.app_context.set_current_dom(new_vdom);
+        self.update_component_styles()?;
         Ok(total_patches)
     }
 }
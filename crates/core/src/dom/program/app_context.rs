@@ -1,6 +1,6 @@
 #[cfg(feature = "with-measure")]
 use crate::dom::Measurements;
-use crate::dom::{Application, Dispatch};
+use crate::dom::{component::ViewCache, Application, Context, Dispatch, Middleware};
 use crate::vdom;
 use std::{
     cell::{Ref, RefCell},
@@ -30,6 +30,15 @@ where
 
     /// pending cmds that hasn't been emited yet
     pub(crate) pending_dispatches: Rc<RefCell<VecDeque<Dispatch<APP>>>>,
+
+    /// the middlewares which observe the msgs, the app and the cmds
+    pub(crate) middlewares: Rc<RefCell<Vec<Box<dyn Middleware<APP>>>>>,
+
+    /// the provided values which are used when creating the view of the app
+    pub(crate) context: Rc<RefCell<Context>>,
+
+    /// the views of the components in the previous view of the app
+    pub(crate) views: Rc<RefCell<ViewCache>>,
 }
 
 pub(crate) struct WeakContext<APP>
@@ -40,6 +49,9 @@ where
     pub(crate) current_vdom: Weak<RefCell<vdom::Node<APP::MSG>>>,
     pub(crate) pending_msgs: Weak<RefCell<VecDeque<APP::MSG>>>,
     pub(crate) pending_dispatches: Weak<RefCell<VecDeque<Dispatch<APP>>>>,
+    pub(crate) middlewares: Weak<RefCell<Vec<Box<dyn Middleware<APP>>>>>,
+    pub(crate) context: Weak<RefCell<Context>>,
+    pub(crate) views: Weak<RefCell<ViewCache>>,
 }
 
 impl<APP> WeakContext<APP>
@@ -51,11 +63,17 @@ where
         let current_vdom = self.current_vdom.upgrade()?;
         let pending_msgs = self.pending_msgs.upgrade()?;
         let pending_dispatches = self.pending_dispatches.upgrade()?;
+        let middlewares = self.middlewares.upgrade()?;
+        let context = self.context.upgrade()?;
+        let views = self.views.upgrade()?;
         Some(AppContext {
             app,
             current_vdom,
             pending_msgs,
             pending_dispatches,
+            middlewares,
+            context,
+            views,
         })
     }
 }
@@ -70,6 +88,9 @@ where
             current_vdom: Weak::clone(&self.current_vdom),
             pending_msgs: Weak::clone(&self.pending_msgs),
             pending_dispatches: Weak::clone(&self.pending_dispatches),
+            middlewares: Weak::clone(&self.middlewares),
+            context: Weak::clone(&self.context),
+            views: Weak::clone(&self.views),
         }
     }
 }
@@ -84,6 +105,9 @@ where
             current_vdom: Rc::downgrade(&this.current_vdom),
             pending_msgs: Rc::downgrade(&this.pending_msgs),
             pending_dispatches: Rc::downgrade(&this.pending_dispatches),
+            middlewares: Rc::downgrade(&this.middlewares),
+            context: Rc::downgrade(&this.context),
+            views: Rc::downgrade(&this.views),
         }
     }
     pub fn strong_count(&self) -> usize {
@@ -104,6 +128,9 @@ where
             current_vdom: Rc::clone(&self.current_vdom),
             pending_msgs: Rc::clone(&self.pending_msgs),
             pending_dispatches: Rc::clone(&self.pending_dispatches),
+            middlewares: Rc::clone(&self.middlewares),
+            context: Rc::clone(&self.context),
+            views: Rc::clone(&self.views),
         }
     }
 }
@@ -113,20 +140,26 @@ where
     APP: Application,
 {
     pub fn init_app(&self) -> Dispatch<APP> {
-        Dispatch::from(self.app.borrow_mut().init())
+        let cmd = self.app.borrow_mut().init();
+        for middleware in self.middlewares.borrow_mut().iter_mut() {
+            middleware.on_cmd(&cmd);
+        }
+        Dispatch::from(cmd)
+    }
+
+    pub fn add_middleware(&self, middleware: impl Middleware<APP> + 'static) {
+        self.middlewares.borrow_mut().push(Box::new(middleware));
     }
 
     pub fn view(&self) -> vdom::Node<APP::MSG> {
+        let _scope = self.context.borrow().enter();
+        let _views = ViewCache::enter(&self.views);
         self.app.borrow().view()
     }
     pub fn dynamic_style(&self) -> String {
         self.app.borrow().style().join("")
     }
 
-    pub fn static_style(&self) -> String {
-        APP::stylesheet().join("")
-    }
-
     pub fn set_current_dom(&mut self, new_vdom: vdom::Node<APP::MSG>) {
         *self.current_vdom.borrow_mut() = new_vdom;
     }
@@ -145,7 +178,18 @@ where
     }
 
     pub fn update_app(&mut self, msg: APP::MSG) -> Dispatch<APP> {
-        Dispatch::from(self.app.borrow_mut().update(msg))
+        for middleware in self.middlewares.borrow_mut().iter_mut() {
+            middleware.before_update(&msg);
+        }
+        // the middlewares are not borrowed while the app is updated,
+        // so a middleware can be added from the update
+        let cmd = self.app.borrow_mut().update(msg);
+        let app = self.app.borrow();
+        for middleware in self.middlewares.borrow_mut().iter_mut() {
+            middleware.after_update(&app);
+            middleware.on_cmd(&cmd);
+        }
+        Dispatch::from(cmd)
     }
 
     /// return true if there are still pending msgs
@@ -0,0 +1,28 @@
+use crate::dom::{Application, Cmd, DomPatch};
+
+/// Middleware are registered to a Program with [`Program::with_middleware`](crate::dom::Program::with_middleware)
+/// before it is mounted or with [`Program::add_middleware`](crate::dom::Program::add_middleware)
+/// to observe the msgs, the app, the cmds and the dom patches as they flow through the Program.
+///
+/// This is useful for logging, analytics, persistence and checking of invariants
+/// without modifying the `update` function of the app.
+/// All the hooks have a default empty implementation.
+///
+/// Note: the hooks are called while the Program is processing the msgs,
+/// so they must not dispatch msgs to the Program directly, use a Cmd or a timeout instead.
+pub trait Middleware<APP>
+where
+    APP: Application,
+{
+    /// called before the app is updated with the msg
+    fn before_update(&mut self, _msg: &APP::MSG) {}
+
+    /// called after the app is updated with a msg
+    fn after_update(&mut self, _app: &APP) {}
+
+    /// called with the Cmd returned by the app `init` and `update`, before it is executed
+    fn on_cmd(&mut self, _cmd: &Cmd<APP::MSG>) {}
+
+    /// called with the patches which are about to be applied to the DOM
+    fn on_patches(&mut self, _patches: &[DomPatch]) {}
+}
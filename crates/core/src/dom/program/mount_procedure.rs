@@ -14,7 +14,8 @@ pub enum MountAction {
 pub enum MountTarget {
     /// attached in the mount node
     MountNode,
-    /// attached to the shadow root
+    /// attached to the shadow root,
+    /// the stylesheet of the app is injected into the shadow root, scoping it to the app
     ShadowRoot,
 }
 
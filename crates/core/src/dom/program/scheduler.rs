@@ -0,0 +1,78 @@
+/// specify when the pending msgs are dispatched and the DOM is updated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpdateStrategy {
+    /// dispatch and update the DOM right away in the same call
+    Sync,
+    /// dispatch and update the DOM in a microtask, after the current event handler is finished
+    Microtask,
+    /// dispatch and update the DOM in the next animation frame using `requestAnimationFrame`
+    AnimationFrame,
+    /// dispatch and update the DOM when the browser is idle using `requestIdleCallback`,
+    /// the pending msgs that are not dispatched before the deadline are dispatched
+    /// on the next idle callback
+    IdleCallback,
+}
+
+/// Configure how the Program schedules the dispatching of msgs and updating of the DOM
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SchedulerConfig {
+    /// the strategy used for dispatching the msgs and updating the DOM
+    pub strategy: UpdateStrategy,
+    /// the minimum time in milliseconds between DOM updates, when debounce is enabled
+    pub frame_budget: f64,
+    /// when enabled, an update of the DOM which comes sooner than the frame budget
+    /// is postponed until the frame budget has elapsed, merging the updates in between
+    pub debounce: bool,
+}
+
+impl SchedulerConfig {
+    /// the frame budget to achieve 60 frames per second
+    pub const FRAME_BUDGET_60FPS: f64 = 16.0;
+
+    /// dispatch and update the DOM synchronously without debouncing,
+    /// useful in tests where the DOM is inspected right after dispatching the msgs
+    pub fn sync() -> Self {
+        Self {
+            strategy: UpdateStrategy::Sync,
+            frame_budget: Self::FRAME_BUDGET_60FPS,
+            debounce: false,
+        }
+    }
+
+    /// set the update strategy
+    pub fn with_strategy(mut self, strategy: UpdateStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// set the minimum time in milliseconds between DOM updates
+    pub fn with_frame_budget(mut self, frame_budget: f64) -> Self {
+        self.frame_budget = frame_budget;
+        self
+    }
+
+    /// enable or disable the debouncing of DOM updates
+    pub fn with_debounce(mut self, debounce: bool) -> Self {
+        self.debounce = debounce;
+        self
+    }
+}
+
+impl Default for SchedulerConfig {
+    /// uses the strategy of the enabled `with-ric` or `with-raf` feature,
+    /// otherwise the updates are done in a microtask
+    fn default() -> Self {
+        let strategy = if cfg!(feature = "with-ric") {
+            UpdateStrategy::IdleCallback
+        } else if cfg!(feature = "with-raf") {
+            UpdateStrategy::AnimationFrame
+        } else {
+            UpdateStrategy::Microtask
+        };
+        Self {
+            strategy,
+            frame_budget: Self::FRAME_BUDGET_60FPS,
+            debounce: true,
+        }
+    }
+}
@@ -0,0 +1,454 @@
+//! The registry of the component stylesheets which are injected into the document.
+//!
+//! The stylesheet of a component is injected once for each container, ie: the document head
+//! or the shadow root where the program is mounted, the first time the component is rendered,
+//! and removed when the last instance of the component is gone.
+//! The dynamic style of a program is kept in a single style element which is updated in place,
+//! and removed when the program is dropped.
+use crate::{
+    dom::{document, Error},
+    vdom::{Leaf, Node, Patch, PatchType, TreePath},
+};
+use std::{
+    any::TypeId,
+    cell::RefCell,
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+};
+
+/// the stylesheet of a component, which is only called when it is injected
+pub(crate) type Stylesheet = fn() -> Vec<String>;
+
+/// the number of instances of each component
+type Counts = HashMap<TypeId, (usize, Stylesheet)>;
+
+thread_local! {
+    static REGISTRY: RefCell<Vec<RegisteredStyle>> = const { RefCell::new(vec![]) };
+}
+
+/// a component stylesheet which is injected into a container
+struct RegisteredStyle {
+    type_id: TypeId,
+    container: web_sys::Node,
+    /// the number of the rendered instances of the component in the container
+    count: usize,
+    /// the style element, None when the stylesheet is empty
+    style: Option<web_sys::Element>,
+}
+
+impl RegisteredStyle {
+    fn is_for(&self, type_id: TypeId, container: &web_sys::Node) -> bool {
+        self.type_id == type_id && self.container.is_same_node(Some(container))
+    }
+}
+
+/// add the instances of the component, the stylesheet is injected into the container
+/// if it is not injected yet
+pub(crate) fn acquire(
+    type_id: TypeId,
+    container: &web_sys::Node,
+    stylesheet: Stylesheet,
+    count: usize,
+) -> Result<(), Error> {
+    REGISTRY.with(|registry| {
+        let mut registry = registry.borrow_mut();
+        if let Some(registered) = registry.iter_mut().find(|r| r.is_for(type_id, container)) {
+            registered.count += count;
+            return Ok(());
+        }
+        let stylesheet = stylesheet().join("");
+        let style = if stylesheet.is_empty() {
+            None
+        } else {
+            let style = document().create_element("style")?;
+            style.set_class_name(&format!("static {}", type_hash(type_id)));
+            style.set_text_content(Some(&stylesheet));
+            container.append_child(&style)?;
+            Some(style)
+        };
+        registry.push(RegisteredStyle {
+            type_id,
+            container: container.clone(),
+            count,
+            style,
+        });
+        Ok(())
+    })
+}
+
+/// remove the instances of the component, the stylesheet is removed from the container
+/// when there are no more instances of the component
+pub(crate) fn release(type_id: TypeId, container: &web_sys::Node, count: usize) {
+    REGISTRY.with(|registry| {
+        let mut registry = registry.borrow_mut();
+        let Some(index) = registry.iter().position(|r| r.is_for(type_id, container)) else {
+            return;
+        };
+        let registered = &mut registry[index];
+        registered.count = registered.count.saturating_sub(count);
+        if registered.count == 0 {
+            if let Some(style) = registry.remove(index).style {
+                style.remove();
+            }
+        }
+    })
+}
+
+fn type_hash(type_id: TypeId) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    type_id.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// The instances of the components in the current view of a program,
+/// which are registered in the container where the program is mounted
+#[derive(Default)]
+pub(crate) struct ComponentStyles {
+    /// the document head or the shadow root, set when the program is mounted
+    container: Option<web_sys::Node>,
+    /// the number of instances of each component
+    counts: Counts,
+    /// the stylesheet of the app itself is registered by the program which renders it,
+    /// as it is for the stateful components which are not in a shadow root
+    rendered_by_parent: bool,
+    /// the app which stylesheet is registered by this program
+    app: Option<TypeId>,
+}
+
+impl ComponentStyles {
+    /// the stylesheet of the app is registered by the program where it is rendered as a component
+    pub(crate) fn rendered_by_parent() -> Self {
+        Self {
+            container: None,
+            counts: Counts::new(),
+            rendered_by_parent: true,
+            app: None,
+        }
+    }
+
+    /// register the stylesheet of the app into the container
+    pub(crate) fn mount(
+        &mut self,
+        container: web_sys::Node,
+        type_id: TypeId,
+        stylesheet: Stylesheet,
+    ) -> Result<(), Error> {
+        if !self.rendered_by_parent {
+            acquire(type_id, &container, stylesheet, 1)?;
+            self.app = Some(type_id);
+        }
+        self.container = Some(container);
+        Ok(())
+    }
+
+    /// register the components in the view and release the components which are gone
+    pub(crate) fn update<MSG>(&mut self, view: &Node<MSG>) -> Result<(), Error> {
+        let Some(container) = self.container.as_ref() else {
+            return Ok(());
+        };
+        let mut counts = Counts::new();
+        count_components(view, &mut counts);
+        for (type_id, (count, stylesheet)) in counts.iter() {
+            let previous = self.counts.get(type_id).map_or(0, |(count, _)| *count);
+            if *count > previous {
+                acquire(*type_id, container, *stylesheet, count - previous)?;
+            } else if previous > *count {
+                release(*type_id, container, previous - count);
+            }
+        }
+        for (type_id, (previous, _)) in self.counts.iter() {
+            if !counts.contains_key(type_id) {
+                release(*type_id, container, *previous);
+            }
+        }
+        self.counts = counts;
+        Ok(())
+    }
+
+    /// register the components which are added and release the components which are removed
+    pub(crate) fn apply(&mut self, changes: ComponentChanges) -> Result<(), Error> {
+        let Some(container) = self.container.as_ref() else {
+            return Ok(());
+        };
+        // the added instances are acquired first, so the stylesheet of a component
+        // which is both added and removed stays injected
+        for (type_id, (count, stylesheet)) in changes.added {
+            acquire(type_id, container, stylesheet, count)?;
+            self.counts.entry(type_id).or_insert((0, stylesheet)).0 += count;
+        }
+        for (type_id, (count, _)) in changes.removed {
+            release(type_id, container, count);
+            if let Some((previous, _)) = self.counts.get_mut(&type_id) {
+                *previous = previous.saturating_sub(count);
+                if *previous == 0 {
+                    self.counts.remove(&type_id);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Drop for ComponentStyles {
+    fn drop(&mut self) {
+        let Some(container) = self.container.take() else {
+            return;
+        };
+        for (type_id, (count, _)) in self.counts.drain() {
+            release(type_id, &container, count);
+        }
+        if let Some(app) = self.app.take() {
+            release(app, &container, 1);
+        }
+    }
+}
+
+/// The instances of the components in the nodes which are added and removed by the patches
+#[derive(Default)]
+pub(crate) struct ComponentChanges {
+    added: Counts,
+    removed: Counts,
+}
+
+impl ComponentChanges {
+    /// count the components in the nodes which are changed by the patches of `old_view` into `new_view`.
+    /// The children of the nodes where a child is removed or replaced are counted in both views,
+    /// since the index of the other children are shifted.
+    /// Returns None when the whole view has to be counted, ie: the root node is replaced.
+    pub(crate) fn from_patches<MSG>(
+        old_view: &Node<MSG>,
+        new_view: &Node<MSG>,
+        patches: &[Patch<MSG>],
+    ) -> Option<Self> {
+        let mut recounted: Vec<TreePath> = vec![];
+        let mut added: Vec<(TreePath, Vec<&Node<MSG>>)> = vec![];
+        for patch in patches {
+            let path = &patch.patch_path;
+            match &patch.patch_type {
+                PatchType::InsertBeforeNode { nodes } => {
+                    added.push((path.backtrack(), nodes.iter().map(|node| &**node).collect()));
+                }
+                PatchType::InsertAfterNode { nodes } => {
+                    added.push((path.backtrack(), nodes.clone()));
+                }
+                PatchType::AppendChildren { children } => {
+                    added.push((path.clone(), children.clone()));
+                }
+                PatchType::ClearChildren => recounted.push(path.clone()),
+                PatchType::RemoveNode | PatchType::ReplaceNode { .. } => {
+                    if path.is_empty() {
+                        return None;
+                    }
+                    recounted.push(path.backtrack());
+                }
+                PatchType::MoveBeforeNode { .. }
+                | PatchType::MoveAfterNode { .. }
+                | PatchType::AddAttributes { .. }
+                | PatchType::RemoveAttributes { .. } => (),
+            }
+        }
+        // the nodes inside a recounted node are already counted with it
+        let is_inside = |path: &TreePath, other: &TreePath| {
+            path.path.len() > other.path.len() && path.path.starts_with(&other.path)
+        };
+        recounted.sort_by(|a, b| a.path.cmp(&b.path));
+        recounted.dedup();
+        let outermost: Vec<&TreePath> = recounted
+            .iter()
+            .filter(|path| !recounted.iter().any(|other| is_inside(path, other)))
+            .collect();
+
+        let mut changes = Self::default();
+        for (parent, nodes) in added {
+            if outermost
+                .iter()
+                .any(|path| **path == parent || is_inside(&parent, path))
+            {
+                continue;
+            }
+            for node in nodes {
+                count_components(node, &mut changes.added);
+            }
+        }
+        for path in outermost {
+            for child in child_nodes(find_node(old_view, &path.path)?) {
+                count_components(child, &mut changes.removed);
+            }
+            for child in child_nodes(find_node(new_view, &path.path)?) {
+                count_components(child, &mut changes.added);
+            }
+        }
+        Some(changes)
+    }
+}
+
+/// the node at the path, which is traversed the same way as the DOM nodes
+fn find_node<'a, MSG>(node: &'a Node<MSG>, path: &[usize]) -> Option<&'a Node<MSG>> {
+    match path.split_first() {
+        Some((index, rest)) => find_node(child_nodes(node).get(*index)?, rest),
+        None => Some(node),
+    }
+}
+
+/// the children of the node which are addressed by the patch paths,
+/// the view of a stateless component is in place of the component
+fn child_nodes<MSG>(node: &Node<MSG>) -> &[Node<MSG>] {
+    match node {
+        Node::Element(element) => element.children(),
+        Node::Leaf(Leaf::StatelessComponent(model)) => child_nodes(model.view()),
+        Node::Leaf(Leaf::TemplatedView(templated_view)) => child_nodes(&templated_view.view),
+        Node::Leaf(Leaf::StatefulComponent(model)) => &model.children,
+        Node::Leaf(Leaf::Fragment(nodes)) => nodes,
+        Node::Leaf(Leaf::Portal(portal)) => &portal.children,
+        Node::Leaf(_) => &[],
+    }
+}
+
+fn count_components<MSG>(node: &Node<MSG>, counts: &mut Counts) {
+    fn count(counts: &mut Counts, type_id: TypeId, stylesheet: Stylesheet) {
+        counts.entry(type_id).or_insert((0, stylesheet)).0 += 1;
+    }
+    match node {
+        Node::Element(element) => {
+            for child in element.children() {
+                count_components(child, counts);
+            }
+        }
+        Node::Leaf(Leaf::StatelessComponent(model)) => {
+            count(counts, model.type_id, model.stylesheet);
+            count_components(model.view(), counts);
+        }
+        Node::Leaf(Leaf::StatefulComponent(model)) => {
+            // the view of the stateful component is rendered by its own program
+            if let Some(stylesheet) = model.stylesheet {
+                count(counts, model.type_id, stylesheet);
+            }
+            for child in model.children.iter() {
+                count_components(child, counts);
+            }
+        }
+        Node::Leaf(Leaf::NodeList(nodes)) | Node::Leaf(Leaf::Fragment(nodes)) => {
+            for child in nodes {
+                count_components(child, counts);
+            }
+        }
+        Node::Leaf(Leaf::TemplatedView(templated_view)) => {
+            count_components(&templated_view.view, counts);
+        }
+        Node::Leaf(Leaf::Portal(portal)) => {
+            for child in portal.children.iter() {
+                count_components(child, counts);
+            }
+        }
+        Node::Leaf(_) => (),
+    }
+}
+
+/// The style element of the dynamic style of a program
+#[derive(Default)]
+pub(crate) struct DynamicStyle {
+    /// created the first time the style is not empty
+    style: Option<web_sys::Element>,
+    /// the css which is in the style element
+    css: String,
+}
+
+impl DynamicStyle {
+    /// replace the text of the style element when the css is changed
+    pub(crate) fn update(
+        &mut self,
+        container: &web_sys::Node,
+        class_names: &str,
+        css: String,
+    ) -> Result<(), Error> {
+        if css == self.css {
+            return Ok(());
+        }
+        match self.style.as_ref() {
+            Some(style) => style.set_text_content(Some(&css)),
+            None => {
+                let style = document().create_element("style")?;
+                style.set_class_name(class_names);
+                style.set_text_content(Some(&css));
+                container.append_child(&style)?;
+                self.style = Some(style);
+            }
+        }
+        self.css = css;
+        Ok(())
+    }
+}
+
+impl Drop for DynamicStyle {
+    fn drop(&mut self) {
+        if let Some(style) = self.style.take() {
+            style.remove();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        dom::{component, Component, Effects},
+        html::*,
+        vdom::diff,
+    };
+
+    struct Badge;
+
+    impl Component for Badge {
+        type MSG = ();
+        type XMSG = ();
+
+        fn update(&mut self, _msg: ()) -> Effects<(), ()> {
+            Effects::none()
+        }
+
+        fn view(&self) -> Node<()> {
+            span([], [text("new")])
+        }
+    }
+
+    fn view(badges: usize) -> Node<()> {
+        div(
+            [],
+            [
+                text("badges"),
+                ul([], (0..badges).map(|_| li([], [component(&Badge)]))),
+            ],
+        )
+    }
+
+    /// the number of badges which are added and removed from the old view into the new view
+    fn badge_changes(old: &Node<()>, new: &Node<()>) -> Option<(usize, usize)> {
+        let changes = ComponentChanges::from_patches(old, new, &diff(old, new))?;
+        let count = |counts: &Counts| {
+            counts
+                .get(&TypeId::of::<Badge>())
+                .map_or(0, |(count, _)| *count)
+        };
+        Some((count(&changes.added), count(&changes.removed)))
+    }
+
+    #[test]
+    fn only_the_added_nodes_are_counted() {
+        assert_eq!(badge_changes(&view(2), &view(3)), Some((1, 0)));
+    }
+
+    #[test]
+    fn the_children_are_recounted_when_a_child_is_removed() {
+        assert_eq!(badge_changes(&view(3), &view(1)), Some((1, 3)));
+    }
+
+    #[test]
+    fn nothing_is_counted_when_the_components_are_unchanged() {
+        assert_eq!(badge_changes(&view(2), &view(2)), Some((0, 0)));
+    }
+
+    #[test]
+    fn the_whole_view_is_counted_when_the_root_is_replaced() {
+        assert_eq!(badge_changes(&view(1), &span([], [])), None);
+    }
+}
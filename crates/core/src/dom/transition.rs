@@ -0,0 +1,182 @@
+//! Enter and leave transitions for elements that has the `transition` attribute
+//!
+//! The classes used follows the same naming convention as vue's `<Transition>`
+use crate::{
+    dom::{
+        dom_node::{intern, EventClosure},
+        window, DomNode,
+    },
+    vdom::TRANSITION,
+};
+use std::{cell::RefCell, rc::Rc};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+
+/// the events which signals that the css transition or animation of an element has ended
+const END_EVENTS: [&str; 2] = ["transitionend", "animationend"];
+
+impl DomNode {
+    /// return the name of the transition of this element
+    pub(crate) fn transition_name(&self) -> Option<String> {
+        if self.has_transition() {
+            self.as_element().get_attribute(intern(TRANSITION))
+        } else {
+            None
+        }
+    }
+
+    /// apply the enter classes of this element, which are then removed
+    /// when the enter transition has ended
+    pub(crate) fn enter_transition(&self) -> Result<(), JsValue> {
+        let Some(name) = self.transition_name() else {
+            return Ok(());
+        };
+        let element = self.as_element();
+        let enter_from = format!("{name}-enter-from");
+        let enter_active = format!("{name}-enter-active");
+        let enter_to = format!("{name}-enter-to");
+
+        element.class_list().add_2(&enter_from, &enter_active)?;
+
+        request_next_frame(move || {
+            let class_list = element.class_list();
+            class_list.remove_1(&enter_from)?;
+            class_list.add_1(&enter_to)?;
+            let target = element.clone();
+            on_transition_end(&target, move || {
+                element.class_list().remove_2(&enter_active, &enter_to)
+            })
+        })
+    }
+
+    /// apply the leave classes of this element, then remove it
+    /// from the DOM when the leave transition has ended.
+    ///
+    /// Note: this element must have been already detached from its DomNode parent
+    pub(crate) fn leave_transition(&self) -> Result<(), JsValue> {
+        let Some(name) = self.transition_name() else {
+            return Ok(());
+        };
+        let element = self.as_element();
+        let leave_from = format!("{name}-leave-from");
+        let leave_active = format!("{name}-leave-active");
+        let leave_to = format!("{name}-leave-to");
+
+        let class_list = element.class_list();
+        // in case the element is removed while it is still entering
+        class_list.remove_3(
+            &format!("{name}-enter-from"),
+            &format!("{name}-enter-active"),
+            &format!("{name}-enter-to"),
+        )?;
+        class_list.add_2(&leave_from, &leave_active)?;
+
+        request_next_frame(move || {
+            let class_list = element.class_list();
+            class_list.remove_1(&leave_from)?;
+            class_list.add_1(&leave_to)?;
+            let target = element.clone();
+            on_transition_end(&target, move || {
+                element.remove();
+                Ok(())
+            })
+        })
+    }
+}
+
+/// execute the closure after the next frame has been painted,
+/// this ensures the browser has rendered the initial classes before they are swapped
+pub(super) fn request_next_frame<F>(f: F) -> Result<(), JsValue>
+where
+    F: FnOnce() -> Result<(), JsValue> + 'static,
+{
+    let outer = Closure::once_into_js(move || {
+        let inner = Closure::once_into_js(move || log_error(f()));
+        log_error(
+            window()
+                .request_animation_frame(inner.unchecked_ref())
+                .map(|_| ()),
+        );
+    });
+    window().request_animation_frame(outer.unchecked_ref())?;
+    Ok(())
+}
+
+/// execute the closure when the transition or animation of this element has ended.
+/// The closure is executed right away if the element has no transition or animation.
+pub(super) fn on_transition_end<F>(element: &web_sys::Element, f: F) -> Result<(), JsValue>
+where
+    F: FnOnce() -> Result<(), JsValue> + 'static,
+{
+    if !has_duration(element) {
+        return f();
+    }
+    // the listener is stored here so it can remove and drop itself after it is called
+    let listener: Rc<RefCell<Option<EventClosure>>> = Rc::new(RefCell::new(None));
+    let target = element.clone();
+    let listener_clone = Rc::clone(&listener);
+    let mut f = Some(f);
+    let closure: EventClosure = Closure::new(move |event: web_sys::Event| {
+        // ignore the transitions of the descendant elements that bubbled up
+        if event.target().as_ref() != Some(target.as_ref()) {
+            return;
+        }
+        if let Some(f) = f.take() {
+            log_error(f());
+        }
+        if let Some(closure) = listener_clone.borrow_mut().take() {
+            for event_name in END_EVENTS {
+                log_error(target.remove_event_listener_with_callback(
+                    intern(event_name),
+                    closure.as_ref().unchecked_ref(),
+                ));
+            }
+            // NOTE: wasm_bindgen defers the actual drop of the closure
+            // until this current invocation has returned
+            drop(closure);
+        }
+    });
+    for event_name in END_EVENTS {
+        element.add_event_listener_with_callback(
+            intern(event_name),
+            closure.as_ref().unchecked_ref(),
+        )?;
+    }
+    *listener.borrow_mut() = Some(closure);
+    Ok(())
+}
+
+/// the errors in the callbacks which are called later by the browser
+/// has nowhere to propagate to, so they are logged instead
+fn log_error(result: Result<(), JsValue>) {
+    if let Err(e) = result {
+        log::error!("error in transition callback: {e:?}");
+    }
+}
+
+/// returns true if the computed style of the element has a non-zero
+/// transition or animation duration
+fn has_duration(element: &web_sys::Element) -> bool {
+    let Ok(Some(style)) = window().get_computed_style(element) else {
+        return false;
+    };
+    ["transition-duration", "animation-duration"]
+        .iter()
+        .any(|property| {
+            style
+                .get_property_value(property)
+                .map(|durations| durations.split(',').any(|d| parse_duration(d) > 0.0))
+                .unwrap_or(false)
+        })
+}
+
+/// parse css time value such as `0.3s` or `300ms` into milliseconds
+fn parse_duration(duration: &str) -> f64 {
+    let duration = duration.trim();
+    if let Some(ms) = duration.strip_suffix("ms") {
+        ms.parse().unwrap_or(0.0)
+    } else if let Some(s) = duration.strip_suffix('s') {
+        s.parse::<f64>().map(|s| s * 1000.0).unwrap_or(0.0)
+    } else {
+        0.0
+    }
+}
@@ -0,0 +1,431 @@
+//! A virtualized list which renders only the visible rows of a huge list,
+//! and optionally only the visible columns of a grid.
+use crate::{
+    dom::{
+        events::{on_accepted, on_mount},
+        Event,
+    },
+    html::{
+        attributes::{class, styles},
+        div,
+        units::px,
+    },
+    vdom::{attr, key, Attribute, Node},
+};
+use std::ops::Range;
+use wasm_bindgen::JsCast;
+
+/// The height of the rows of a virtual list
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ItemSize {
+    /// all the rows have this height in pixels
+    Fixed(f64),
+    /// the rows are measured once they are rendered,
+    /// the estimated height is used for the rows which are not measured yet
+    Measured {
+        /// the height in pixels of the rows which are not yet measured
+        estimated: f64,
+    },
+}
+
+/// The msgs of the virtual list.
+/// The app wraps these msgs into its own msg and passes them back to [`VirtualList::update`]
+#[derive(Debug, Clone)]
+pub enum VirtualListMsg {
+    /// the scrollable viewport of the list is mounted
+    Mounted(web_sys::Element),
+    /// the viewport is scrolled
+    Scrolled {
+        /// the vertical scroll offset in pixels
+        scroll_top: f64,
+        /// the horizontal scroll offset in pixels
+        scroll_left: f64,
+        /// the index and height of the rendered rows, when the rows are measured
+        measured: Vec<(usize, f64)>,
+    },
+    /// scroll the viewport such that the row at this index is at the top
+    ScrollToIndex(usize),
+}
+
+/// the columns of a virtual grid
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Columns {
+    count: usize,
+    width: f64,
+    viewport_width: f64,
+}
+
+/// A list which renders only the rows which are visible in its viewport plus a few overscan rows,
+/// so lists with a huge number of rows only create the vdom nodes and DOM elements of the
+/// rows that can be seen.
+///
+/// The rows are keyed by their index so scrolling only patches the rows which enter
+/// and leave the viewport.
+///
+/// # Example
+/// ```rust
+/// use sauron::{*, dom::{ItemSize, VirtualList, VirtualListMsg}};
+///
+/// enum Msg {
+///     List(VirtualListMsg),
+/// }
+///
+/// let list = VirtualList::new(100_000, ItemSize::Fixed(20.0), 200.0);
+/// let view: Node<Msg> = list.view(Msg::List, |index| text!("row {index}"));
+/// assert_eq!(list.visible_rows(), 0..14);
+/// ```
+#[derive(Debug, Clone)]
+pub struct VirtualList {
+    row_count: usize,
+    item_size: ItemSize,
+    /// the heights of each row, only used when the rows are measured
+    heights: Vec<f64>,
+    /// the offset of each row from the top of the list, followed by the total height,
+    /// this is the prefix sum of the heights
+    offsets: Vec<f64>,
+    columns: Option<Columns>,
+    viewport_height: f64,
+    overscan: usize,
+    scroll_top: f64,
+    scroll_left: f64,
+    viewport: Option<web_sys::Element>,
+}
+
+impl VirtualList {
+    /// the default number of rows rendered above and below the visible rows
+    pub const DEFAULT_OVERSCAN: usize = 3;
+
+    /// create a virtual list with the number of rows, the height of the rows
+    /// and the height in pixels of the scrollable viewport
+    pub fn new(row_count: usize, item_size: ItemSize, viewport_height: f64) -> Self {
+        let mut virtual_list = Self {
+            row_count: 0,
+            item_size,
+            heights: vec![],
+            offsets: vec![0.0],
+            columns: None,
+            viewport_height,
+            overscan: Self::DEFAULT_OVERSCAN,
+            scroll_top: 0.0,
+            scroll_left: 0.0,
+            viewport: None,
+        };
+        virtual_list.set_row_count(row_count);
+        virtual_list
+    }
+
+    /// set the number of rows rendered above and below the visible rows
+    pub fn with_overscan(mut self, overscan: usize) -> Self {
+        self.overscan = overscan;
+        self
+    }
+
+    /// also virtualize the columns of each row, where each of the columns has a fixed width,
+    /// use [`VirtualList::view_grid`] to render the cells
+    pub fn with_columns(mut self, count: usize, width: f64, viewport_width: f64) -> Self {
+        self.columns = Some(Columns {
+            count,
+            width,
+            viewport_width,
+        });
+        self
+    }
+
+    /// change the number of rows, the measured heights of the remaining rows are kept
+    pub fn set_row_count(&mut self, row_count: usize) {
+        self.row_count = row_count;
+        if let ItemSize::Measured { estimated } = self.item_size {
+            let unchanged = self.heights.len().min(row_count);
+            self.heights.resize(row_count, estimated);
+            self.update_offsets(unchanged);
+        }
+    }
+
+    /// recompute the offsets of the rows after the row at this index
+    fn update_offsets(&mut self, from: usize) {
+        self.offsets.truncate(from + 1);
+        let mut top = self.offsets[from];
+        for height in self.heights[from..].iter() {
+            top += height;
+            self.offsets.push(top);
+        }
+    }
+
+    /// the number of rows
+    pub fn row_count(&self) -> usize {
+        self.row_count
+    }
+
+    /// the vertical scroll offset in pixels
+    pub fn scroll_top(&self) -> f64 {
+        self.scroll_top
+    }
+
+    /// the horizontal scroll offset in pixels
+    pub fn scroll_left(&self) -> f64 {
+        self.scroll_left
+    }
+
+    /// update the virtual list with the msg
+    pub fn update(&mut self, msg: VirtualListMsg) {
+        match msg {
+            VirtualListMsg::Mounted(viewport) => {
+                self.viewport = Some(viewport);
+            }
+            VirtualListMsg::Scrolled {
+                scroll_top,
+                scroll_left,
+                measured,
+            } => {
+                self.scroll_top = scroll_top;
+                self.scroll_left = scroll_left;
+                let mut changed = self.row_count;
+                for (index, height) in measured {
+                    if let Some(row_height) = self.heights.get_mut(index) {
+                        if *row_height != height {
+                            *row_height = height;
+                            changed = changed.min(index);
+                        }
+                    }
+                }
+                if changed < self.row_count {
+                    self.update_offsets(changed);
+                }
+            }
+            VirtualListMsg::ScrollToIndex(index) => self.scroll_to_index(index),
+        }
+    }
+
+    /// scroll the viewport such that the row at this index is at the top
+    pub fn scroll_to_index(&mut self, index: usize) {
+        let index = index.min(self.row_count.saturating_sub(1));
+        let max_scroll = (self.total_height() - self.viewport_height).max(0.0);
+        self.scroll_top = self.row_offset(index).min(max_scroll);
+        if let Some(viewport) = &self.viewport {
+            viewport.set_scroll_top(self.scroll_top as i32);
+        }
+    }
+
+    /// the offset in pixels of the row at this index from the top of the list
+    pub fn row_offset(&self, index: usize) -> f64 {
+        match self.item_size {
+            ItemSize::Fixed(height) => index as f64 * height,
+            ItemSize::Measured { .. } => self.offsets[index.min(self.row_count)],
+        }
+    }
+
+    /// the height in pixels of all the rows
+    pub fn total_height(&self) -> f64 {
+        self.row_offset(self.row_count)
+    }
+
+    /// the index of the row at this offset in pixels
+    fn row_at(&self, offset: f64) -> usize {
+        match self.item_size {
+            ItemSize::Fixed(height) if height > 0.0 => (offset / height).floor() as usize,
+            ItemSize::Fixed(_) => 0,
+            // the number of rows which ends at or above the offset
+            ItemSize::Measured { .. } => {
+                self.offsets[1..].partition_point(|&bottom| bottom <= offset)
+            }
+        }
+    }
+
+    /// the range of the rows that are rendered, which are the rows that are visible
+    /// in the viewport plus the overscan rows
+    pub fn visible_rows(&self) -> Range<usize> {
+        let first = self.row_at(self.scroll_top);
+        let last = self.row_at(self.scroll_top + self.viewport_height);
+        let start = first.saturating_sub(self.overscan).min(self.row_count);
+        let end = (last + 1 + self.overscan).min(self.row_count);
+        start..end
+    }
+
+    /// the range of the columns that are rendered, this is empty when the columns are not
+    /// virtualized
+    pub fn visible_columns(&self) -> Range<usize> {
+        match self.columns {
+            Some(Columns {
+                count,
+                width,
+                viewport_width,
+            }) if width > 0.0 => {
+                let first = (self.scroll_left / width).floor() as usize;
+                let last = ((self.scroll_left + viewport_width) / width).floor() as usize;
+                let start = first.saturating_sub(self.overscan).min(count);
+                let end = (last + 1 + self.overscan).min(count);
+                start..end
+            }
+            _ => 0..0,
+        }
+    }
+
+    /// the view of the list where only the visible rows are rendered using `render_row`,
+    /// the msgs of the list are wrapped into the app's msg using `map_msg`
+    pub fn view<MSG, M, R>(&self, map_msg: M, render_row: R) -> Node<MSG>
+    where
+        MSG: 'static,
+        M: Fn(VirtualListMsg) -> MSG + Clone + 'static,
+        R: Fn(usize) -> Node<MSG>,
+    {
+        self.view_rows(map_msg, |index| vec![render_row(index)], [])
+    }
+
+    /// the view of a grid where only the visible rows and the visible columns are rendered
+    /// using `render_cell` which is called with the row and column index.
+    /// Each row is rendered as a single cell at column 0 when the columns are not set
+    /// with [`VirtualList::with_columns`]
+    pub fn view_grid<MSG, M, C>(&self, map_msg: M, render_cell: C) -> Node<MSG>
+    where
+        MSG: 'static,
+        M: Fn(VirtualListMsg) -> MSG + Clone + 'static,
+        C: Fn(usize, usize) -> Node<MSG>,
+    {
+        let Some(columns) = self.columns else {
+            return self.view(map_msg, |row| render_cell(row, 0));
+        };
+        let visible_columns = self.visible_columns();
+        let padding_left = visible_columns.start as f64 * columns.width;
+        self.view_rows(
+            map_msg,
+            |row| {
+                visible_columns
+                    .clone()
+                    .map(|column| {
+                        div(
+                            [
+                                key(column),
+                                class("sauron-virtual-list__cell"),
+                                styles([
+                                    ("flex", "0 0 auto".to_string()),
+                                    ("width", px(columns.width)),
+                                ]),
+                            ],
+                            [render_cell(row, column)],
+                        )
+                    })
+                    .collect()
+            },
+            [
+                ("display", "flex".to_string()),
+                ("padding-left", px(padding_left)),
+            ],
+        )
+    }
+
+    fn view_rows<MSG, M, R>(
+        &self,
+        map_msg: M,
+        render_row: R,
+        row_styles: impl IntoIterator<Item = (&'static str, String)> + Clone,
+    ) -> Node<MSG>
+    where
+        MSG: 'static,
+        M: Fn(VirtualListMsg) -> MSG + Clone + 'static,
+        R: Fn(usize) -> Vec<Node<MSG>>,
+    {
+        let visible_rows = self.visible_rows();
+        let rows_top = self.row_offset(visible_rows.start);
+        let row_height = match self.item_size {
+            ItemSize::Fixed(height) => Some(px(height)),
+            ItemSize::Measured { .. } => None,
+        };
+        let rows = visible_rows.map(|index| {
+            div(
+                [
+                    key(index),
+                    class("sauron-virtual-list__row"),
+                    attr("data-index", index),
+                    styles(
+                        row_height
+                            .iter()
+                            .map(|height| ("height", height.clone()))
+                            .chain(row_styles.clone()),
+                    ),
+                ],
+                render_row(index),
+            )
+        });
+        let mut viewport_styles = vec![
+            ("overflow", "auto".to_string()),
+            ("position", "relative".to_string()),
+            ("height", px(self.viewport_height)),
+        ];
+        let mut content_styles = vec![
+            ("position", "relative".to_string()),
+            ("height", px(self.total_height())),
+        ];
+        if let Some(columns) = self.columns {
+            viewport_styles.push(("width", px(columns.viewport_width)));
+            content_styles.push(("width", px(columns.count as f64 * columns.width)));
+        }
+        let on_mount_msg = map_msg.clone();
+        div(
+            [
+                class("sauron-virtual-list"),
+                styles(viewport_styles),
+                on_mount(move |me| {
+                    on_mount_msg(VirtualListMsg::Mounted(me.target_node.as_element()))
+                }),
+                on_viewport_scroll(self.item_size, map_msg),
+            ],
+            [div(
+                [
+                    class("sauron-virtual-list__content"),
+                    styles(content_styles),
+                ],
+                [div(
+                    [
+                        class("sauron-virtual-list__rows"),
+                        styles([
+                            ("position", "absolute".to_string()),
+                            ("top", px(rows_top)),
+                            ("left", px(0)),
+                            ("right", px(0)),
+                        ]),
+                    ],
+                    rows,
+                )],
+            )],
+        )
+    }
+}
+
+/// the scroll listener of the viewport, which also measures the rendered rows
+/// when the rows are measured
+fn on_viewport_scroll<MSG, M>(item_size: ItemSize, map_msg: M) -> Attribute<MSG>
+where
+    MSG: 'static,
+    M: Fn(VirtualListMsg) -> MSG + 'static,
+{
+    // the events which are not dispatched from the viewport element are ignored
+    on_accepted("scroll", move |event: Event| {
+        let viewport: web_sys::Element = event.as_web()?.target()?.dyn_into().ok()?;
+        let measured = match item_size {
+            ItemSize::Fixed(_) => vec![],
+            ItemSize::Measured { .. } => measure_rows(&viewport),
+        };
+        Some(map_msg(VirtualListMsg::Scrolled {
+            scroll_top: viewport.scroll_top() as f64,
+            scroll_left: viewport.scroll_left() as f64,
+            measured,
+        }))
+    })
+}
+
+/// the index and height of the rows rendered in the viewport
+fn measure_rows(viewport: &web_sys::Element) -> Vec<(usize, f64)> {
+    let Ok(rows) = viewport.query_selector_all(
+        ":scope > .sauron-virtual-list__content > .sauron-virtual-list__rows > .sauron-virtual-list__row",
+    ) else {
+        return vec![];
+    };
+    (0..rows.length())
+        .filter_map(|i| {
+            let row: web_sys::Element = rows.get(i)?.unchecked_into();
+            let index = row.get_attribute("data-index")?.parse().ok()?;
+            let height = row.get_bounding_client_rect().height();
+            Some((index, height))
+        })
+        .collect()
+}
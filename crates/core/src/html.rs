@@ -2,12 +2,14 @@
 use crate::vdom;
 use crate::vdom::Leaf;
 pub use crate::vdom::{element, element_ns};
-use crate::vdom::{Attribute, Node};
+use crate::vdom::{Attribute, Node, Portal, PortalTarget};
+pub use error_boundary::{error_boundary, error_boundary_with_report, ViewError, ViewResult};
 use std::borrow::Cow;
 pub use tags::{commons::*, self_closing::*, *};
 
 #[macro_use]
 pub mod attributes;
+mod error_boundary;
 #[cfg(feature = "with-lookup")]
 pub mod lookup;
 pub mod tags;
@@ -141,6 +143,27 @@ pub fn fragment<MSG>(nodes: impl IntoIterator<Item = Node<MSG>>) -> Node<MSG> {
     vdom::fragment(nodes)
 }
 
+/// portal creates and patches its children inside a different container such as `body`
+/// or a `#modals` div, so modals, tooltips and dropdowns can escape the `overflow: hidden` of
+/// their parents.
+/// The events of the children are still dispatched to the app, and the children are removed
+/// from the container when the portal is removed.
+/// # Example
+/// ```rust
+/// use sauron::{*, html::portal};
+///
+/// let node: Node<()> = portal("#modals", [div([class("modal")], [text("hello")])]);
+/// ```
+pub fn portal<MSG>(
+    target: impl Into<PortalTarget>,
+    children: impl IntoIterator<Item = Node<MSG>>,
+) -> Node<MSG> {
+    Node::Leaf(Leaf::Portal(Portal {
+        target: target.into(),
+        children: children.into_iter().collect(),
+    }))
+}
+
 /// create a doctype
 pub fn doctype<MSG>(s: impl Into<Cow<'static, str>>) -> Node<MSG> {
     Node::Leaf(Leaf::DocType(s.into()))
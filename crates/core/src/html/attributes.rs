@@ -8,7 +8,7 @@ use std::borrow::Cow;
 
 pub use crate::vdom::EventCallback;
 pub use crate::vdom::Style;
-pub use crate::vdom::{key, replace, skip, skip_criteria};
+pub use crate::vdom::{animate_moves, key, replace, skip, skip_criteria, transition};
 pub use crate::{dom::Event, vdom::Attribute};
 pub use attribute_macros::commons::*;
 pub use attribute_macros::*;
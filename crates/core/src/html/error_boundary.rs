@@ -0,0 +1,103 @@
+//! Error boundaries which renders a fallback view when the view of a subtree fails to build
+use crate::vdom::Node;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+
+/// The error caught by an error boundary while building the view of its subtree
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ViewError {
+    /// the view panicked with this message
+    #[error("the view panicked: {0}")]
+    Panicked(String),
+    /// the view returned an error with this message
+    #[error("the view failed: {0}")]
+    Failed(String),
+}
+
+/// A view which may fail to build, this is implemented for `Node` and for
+/// a `Result` of a `Node` where the error can be displayed
+pub trait ViewResult<MSG> {
+    /// convert into the resulting node or the error of the view
+    fn into_view_result(self) -> Result<Node<MSG>, ViewError>;
+}
+
+impl<MSG> ViewResult<MSG> for Node<MSG> {
+    fn into_view_result(self) -> Result<Node<MSG>, ViewError> {
+        Ok(self)
+    }
+}
+
+impl<MSG, E> ViewResult<MSG> for Result<Node<MSG>, E>
+where
+    E: fmt::Display,
+{
+    fn into_view_result(self) -> Result<Node<MSG>, ViewError> {
+        self.map_err(|e| ViewError::Failed(e.to_string()))
+    }
+}
+
+/// Build the view of a subtree, rendering the fallback instead if the view panics
+/// or returns an error, so a broken part of the page doesn't take down the whole app.
+///
+/// The view can either return a `Node` or a `Result` of a `Node`.
+/// Panics are caught using `catch_unwind`, therefore it only works on targets that unwinds,
+/// on `wasm32-unknown-unknown` panics abort, so return a `Result` from the view instead.
+///
+/// Only the `view` closure is covered, which is called right away while the parent view is
+/// built. The `update` of the apps and components, and the view of the stateful components
+/// in the subtree, which is built later by their own program, are not covered.
+/// The errors while patching the DOM are reported to
+/// [`Application::on_error`](crate::dom::Application::on_error) instead.
+///
+/// # Example
+/// ```rust
+/// use sauron::{*, html::ViewError};
+///
+/// let node: Node<()> = error_boundary(
+///     |error: &ViewError| p([class("error")], [text(error)]),
+///     || -> Result<Node<()>, String> { Err("no data".to_string()) },
+/// );
+/// assert_eq!(
+///     node.render_to_string(),
+///     r#"<p class="error">the view failed: no data</p>"#
+/// );
+/// ```
+pub fn error_boundary<MSG, F, V, R>(fallback: F, view: V) -> Node<MSG>
+where
+    F: FnOnce(&ViewError) -> Node<MSG>,
+    V: FnOnce() -> R,
+    R: ViewResult<MSG>,
+{
+    error_boundary_with_report(fallback, view, |error| log::error!("{error}"))
+}
+
+/// Same as [`error_boundary`] but the error is also reported to `on_error`
+/// before the fallback is rendered, instead of being logged
+pub fn error_boundary_with_report<MSG, F, V, R, E>(fallback: F, view: V, on_error: E) -> Node<MSG>
+where
+    F: FnOnce(&ViewError) -> Node<MSG>,
+    V: FnOnce() -> R,
+    R: ViewResult<MSG>,
+    E: FnOnce(&ViewError),
+{
+    let result = panic::catch_unwind(AssertUnwindSafe(|| view().into_view_result()))
+        .unwrap_or_else(|payload| Err(ViewError::Panicked(panic_message(payload.as_ref()))));
+    match result {
+        Ok(node) => node,
+        Err(error) => {
+            on_error(&error);
+            fallback(&error)
+        }
+    }
+}
+
+/// extract the message of the panic payload
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
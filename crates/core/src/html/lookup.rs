@@ -16,7 +16,7 @@ use crate::{
 use once_cell::sync::Lazy;
 use std::collections::BTreeMap;
 use std::collections::BTreeSet;
-pub use style_lookup::match_property;
+pub use style_lookup::{match_property, property_names};
 mod style_lookup;
 
 /// All of the svg tags
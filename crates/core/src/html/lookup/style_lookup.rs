@@ -16,6 +16,11 @@ pub fn match_property(property: &str) -> Option<&str> {
     }
 }
 
+/// the names of the style properties in snake_case, ie: `background_color`
+pub fn property_names() -> impl Iterator<Item = &'static str> {
+    HTML_STYLES.keys().chain(SVG_STYLES.keys()).copied()
+}
+
 static ALL_STYLES: Lazy<BTreeSet<&'static str>> = Lazy::new(|| {
     BTreeSet::from_iter(
         HTML_STYLES
@@ -25,7 +25,7 @@ pub mod prelude {
         },
         br, comment,
         commons::*,
-        hr, img, input, lazy_view_if, text,
+        error_boundary, error_boundary_with_report, hr, img, input, lazy_view_if, text,
         units::{ch, cm, deg, ex, grad, mm, ms, percent, pt, px, rad, rgb, rgba, s, turn, vh, vw},
         view_if,
     };
@@ -36,7 +36,8 @@ pub mod prelude {
     pub use crate::svg::commons::*;
     pub use crate::svg::special::*;
     pub use crate::vdom::{
-        diff, Attribute, AttributeValue, Element, EventCallback, Node, Patch, TreePath, Value,
+        diff, Attribute, AttributeValue, Element, EventCallback, ListenerOptions, Node, Patch,
+        TreePath, Value,
     };
 
     use cfg_if::cfg_if;
@@ -51,8 +52,8 @@ pub mod prelude {
         pub use crate::html::events::*;
         pub use crate::dom::{Application, SkipDiff, skip_if, events, Program, document, Document, now, window, Window, Dispatch,
             AnimationFrameHandle, Component, StatefulComponent, Effects, Measurements, MountAction,
-            MountTarget, Cmd, TimeoutCallbackHandle, DomAttrValue,
-            stateful_component, Time,
+            MountTarget, Cmd, TimeoutCallbackHandle, DomAttrValue, Error, Middleware, SchedulerConfig, UpdateStrategy,
+            stateful_component, Time, register_custom_element, provide, use_context,
         };
     }}
 }
@@ -62,4 +63,6 @@ pub mod html;
 #[macro_use]
 pub mod svg;
 pub mod dom;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 pub mod vdom;
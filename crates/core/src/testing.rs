@@ -0,0 +1,211 @@
+//! Utilities for testing the view of components and applications without a browser.
+//!
+//! Nodes can be queried using css selectors, inspected through their text content,
+//! attributes and classes, and events can be simulated on them to get the resulting MSG.
+//! The rendered view can also be compared against a snapshot file using [`assert_snapshot`].
+//!
+//! # Example
+//! ```rust
+//! use sauron::{*, testing::SelectorError};
+//!
+//! #[derive(Debug, PartialEq)]
+//! enum Msg {
+//!     Save,
+//!     NameChanged(String),
+//! }
+//!
+//! let view: Node<Msg> = form([], [
+//!     input([r#type("text"), on_input(|e| Msg::NameChanged(e.value()))], []),
+//!     button([class("btn primary"), on_click(|_| Msg::Save)], [text("Save")]),
+//! ]);
+//!
+//! let button = view.find("form button.primary")?.expect("must have a button");
+//! assert_eq!(button.text_content(), "Save");
+//! assert!(button.has_class("btn"));
+//! assert_eq!(button.simulate_click(), Some(Msg::Save));
+//!
+//! let input = view.find("input[type=text]")?.expect("must have an input");
+//! assert_eq!(
+//!     input.simulate_input("sauron"),
+//!     Some(Msg::NameChanged("sauron".to_string()))
+//! );
+//! # Ok::<(), SelectorError>(())
+//! ```
+pub use selector::{Selector, SelectorError};
+pub use snapshot::{assert_snapshot, assert_snapshot_in, UPDATE_SNAPSHOTS_ENV};
+
+use crate::vdom::{Attribute, Leaf, Node, Style, Value};
+
+mod selector;
+mod snapshot;
+
+impl<MSG> Node<MSG> {
+    /// returns the first element in this node and its descendants that matches the css selector,
+    /// or the error if the selector is not a valid css selector
+    pub fn find(&self, selector: &str) -> Result<Option<&Node<MSG>>, SelectorError> {
+        Ok(self.find_all(selector)?.into_iter().next())
+    }
+
+    /// returns all the elements in this node and its descendants that matches the css selector,
+    /// in document order, or the error if the selector is not a valid css selector
+    pub fn find_all(&self, selector: &str) -> Result<Vec<&Node<MSG>>, SelectorError> {
+        let selector = Selector::parse(selector)?;
+        let mut found = vec![];
+        self.collect_matches(&selector, &mut vec![], &mut found);
+        Ok(found)
+    }
+
+    fn collect_matches<'a>(
+        &'a self,
+        selector: &Selector,
+        ancestors: &mut Vec<&'a Node<MSG>>,
+        found: &mut Vec<&'a Node<MSG>>,
+    ) {
+        match self {
+            Node::Element(element) => {
+                if selector.matches(self, ancestors) {
+                    found.push(self);
+                }
+                ancestors.push(self);
+                for child in element.children() {
+                    child.collect_matches(selector, ancestors, found);
+                }
+                ancestors.pop();
+            }
+            Node::Leaf(leaf) => {
+                for child in leaf_children(leaf) {
+                    child.collect_matches(selector, ancestors, found);
+                }
+            }
+        }
+    }
+
+    /// returns the concatenated text of this node and its descendants
+    pub fn text_content(&self) -> String {
+        let mut buffer = String::new();
+        self.collect_text(&mut buffer);
+        buffer
+    }
+
+    fn collect_text(&self, buffer: &mut String) {
+        match self {
+            Node::Element(element) => {
+                for child in element.children() {
+                    child.collect_text(buffer);
+                }
+            }
+            Node::Leaf(Leaf::Text(text)) => buffer.push_str(text),
+            Node::Leaf(leaf) => {
+                for child in leaf_children(leaf) {
+                    child.collect_text(buffer);
+                }
+            }
+        }
+    }
+
+    /// returns the value of the attribute of this element as it would be rendered,
+    /// multiple values of the same attribute name are joined with a space
+    pub fn get_attribute(&self, name: &str) -> Option<String> {
+        let attributes = self.attributes()?;
+        let attributes: Vec<&Attribute<MSG>> = attributes
+            .iter()
+            .filter(|att| *att.name() == name)
+            .collect();
+        if attributes.is_empty() {
+            return None;
+        }
+        let mut plain_values: Vec<&Value> = vec![];
+        let mut styles: Vec<&Style> = vec![];
+        for att in attributes {
+            let grouped = Attribute::group_values(att);
+            plain_values.extend(grouped.plain_values);
+            styles.extend(grouped.styles);
+        }
+        Style::merge_to_string(styles)
+            .or_else(|| Value::merge_to_string(plain_values))
+            .or_else(|| Some(String::new()))
+    }
+
+    /// returns the classes of this element
+    pub fn classes(&self) -> Vec<String> {
+        self.get_attribute("class")
+            .map(|class| class.split_whitespace().map(String::from).collect())
+            .unwrap_or_default()
+    }
+
+    /// returns true if this element has the class
+    pub fn has_class(&self, class_name: &str) -> bool {
+        self.classes().iter().any(|class| class == class_name)
+    }
+}
+
+#[cfg(feature = "with-dom")]
+impl<MSG> Node<MSG>
+where
+    MSG: 'static,
+{
+    /// call the event listeners of this element for the event `event_name`
+    /// with a synthetic event, the target of the event has the attributes of this element.
+    /// Returns the MSG of the last listener, or None if there is no listener for the event.
+    pub fn simulate_event(&self, event_name: &str) -> Option<MSG> {
+        self.simulate_event_with_target(event_name, |_| Ok(()))
+    }
+
+    /// simulate a click on this element, returns the MSG emitted by the `click` listener.
+    ///
+    /// Note: the `on_click` listeners are passed a synthetic `MouseEvent`,
+    /// which fields can not be read when running natively
+    pub fn simulate_click(&self) -> Option<MSG> {
+        self.simulate_event("click")
+    }
+
+    /// simulate the user entering the `value` into this element,
+    /// returns the MSG emitted by the `on_input` listener
+    pub fn simulate_input(&self, value: impl ToString) -> Option<MSG> {
+        self.simulate_event_with_target("input", |target| {
+            target.set_attribute("value", value.to_string())
+        })
+    }
+
+    fn simulate_event_with_target(
+        &self,
+        event_name: &str,
+        setup_target: impl FnOnce(&crate::dom::HeadlessNode) -> Result<(), crate::dom::Error>,
+    ) -> Option<MSG> {
+        use crate::dom::{Event, HeadlessEvent, HeadlessNode};
+
+        let attributes = self.attributes()?;
+        let listeners: Vec<_> = attributes
+            .iter()
+            .filter(|att| *att.name() == event_name)
+            .flat_map(|att| Attribute::group_values(att).listeners)
+            .collect();
+        if listeners.is_empty() {
+            return None;
+        }
+        let target = HeadlessNode::create(self, |_: MSG| ());
+        setup_target(&target).ok()?;
+        let headless_event = HeadlessEvent {
+            name: event_name.to_string(),
+            target,
+        };
+        let mut msg = None;
+        for listener in listeners {
+            if let Some(out) = listener.try_emit(Event::HeadlessEvent(headless_event.clone())) {
+                msg = Some(out);
+            }
+        }
+        msg
+    }
+}
+
+/// the nodes contained in this leaf which are rendered as part of the parent element
+fn leaf_children<MSG>(leaf: &Leaf<MSG>) -> Vec<&Node<MSG>> {
+    match leaf {
+        Leaf::NodeList(nodes) | Leaf::Fragment(nodes) => nodes.iter().collect(),
+        Leaf::StatelessComponent(comp) => vec![comp.view()],
+        Leaf::TemplatedView(view) => vec![&view.view],
+        Leaf::Portal(portal) => portal.children.iter().collect(),
+        _ => vec![],
+    }
+}
@@ -0,0 +1,339 @@
+//! A subset of css selectors used for querying the nodes of a view
+//!
+//! Supported selectors:
+//! - type selector: `button`, universal selector: `*`
+//! - class selector: `.primary`
+//! - id selector: `#submit`
+//! - attribute selectors: `[disabled]`, `[type=text]`, `[type="text"]`
+//! - descendant combinator: `form button`, child combinator: `ul > li`
+//! - selector list: `button, a`, the commas in the quoted attribute values are not separators
+use crate::vdom::Node;
+use std::fmt;
+
+/// A parsed css selector
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Selector {
+    /// the selectors in a comma separated selector list
+    complex_selectors: Vec<ComplexSelector>,
+}
+
+/// compound selectors joined with combinators, ie: `ul > li.active`
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ComplexSelector {
+    /// the last compound selector is the one matched against the node
+    compounds: Vec<(Combinator, CompoundSelector)>,
+}
+
+/// the relationship of the compound selector to the one before it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Combinator {
+    /// the first compound selector in a complex selector
+    None,
+    /// any ancestor
+    Descendant,
+    /// the direct parent
+    Child,
+}
+
+/// a sequence of simple selectors without combinator, ie: `button.primary[disabled]`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct CompoundSelector {
+    tag: Option<String>,
+    id: Option<String>,
+    classes: Vec<String>,
+    attributes: Vec<(String, Option<String>)>,
+}
+
+/// Errors encountered when parsing a selector
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectorError {
+    /// the selector is empty
+    EmptySelector,
+    /// an unexpected character is found at this position
+    UnexpectedChar(char, usize),
+    /// the attribute selector is not closed with `]`
+    UnclosedAttribute,
+}
+
+impl fmt::Display for SelectorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::EmptySelector => write!(f, "selector is empty"),
+            Self::UnexpectedChar(ch, pos) => {
+                write!(f, "unexpected character `{ch}` at position {pos}")
+            }
+            Self::UnclosedAttribute => write!(f, "attribute selector is not closed with `]`"),
+        }
+    }
+}
+
+impl std::error::Error for SelectorError {}
+
+impl Selector {
+    /// parse a selector
+    pub fn parse(selector: &str) -> Result<Self, SelectorError> {
+        let chars: Vec<char> = selector.chars().collect();
+        let complex_selectors = split_selector_list(&chars)
+            .into_iter()
+            .map(|(start, end)| {
+                ComplexSelector::parse(&chars[start..end]).map_err(|error| error.offset(start))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { complex_selectors })
+    }
+
+    /// returns true if the element node matches this selector,
+    /// `ancestors` are the element ancestors of the node starting from the root
+    pub(crate) fn matches<MSG>(&self, node: &Node<MSG>, ancestors: &[&Node<MSG>]) -> bool {
+        self.complex_selectors
+            .iter()
+            .any(|complex| complex.matches(node, ancestors))
+    }
+}
+
+/// the ranges of the selectors in a selector list, which are separated by the commas
+/// that are not inside the brackets, parentheses or quotes
+fn split_selector_list(chars: &[char]) -> Vec<(usize, usize)> {
+    let mut ranges = vec![];
+    let mut start = 0;
+    let mut depth = 0;
+    let mut quote = None;
+    for (pos, &ch) in chars.iter().enumerate() {
+        match (quote, ch) {
+            (Some(q), ch) if ch == q => quote = None,
+            (Some(_), _) => (),
+            (None, '"' | '\'') => quote = Some(ch),
+            (None, '[' | '(') => depth += 1,
+            (None, ']' | ')') => depth -= 1,
+            (None, ',') if depth == 0 => {
+                ranges.push((start, pos));
+                start = pos + 1;
+            }
+            _ => (),
+        }
+    }
+    ranges.push((start, chars.len()));
+    ranges
+}
+
+impl SelectorError {
+    /// the error with its position offset by the start of the selector in the selector list
+    fn offset(self, start: usize) -> Self {
+        match self {
+            Self::UnexpectedChar(ch, pos) => Self::UnexpectedChar(ch, start + pos),
+            error => error,
+        }
+    }
+}
+
+impl ComplexSelector {
+    fn parse(chars: &[char]) -> Result<Self, SelectorError> {
+        let mut compounds = vec![];
+        let mut combinator = Combinator::None;
+        let mut pos = 0;
+        while pos < chars.len() {
+            let ch = chars[pos];
+            if ch.is_whitespace() {
+                if combinator == Combinator::None && !compounds.is_empty() {
+                    combinator = Combinator::Descendant;
+                }
+                pos += 1;
+            } else if ch == '>' {
+                if compounds.is_empty() {
+                    return Err(SelectorError::UnexpectedChar(ch, pos));
+                }
+                combinator = Combinator::Child;
+                pos += 1;
+            } else {
+                if !compounds.is_empty() && combinator == Combinator::None {
+                    return Err(SelectorError::UnexpectedChar(ch, pos));
+                }
+                let (compound, next) = CompoundSelector::parse(chars, pos)?;
+                compounds.push((combinator, compound));
+                combinator = Combinator::None;
+                pos = next;
+            }
+        }
+        if compounds.is_empty() {
+            return Err(SelectorError::EmptySelector);
+        }
+        if combinator == Combinator::Child {
+            return Err(SelectorError::UnexpectedChar('>', chars.len()));
+        }
+        Ok(Self { compounds })
+    }
+
+    fn matches<MSG>(&self, node: &Node<MSG>, ancestors: &[&Node<MSG>]) -> bool {
+        let Some(((combinator, last), rest)) = self.compounds.split_last() else {
+            return false;
+        };
+        last.matches(node) && Self::matches_ancestors(rest, *combinator, ancestors)
+    }
+
+    /// match the remaining compound selectors from right to left against the ancestors
+    fn matches_ancestors<MSG>(
+        compounds: &[(Combinator, CompoundSelector)],
+        combinator: Combinator,
+        ancestors: &[&Node<MSG>],
+    ) -> bool {
+        let Some(((next_combinator, compound), rest)) = compounds.split_last() else {
+            return true;
+        };
+        match combinator {
+            Combinator::Child => ancestors.split_last().is_some_and(|(parent, above)| {
+                compound.matches(parent) && Self::matches_ancestors(rest, *next_combinator, above)
+            }),
+            Combinator::Descendant => (0..ancestors.len()).rev().any(|i| {
+                compound.matches(ancestors[i])
+                    && Self::matches_ancestors(rest, *next_combinator, &ancestors[..i])
+            }),
+            Combinator::None => unreachable!("only the first compound has no combinator"),
+        }
+    }
+}
+
+impl CompoundSelector {
+    /// parse the compound selector starting at `pos`, returns the position after it
+    fn parse(chars: &[char], mut pos: usize) -> Result<(Self, usize), SelectorError> {
+        let mut compound = Self::default();
+        let is_name_char = |ch: char| ch.is_alphanumeric() || ch == '-' || ch == '_';
+        let read_name = |pos: usize| -> (String, usize) {
+            let end = (pos..chars.len())
+                .find(|i| !is_name_char(chars[*i]))
+                .unwrap_or(chars.len());
+            (chars[pos..end].iter().collect(), end)
+        };
+        if chars[pos] == '*' {
+            pos += 1;
+        } else if is_name_char(chars[pos]) {
+            let (tag, next) = read_name(pos);
+            compound.tag = Some(tag);
+            pos = next;
+        }
+        while pos < chars.len() {
+            match chars[pos] {
+                '.' | '#' => {
+                    let (name, next) = read_name(pos + 1);
+                    if name.is_empty() {
+                        return Err(SelectorError::UnexpectedChar(chars[pos], pos));
+                    }
+                    if chars[pos] == '.' {
+                        compound.classes.push(name);
+                    } else {
+                        compound.id = Some(name);
+                    }
+                    pos = next;
+                }
+                '[' => {
+                    let end =
+                        closing_bracket(chars, pos).ok_or(SelectorError::UnclosedAttribute)?;
+                    let inner: String = chars[pos + 1..end].iter().collect();
+                    let attribute = match inner.split_once('=') {
+                        Some((name, value)) => {
+                            let value = value.trim().trim_matches(|c| c == '"' || c == '\'');
+                            (name.trim().to_string(), Some(value.to_string()))
+                        }
+                        None => (inner.trim().to_string(), None),
+                    };
+                    compound.attributes.push(attribute);
+                    pos = end + 1;
+                }
+                ch if ch.is_whitespace() || ch == '>' => break,
+                ch => return Err(SelectorError::UnexpectedChar(ch, pos)),
+            }
+        }
+        Ok((compound, pos))
+    }
+
+    fn matches<MSG>(&self, node: &Node<MSG>) -> bool {
+        let Some(tag) = node.tag() else {
+            return false;
+        };
+        if let Some(expected_tag) = &self.tag {
+            if !tag.eq_ignore_ascii_case(expected_tag) {
+                return false;
+            }
+        }
+        if let Some(id) = &self.id {
+            if node.get_attribute("id").as_ref() != Some(id) {
+                return false;
+            }
+        }
+        if !self.classes.iter().all(|class| node.has_class(class)) {
+            return false;
+        }
+        self.attributes.iter().all(
+            |(name, expected)| match (node.get_attribute(name), expected) {
+                (Some(value), Some(expected)) => value == *expected,
+                (Some(_), None) => true,
+                (None, _) => false,
+            },
+        )
+    }
+}
+
+/// the position of the `]` which closes the attribute selector starting at `start`,
+/// skipping the `]` in the quoted value
+fn closing_bracket(chars: &[char], start: usize) -> Option<usize> {
+    let mut quote = None;
+    for (pos, &ch) in chars.iter().enumerate().skip(start + 1) {
+        match (quote, ch) {
+            (Some(q), ch) if ch == q => quote = None,
+            (Some(_), _) => (),
+            (None, '"' | '\'') => quote = Some(ch),
+            (None, ']') => return Some(pos),
+            _ => (),
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_compound_and_combinators() {
+        let selector = Selector::parse("ul > li.active[data-id='1'] a").unwrap();
+        let compounds = &selector.complex_selectors[0].compounds;
+        assert_eq!(compounds.len(), 3);
+        assert_eq!(compounds[1].0, Combinator::Child);
+        assert_eq!(compounds[1].1.classes, vec!["active"]);
+        assert_eq!(
+            compounds[1].1.attributes,
+            vec![("data-id".to_string(), Some("1".to_string()))]
+        );
+        assert_eq!(compounds[2].0, Combinator::Descendant);
+    }
+
+    #[test]
+    fn invalid_selectors() {
+        assert_eq!(Selector::parse(""), Err(SelectorError::EmptySelector));
+        assert_eq!(
+            Selector::parse("ul >"),
+            Err(SelectorError::UnexpectedChar('>', 4))
+        );
+        assert_eq!(
+            Selector::parse("a[href"),
+            Err(SelectorError::UnclosedAttribute)
+        );
+        assert_eq!(
+            Selector::parse("a, b.>"),
+            Err(SelectorError::UnexpectedChar('.', 4))
+        );
+    }
+
+    #[test]
+    fn commas_in_quoted_values_are_not_separators() {
+        let selector = Selector::parse("a[data-x='a,b'], b[title=\"x]y\"]").unwrap();
+        assert_eq!(selector.complex_selectors.len(), 2);
+        assert_eq!(
+            selector.complex_selectors[0].compounds[0].1.attributes,
+            vec![("data-x".to_string(), Some("a,b".to_string()))]
+        );
+        assert_eq!(
+            selector.complex_selectors[1].compounds[0].1.attributes,
+            vec![("title".to_string(), Some("x]y".to_string()))]
+        );
+    }
+}
@@ -0,0 +1,119 @@
+//! Snapshot testing of the rendered views
+//!
+//! The view is rendered with nice indention, where the event listeners are rendered as
+//! `on:<event>` attributes, ie: `on:click`, and compared against the
+//! snapshot file `tests/snapshots/<name>.snap` of the crate being tested.
+//!
+//! Set the environment variable `SAURON_UPDATE_SNAPSHOTS=1` to create the missing snapshots
+//! and overwrite the snapshots that don't match the rendered view.
+use crate::vdom::Node;
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+/// the environment variable which enables the update mode of the snapshots
+pub const UPDATE_SNAPSHOTS_ENV: &str = "SAURON_UPDATE_SNAPSHOTS";
+
+/// assert that the rendered view matches the snapshot with the name `name`.
+///
+/// # Panics
+/// if the snapshot doesn't exist or the rendered view doesn't match the snapshot,
+/// the panic message contains the diff of the snapshot and the rendered view.
+/// No panic occurs when the update mode is enabled, instead the snapshot is written.
+pub fn assert_snapshot<MSG>(name: &str, node: &Node<MSG>) {
+    let dir = snapshot_dir();
+    assert_snapshot_in(&dir, name, node)
+}
+
+/// assert that the rendered view matches the snapshot with the name `name` located in `dir`
+pub fn assert_snapshot_in<MSG>(dir: &Path, name: &str, node: &Node<MSG>) {
+    let path = dir.join(format!("{name}.snap"));
+    let actual = format!("{}\n", node.render_to_snapshot());
+    let expected = fs::read_to_string(&path).ok();
+
+    if expected.as_deref() == Some(actual.as_str()) {
+        return;
+    }
+    if is_update_mode() {
+        fs::create_dir_all(dir).expect("must create the snapshot directory");
+        fs::write(&path, &actual).expect("must write the snapshot");
+        return;
+    }
+    match expected {
+        Some(expected) => panic!(
+            "snapshot `{name}` does not match the rendered view ({}):\n{}\n\
+            rerun the test with `{UPDATE_SNAPSHOTS_ENV}=1` to accept the changes",
+            path.display(),
+            diff_lines(&expected, &actual)
+        ),
+        None => panic!(
+            "snapshot `{name}` does not exist ({}), the rendered view is:\n{actual}\n\
+            rerun the test with `{UPDATE_SNAPSHOTS_ENV}=1` to create it",
+            path.display()
+        ),
+    }
+}
+
+fn is_update_mode() -> bool {
+    env::var(UPDATE_SNAPSHOTS_ENV).is_ok_and(|v| !v.is_empty() && v != "0")
+}
+
+/// the `tests/snapshots` directory of the crate being tested
+fn snapshot_dir() -> PathBuf {
+    let manifest_dir = env::var_os("CARGO_MANIFEST_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_default();
+    manifest_dir.join("tests").join("snapshots")
+}
+
+/// a line diff of the expected and actual text,
+/// removed lines are prefixed with `-` and added lines with `+`
+fn diff_lines(expected: &str, actual: &str) -> String {
+    let old: Vec<&str> = expected.lines().collect();
+    let new: Vec<&str> = actual.lines().collect();
+
+    // lengths of the longest common subsequence of old[i..] and new[j..]
+    let mut lcs = vec![vec![0; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut lines = vec![];
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            lines.push(format!("  {}", old[i]));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            lines.push(format!("- {}", old[i]));
+            i += 1;
+        } else {
+            lines.push(format!("+ {}", new[j]));
+            j += 1;
+        }
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_diff() {
+        let expected = "<div>\n  <span>a</span>\n  <span>b</span>\n</div>";
+        let actual = "<div>\n  <span>a</span>\n  <span>c</span>\n</div>";
+        assert_eq!(
+            diff_lines(expected, actual),
+            "  <div>\n    <span>a</span>\n-   <span>b</span>\n+   <span>c</span>\n  </div>"
+        );
+    }
+}
@@ -11,21 +11,26 @@ pub use attribute::Callback;
 pub use attribute::GroupedAttributeValues;
 pub use element::Element;
 pub use leaf::Leaf;
+pub use portal::{Portal, PortalTarget};
 pub use templated_view::TemplatedView;
 
 mod attribute;
 mod element;
 mod leaf;
 mod map_msg;
+mod portal;
 mod render;
 mod templated_view;
 
 pub use attribute::special::{
-    key, replace, skip, skip_criteria, KEY, REPLACE, SKIP, SKIP_CRITERIA,
+    animate_moves, key, replace, skip, skip_criteria, transition, ANIMATE_MOVES, KEY, REPLACE,
+    SKIP, SKIP_CRITERIA, TRANSITION,
 };
 #[cfg(feature = "ensure-attr-set")]
 pub(crate) use attribute::special::{CHECKED, DISABLED, OPEN, VALUE};
-pub use attribute::{attr, attr_ns, AttributeName, AttributeValue, Namespace, Style, Tag, Value};
+pub use attribute::{
+    attr, attr_ns, AttributeName, AttributeValue, ListenerOptions, Namespace, Style, Tag, Value,
+};
 pub use diff::{diff, diff_recursive};
 pub use node::{element, element_ns, fragment, leaf, node_list, Node};
 pub use patch::{Patch, PatchType, TreePath};
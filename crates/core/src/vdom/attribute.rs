@@ -6,7 +6,7 @@ use derive_where::derive_where;
 use indexmap::IndexMap;
 
 pub use attribute_value::AttributeValue;
-pub use callback::Callback;
+pub use callback::{Callback, ListenerOptions};
 pub use style::Style;
 pub use value::Value;
 
@@ -101,6 +101,72 @@ impl<MSG> Attribute<MSG> {
             .unwrap_or(false)
     }
 
+    /// attach the event listeners of this attribute with these options
+    pub fn with_listener_options(self, options: ListenerOptions) -> Self {
+        Attribute {
+            namespace: self.namespace,
+            name: self.name,
+            value: self
+                .value
+                .into_iter()
+                .map(|v| match v {
+                    AttributeValue::EventListener(cb) => {
+                        AttributeValue::EventListener(cb.with_options(options))
+                    }
+                    AttributeValue::ComponentEventListener(cb) => {
+                        AttributeValue::ComponentEventListener(cb.with_options(options))
+                    }
+                    _ => v,
+                })
+                .collect(),
+        }
+    }
+
+    /// call the event listeners of this attribute in the capture phase
+    /// # Example
+    /// ```rust
+    /// use sauron::*;
+    /// let close_menu: Attribute<()> = on_click(|_| ()).capture();
+    /// ```
+    pub fn capture(self) -> Self {
+        let options = self.listener_options();
+        self.with_listener_options(ListenerOptions {
+            capture: true,
+            ..options
+        })
+    }
+
+    /// mark the event listeners of this attribute as passive,
+    /// they will not call `prevent_default` such as in scroll, touch and wheel events
+    pub fn passive(self) -> Self {
+        let options = self.listener_options();
+        self.with_listener_options(ListenerOptions {
+            passive: true,
+            ..options
+        })
+    }
+
+    /// the event listeners of this attribute are removed after they are called the first time
+    pub fn once(self) -> Self {
+        let options = self.listener_options();
+        self.with_listener_options(ListenerOptions {
+            once: true,
+            ..options
+        })
+    }
+
+    /// the options of the event listeners of this attribute
+    pub fn listener_options(&self) -> ListenerOptions {
+        self.value
+            .iter()
+            .find_map(|v| match v {
+                AttributeValue::EventListener(cb) => Some(cb.options()),
+                AttributeValue::ComponentEventListener(cb) => Some(cb.options()),
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+
     /// grouped values into plain, function calls, styles and event listeners
     pub(crate) fn group_values(attr: &Attribute<MSG>) -> GroupedAttributeValues<MSG> {
         let mut listeners = vec![];
@@ -24,14 +24,35 @@ use std::{any::TypeId, cell::RefCell, fmt, rc::Rc};
 ///         callback is necessary.
 ///
 pub struct Callback<IN, OUT> {
-    /// the function to be executed
-    func: Rc<RefCell<dyn FnMut(IN) -> OUT>>,
+    /// the function to be executed, returns None when it does not accept the input
+    func: Rc<RefCell<dyn FnMut(IN) -> Option<OUT>>>,
     /// the type_id of the function
     func_type_id: TypeId,
     /// the type type_id of the event this callback will be attached to
     event_type_id: TypeId,
     /// the type_id of the return type of this callback when executed.
     msg_type_id: TypeId,
+    /// the options used when the callback is attached as an event listener
+    options: ListenerOptions,
+}
+
+/// The options used when attaching an event listener to an element
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ListenerOptions {
+    /// the listener is called in the capture phase, before the listeners of the descendants
+    pub capture: bool,
+    /// the listener will never call `prevent_default`, which allows the browser to scroll
+    /// without waiting for the listener
+    pub passive: bool,
+    /// the listener is removed after it is called the first time
+    pub once: bool,
+}
+
+impl ListenerOptions {
+    /// returns true if these are the default options of an event listener
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
 }
 
 impl<IN, F, OUT> From<F> for Callback<IN, OUT>
@@ -40,12 +61,13 @@ where
     OUT: 'static,
     IN: 'static,
 {
-    fn from(func: F) -> Self {
+    fn from(mut func: F) -> Self {
         Self {
-            func: Rc::new(RefCell::new(func)),
+            func: Rc::new(RefCell::new(move |input| Some(func(input)))),
             func_type_id: TypeId::of::<F>(),
             event_type_id: TypeId::of::<IN>(),
             msg_type_id: TypeId::of::<OUT>(),
+            options: ListenerOptions::default(),
         }
     }
 }
@@ -59,19 +81,56 @@ impl<IN, OUT> fmt::Debug for Callback<IN, OUT> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
-            "in: {:?}, out: {:?}, func: {:?}",
-            self.event_type_id, self.msg_type_id, self.func_type_id
+            "in: {:?}, out: {:?}, func: {:?}, options: {:?}",
+            self.event_type_id, self.msg_type_id, self.func_type_id, self.options
         )
     }
 }
 
+impl<IN, OUT> Callback<IN, OUT> {
+    /// return the options used when this callback is attached as an event listener
+    pub fn options(&self) -> ListenerOptions {
+        self.options
+    }
+
+    /// use these options when this callback is attached as an event listener
+    pub fn with_options(mut self, options: ListenerOptions) -> Self {
+        self.options = options;
+        self
+    }
+}
+
 impl<IN, OUT> Callback<IN, OUT>
 where
     IN: 'static,
     OUT: 'static,
 {
+    /// create a callback which only accepts some of the input,
+    /// ie: the typed event listeners does not accept the events which are not of their type
+    pub(crate) fn from_partial<F>(func: F) -> Self
+    where
+        F: FnMut(IN) -> Option<OUT> + 'static,
+    {
+        Self {
+            func: Rc::new(RefCell::new(func)),
+            func_type_id: TypeId::of::<F>(),
+            event_type_id: TypeId::of::<IN>(),
+            msg_type_id: TypeId::of::<OUT>(),
+            options: ListenerOptions::default(),
+        }
+    }
+
     /// This method calls the actual callback.
+    ///
+    /// Note: this panics if the callback does not accept the input, use [`Callback::try_emit`]
+    /// when the input may not be accepted.
     pub fn emit(&self, input: IN) -> OUT {
+        self.try_emit(input)
+            .expect("the callback must accept the input")
+    }
+
+    /// call the actual callback, returns None if the callback does not accept the input
+    pub fn try_emit(&self, input: IN) -> Option<OUT> {
         (self.func.borrow_mut())(input)
     }
 
@@ -83,15 +142,14 @@ where
         MSG2: 'static,
     {
         let source_func_type_id = self.func_type_id;
-        let cb = move |input| {
-            let out = self.emit(input);
-            cb2(out)
-        };
+        let options = self.options;
+        let cb = move |input| self.try_emit(input).map(&cb2);
         Callback {
             func: Rc::new(RefCell::new(cb)),
             func_type_id: source_func_type_id,
             event_type_id: TypeId::of::<IN>(),
             msg_type_id: TypeId::of::<OUT>(),
+            options,
         }
     }
 }
@@ -109,16 +167,19 @@ impl<IN, OUT> Clone for Callback<IN, OUT> {
             func_type_id: self.func_type_id,
             event_type_id: self.event_type_id,
             msg_type_id: self.msg_type_id,
+            options: self.options,
         }
     }
 }
 
 /// Compare if the callbacks are equal
 /// Note, we are only comparing the type_id of the function, the input and the output
+/// and the listener options
 impl<IN, OUT> PartialEq for Callback<IN, OUT> {
     fn eq(&self, other: &Self) -> bool {
         self.event_type_id == other.event_type_id
             && self.msg_type_id == other.msg_type_id
             && self.func_type_id == other.func_type_id
+            && self.options == other.options
     }
 }
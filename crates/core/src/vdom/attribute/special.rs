@@ -23,6 +23,14 @@ pub static SKIP: &AttributeName = &"skip";
 /// The skip criteria attribute
 pub static SKIP_CRITERIA: &AttributeName = &"skip_criteria";
 
+/// NOTE: this is specific to sauron framework
+/// The transition attribute
+pub static TRANSITION: &AttributeName = &"transition";
+
+/// NOTE: this is specific to sauron framework
+/// The animate moves attribute
+pub static ANIMATE_MOVES: &AttributeName = &"animate_moves";
+
 ///
 /// NOTE: The following attributes have special behaviour in the dom, the framework
 /// need to call the specific methods to reflect the state of this attribute to the element
@@ -86,3 +94,47 @@ where
 pub fn replace<MSG>(v: bool) -> Attribute<MSG> {
     attr(REPLACE, v)
 }
+
+/// the enter and leave classes derived from the transition `name` are applied to the element
+/// when it is inserted or removed from the DOM.
+///
+/// Entering: `{name}-enter-from` and `{name}-enter-active` are added when the element is inserted,
+/// on the next frame `{name}-enter-from` is swapped with `{name}-enter-to`, both of the
+/// remaining classes are then removed when the transition or animation has ended.
+///
+/// Leaving: `{name}-leave-from` and `{name}-leave-active` are added when the element is removed,
+/// on the next frame `{name}-leave-from` is swapped with `{name}-leave-to`.
+/// The element is kept in the DOM until the transition or animation has ended.
+///
+/// # Examples
+/// ```rust
+/// use sauron::{*, html::attributes::transition};
+///
+/// let item: Node<()> = li([key(1), transition("fade")], [text("item1")]);
+/// ```
+pub fn transition<V, MSG>(name: V) -> Attribute<MSG>
+where
+    V: Into<Value>,
+{
+    attr(TRANSITION, name)
+}
+
+/// the reordered keyed children of the element with this attribute are animated
+/// from their old position to their new position, using the FLIP technique.
+///
+/// The bounding rect of each child is recorded before the patches are applied,
+/// the children are then translated back to their old position and transitioned
+/// into their new position in `duration_ms` milliseconds.
+///
+/// # Examples
+/// ```rust
+/// use sauron::{*, html::attributes::animate_moves};
+///
+/// let list: Node<()> = ul([animate_moves(300)], [
+///     li([key(1)], [text("item1")]),
+///     li([key(2)], [text("item2")]),
+/// ]);
+/// ```
+pub fn animate_moves<MSG>(duration_ms: u32) -> Attribute<MSG> {
+    attr(ANIMATE_MOVES, duration_ms)
+}
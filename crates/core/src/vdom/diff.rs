@@ -174,14 +174,17 @@ pub fn diff_recursive<'a, MSG>(
                 (Leaf::NodeList(_old_elements), Leaf::NodeList(_new_elements)) => {
                     panic!("Node list must have already unrolled when creating an element");
                 }
+                // the view is created from the same state as the previous view
+                (Leaf::StatelessComponent(old_comp), Leaf::StatelessComponent(new_comp))
+                    if old_comp.is_unchanged(new_comp) => {}
                 (Leaf::StatelessComponent(old_comp), Leaf::StatelessComponent(new_comp)) => {
                     let new_path = SkipPath {
                         path: path.path.clone(),
-                        skip_diff: old_comp.view.skip_diff(),
+                        skip_diff: old_comp.view().skip_diff(),
                     };
 
-                    let old_real_view = old_comp.view.unwrap_template_ref();
-                    let new_real_view = new_comp.view.unwrap_template_ref();
+                    let old_real_view = old_comp.view().unwrap_template_ref();
+                    let new_real_view = new_comp.view().unwrap_template_ref();
 
                     assert!(
                         !old_real_view.is_template(),
@@ -211,6 +214,15 @@ pub fn diff_recursive<'a, MSG>(
                     }
                     patches.extend(patch);
                 }
+                (Leaf::Portal(old_portal), Leaf::Portal(new_portal)) => {
+                    if old_portal.target != new_portal.target {
+                        patches.push(Patch::replace_node(None, path.path.clone(), vec![new_node]));
+                    } else {
+                        let patch =
+                            diff_nodes(None, &old_portal.children, &new_portal.children, path);
+                        patches.extend(patch);
+                    }
+                }
                 (Leaf::TemplatedView(_old_view), _) => {
                     unreachable!("templated view should not be diffed..")
                 }
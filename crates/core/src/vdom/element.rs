@@ -4,6 +4,7 @@ use super::{Attribute, Node};
 use crate::vdom::AttributeValue;
 use crate::vdom::Leaf;
 use crate::vdom::Value;
+use crate::vdom::TRANSITION;
 use derive_where::derive_where;
 use indexmap::IndexMap;
 
@@ -209,4 +210,9 @@ impl<MSG> Element<MSG> {
     pub fn has_mount_callback(&self) -> bool {
         self.attributes().iter().any(|a| a.is_mount_callback())
     }
+
+    /// return true if this element has a transition attribute
+    pub fn has_transition(&self) -> bool {
+        self.attributes().iter().any(|a| a.name == *TRANSITION)
+    }
 }
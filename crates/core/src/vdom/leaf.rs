@@ -5,6 +5,7 @@ use crate::vdom::Attribute;
 use crate::vdom::AttributeName;
 use crate::vdom::AttributeValue;
 use crate::vdom::Node;
+use crate::vdom::Portal;
 use crate::vdom::TemplatedView;
 use derive_where::derive_where;
 use std::borrow::Cow;
@@ -33,6 +34,8 @@ pub enum Leaf<MSG> {
     StatelessComponent(StatelessModel<MSG>),
     /// a view where a template and skip diff is provided
     TemplatedView(TemplatedView<MSG>),
+    /// A node which children are rendered into a different container
+    Portal(Portal<MSG>),
 }
 
 impl<MSG> PartialEq for Leaf<MSG> {
@@ -47,6 +50,7 @@ impl<MSG> PartialEq for Leaf<MSG> {
             #[cfg(feature = "with-dom")]
             (Self::StatefulComponent(v), Self::StatefulComponent(o)) => v == o,
             (Self::StatelessComponent(v), Self::StatelessComponent(o)) => v == o,
+            (Self::Portal(v), Self::Portal(o)) => v == o,
             _ => false,
         }
     }
@@ -115,6 +115,7 @@ impl<MSG> Leaf<MSG> {
             Self::StatefulComponent(v) => Leaf::StatefulComponent(v.map_msg(cb)),
             Self::StatelessComponent(v) => Leaf::StatelessComponent(v.map_msg(cb)),
             Self::TemplatedView(v) => Leaf::TemplatedView(v.map_msg(cb)),
+            Self::Portal(v) => Leaf::Portal(v.map_msg(cb)),
         }
     }
 }
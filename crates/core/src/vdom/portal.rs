@@ -0,0 +1,61 @@
+use crate::vdom::Node;
+use derive_where::derive_where;
+use std::borrow::Cow;
+
+/// The container where the children of a portal are rendered into
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PortalTarget {
+    /// the first element that matches the css selector, ie: `body`, `#modals`
+    Selector(Cow<'static, str>),
+    /// a reference to the container element
+    #[cfg(feature = "with-dom")]
+    Element(web_sys::Element),
+}
+
+impl From<&'static str> for PortalTarget {
+    fn from(selector: &'static str) -> Self {
+        Self::Selector(Cow::from(selector))
+    }
+}
+
+impl From<String> for PortalTarget {
+    fn from(selector: String) -> Self {
+        Self::Selector(Cow::from(selector))
+    }
+}
+
+#[cfg(feature = "with-dom")]
+impl From<web_sys::Element> for PortalTarget {
+    fn from(element: web_sys::Element) -> Self {
+        Self::Element(element)
+    }
+}
+
+/// A node which children are created and patched inside a different container in the DOM,
+/// while the events of the children are still dispatched to the app which owns the portal.
+#[derive_where(Clone, Debug, PartialEq)]
+pub struct Portal<MSG> {
+    /// the container where the children are rendered into
+    pub target: PortalTarget,
+    /// the children of the portal
+    pub children: Vec<Node<MSG>>,
+}
+
+impl<MSG> Portal<MSG> {
+    /// map the msg of this Portal such that `Portal<MSG>` becomes `Portal<MSG2>`
+    pub fn map_msg<F, MSG2>(self, cb: F) -> Portal<MSG2>
+    where
+        F: Fn(MSG) -> MSG2 + Clone + 'static,
+        MSG2: 'static,
+        MSG: 'static,
+    {
+        Portal {
+            target: self.target,
+            children: self
+                .children
+                .into_iter()
+                .map(|child| child.map_msg(cb.clone()))
+                .collect(),
+        }
+    }
+}
@@ -11,6 +11,25 @@ use std::fmt;
 
 const DEFAULT_INDENT_SIZE: usize = 2;
 
+/// how the nodes are rendered
+#[derive(Debug, Clone, Copy)]
+struct RenderOptions {
+    /// no new_lines, no indents
+    compressed: bool,
+    /// render the event listeners as `on:<event>` attributes,
+    /// which gives a symbolic representation of the view useful for snapshots
+    symbolic_listeners: bool,
+}
+
+impl RenderOptions {
+    fn new(compressed: bool) -> Self {
+        Self {
+            compressed,
+            symbolic_listeners: false,
+        }
+    }
+}
+
 /// add an indent if applicable
 fn maybe_indent(buffer: &mut dyn fmt::Write, indent: usize, compressed: bool) -> fmt::Result {
     if !compressed {
@@ -47,10 +66,19 @@ impl<MSG> Node<MSG> {
         buffer: &mut dyn fmt::Write,
         indent: usize,
         compressed: bool,
+    ) -> fmt::Result {
+        self.render_with_options(buffer, indent, RenderOptions::new(compressed))
+    }
+
+    fn render_with_options(
+        &self,
+        buffer: &mut dyn fmt::Write,
+        indent: usize,
+        options: RenderOptions,
     ) -> fmt::Result {
         match self {
-            Node::Element(element) => element.render_with_indent(buffer, indent, compressed),
-            Node::Leaf(leaf) => leaf.render_with_indent(buffer, indent, compressed),
+            Node::Element(element) => element.render_with_options(buffer, indent, options),
+            Node::Leaf(leaf) => leaf.render_with_options(buffer, indent, options),
         }
     }
 
@@ -77,6 +105,21 @@ impl<MSG> Node<MSG> {
         self.render(&mut buffer).expect("must render");
         buffer
     }
+
+    /// render to string with nice indention, where the event listeners are rendered
+    /// symbolically as `on:<event>` attributes, ie: `on:click`.
+    /// The output is stable, such that it can be used for snapshot testing.
+    #[cfg(any(test, feature = "testing"))]
+    pub(crate) fn render_to_snapshot(&self) -> String {
+        let mut buffer = String::new();
+        let options = RenderOptions {
+            compressed: false,
+            symbolic_listeners: true,
+        };
+        self.render_with_options(&mut buffer, 0, options)
+            .expect("must render");
+        buffer
+    }
 }
 
 impl<MSG> Leaf<MSG> {
@@ -86,6 +129,15 @@ impl<MSG> Leaf<MSG> {
         buffer: &mut dyn fmt::Write,
         indent: usize,
         compressed: bool,
+    ) -> fmt::Result {
+        self.render_with_options(buffer, indent, RenderOptions::new(compressed))
+    }
+
+    fn render_with_options(
+        &self,
+        buffer: &mut dyn fmt::Write,
+        indent: usize,
+        options: RenderOptions,
     ) -> fmt::Result {
         match self {
             Leaf::Text(text) => {
@@ -102,21 +154,31 @@ impl<MSG> Leaf<MSG> {
             }
             Leaf::Fragment(nodes) => {
                 for node in nodes {
-                    node.render_with_indent(buffer, indent, compressed)?;
+                    node.render_with_options(buffer, indent, options)?;
                 }
                 Ok(())
             }
             Leaf::NodeList(node_list) => {
                 for node in node_list {
-                    node.render_with_indent(buffer, indent, compressed)?;
+                    node.render_with_options(buffer, indent, options)?;
                 }
                 Ok(())
             }
             Leaf::StatefulComponent(_comp) => {
                 write!(buffer, "<!-- stateful component -->")
             }
-            Leaf::StatelessComponent(comp) => comp.view.render(buffer),
-            Leaf::TemplatedView(view) => view.view.render(buffer),
+            Leaf::StatelessComponent(comp) => {
+                comp.view().render_with_options(buffer, indent, options)
+            }
+            Leaf::TemplatedView(view) => view.view.render_with_options(buffer, indent, options),
+            // the children are rendered in place, since there is no other container
+            // when rendering to a string
+            Leaf::Portal(portal) => {
+                for child in portal.children.iter() {
+                    child.render_with_options(buffer, indent, options)?;
+                }
+                Ok(())
+            }
         }
     }
 }
@@ -129,6 +191,16 @@ impl<MSG> Element<MSG> {
         indent: usize,
         compressed: bool,
     ) -> fmt::Result {
+        self.render_with_options(buffer, indent, RenderOptions::new(compressed))
+    }
+
+    fn render_with_options(
+        &self,
+        buffer: &mut dyn fmt::Write,
+        indent: usize,
+        options: RenderOptions,
+    ) -> fmt::Result {
+        let compressed = options.compressed;
         write!(buffer, "<{}", self.tag())?;
 
         let merged_attributes: Vec<Attribute<MSG>> =
@@ -136,7 +208,7 @@ impl<MSG> Element<MSG> {
 
         for attr in &merged_attributes {
             write!(buffer, " ")?;
-            attr.render(buffer)?;
+            attr.render(buffer, options)?;
         }
 
         if self.self_closing {
@@ -155,12 +227,12 @@ impl<MSG> Element<MSG> {
         if is_lone_child_text_node {
             first_child
                 .unwrap()
-                .render_with_indent(buffer, indent, compressed)?;
+                .render_with_options(buffer, indent, options)?;
         } else {
             // otherwise print all child nodes with each line and indented
             for child in self.children() {
                 maybe_indent(buffer, indent + 1, compressed)?;
-                child.render_with_indent(buffer, indent + 1, compressed)?;
+                child.render_with_options(buffer, indent + 1, options)?;
             }
         }
 
@@ -178,13 +250,21 @@ impl<MSG> Element<MSG> {
 
 impl<MSG> Attribute<MSG> {
     /// render attributes
-    fn render(&self, buffer: &mut dyn fmt::Write) -> fmt::Result {
+    fn render(&self, buffer: &mut dyn fmt::Write, options: RenderOptions) -> fmt::Result {
         let GroupedAttributeValues {
+            listeners,
             plain_values,
             styles,
             ..
         } = Attribute::group_values(self);
 
+        let has_symbolic_listener = options.symbolic_listeners && !listeners.is_empty();
+        if has_symbolic_listener {
+            write!(buffer, "on:{}", self.name())?;
+        }
+        // the values are separated from the symbolic listener of the same attribute name
+        let separator = if has_symbolic_listener { " " } else { "" };
+
         // These are attribute values which specifies the state of the element
         // regardless of it's value.
         // This is counter-intuitive to what we are trying to do, therefore
@@ -204,10 +284,15 @@ impl<MSG> Attribute<MSG> {
 
         if !should_skip_attribute {
             if let Some(merged_plain_values) = Value::merge_to_string(plain_values) {
-                write!(buffer, "{}=\"{}\"", self.name(), merged_plain_values)?;
+                write!(
+                    buffer,
+                    "{separator}{}=\"{}\"",
+                    self.name(),
+                    merged_plain_values
+                )?;
             }
             if let Some(merged_styles) = Style::merge_to_string(styles) {
-                write!(buffer, "{}=\"{}\"", self.name(), merged_styles)?;
+                write!(buffer, "{separator}{}=\"{}\"", self.name(), merged_styles)?;
             }
         }
         Ok(())
@@ -216,7 +301,8 @@ impl<MSG> Attribute<MSG> {
     /// render compressed html to string
     pub fn render_to_string(&self) -> String {
         let mut buffer = String::new();
-        self.render(&mut buffer).expect("must render");
+        self.render(&mut buffer, RenderOptions::new(true))
+            .expect("must render");
         buffer
     }
 }
@@ -246,6 +332,24 @@ mod test {
         );
     }
 
+    #[cfg(feature = "with-dom")]
+    #[test]
+    fn test_render_to_snapshot_listeners() {
+        use crate::dom::events::on;
+        let view: Node<()> = button(
+            vec![on("click", |_| ()), attr("click", "track"), class("save")],
+            vec![text("Save")],
+        );
+        assert_eq!(
+            view.render_to_snapshot(),
+            r#"<button on:click click="track" class="save">Save</button>"#
+        );
+        assert_eq!(
+            view.render_to_string_pretty(),
+            r#"<button click="track" class="save">Save</button>"#
+        );
+    }
+
     #[test]
     fn test_render_classes() {
         let view: Node<()> = div(vec![class("frame"), class("component")], vec![]);
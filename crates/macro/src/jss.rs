@@ -1,62 +1,117 @@
 use quote::{quote, ToTokens};
 use syn::parse::{Parse, ParseStream};
-use syn::{Expr, Result, Token};
+use syn::{Expr, ExprLit, Lit, LitStr, Result, Token};
 
 pub(crate) use style::Style;
 
 mod style;
 
+/// the at-rules which contains declarations instead of rules
+const DECLARATION_AT_RULES: [&str; 5] = [
+    "@font-face",
+    "@page",
+    "@property",
+    "@counter-style",
+    "@font-palette-values",
+];
+
 pub(crate) struct StyleSheetWithConditionalGroup {
     conditional_group: Expr,
-    selector_with_styles: Vec<SelectorWithStyle>,
+    rules: Vec<Rule>,
 }
 
 pub(crate) struct StyleSheet {
-    selector_with_styles: Vec<SelectorWithStyle>,
+    rules: Vec<Rule>,
+}
+
+enum Rule {
+    /// ```ignore
+    /// ".layer0" : {
+    ///     background_color: "red",
+    ///     "&:hover": { background_color: "blue" },
+    /// }
+    /// ```
+    Selector(SelectorWithStyle),
+    /// ```ignore
+    /// "@keyframes fade": {
+    ///     "from": { opacity: 0 },
+    ///     "to": { opacity: 1 },
+    /// }
+    /// ```
+    /// also `@media`, `@supports`, `@layer` and the other at-rules which contains rules
+    ConditionalGroup { at_rule: LitStr, rules: Vec<Rule> },
+    /// ```ignore
+    /// "@font-face": {
+    ///     font_family: "Fira",
+    ///     src: "url(fira.woff2)",
+    /// }
+    /// ```
+    Declarations { at_rule: LitStr, style: Style },
+    /// ```ignore
+    /// "@layer reset, base"
+    /// ```
+    Statement(LitStr),
 }
 
 /// ```ignore
 /// ".layer0" : {
 ///     background_color: "red",
 ///     border: (px(1), "solid", "green"),
+///     "& > .child": {
+///         display: "none",
+///     },
 /// }
 /// ```
 struct SelectorWithStyle {
     selector: Expr,
     style: Style,
+    /// the rules which selector is relative to this selector
+    nested: Vec<SelectorWithStyle>,
+}
+
+/// parse the rules until the input is empty
+fn parse_rules(input: ParseStream) -> Result<Vec<Rule>> {
+    let mut rules = vec![];
+    while !input.is_empty() {
+        rules.push(input.parse()?);
+        if input.peek(Token![,]) {
+            input.parse::<Token![,]>()?;
+        }
+    }
+    Ok(rules)
+}
+
+/// expression which joins the css blocks of the rules
+fn rules_to_tokens(rules: &[Rule]) -> proc_macro2::TokenStream {
+    let rules = rules.iter().map(|rule| rule.to_blocks_tokens());
+    quote! {
+        [#(#rules),*].concat().join("\n")
+    }
+}
+
+/// the string literal of the at-rule, ie: `"@media screen"`
+fn at_rule(expr: &Expr) -> Option<&LitStr> {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(lit_str),
+            ..
+        }) if lit_str.value().trim_start().starts_with('@') => Some(lit_str),
+        _ => None,
+    }
 }
 
 impl Parse for StyleSheet {
     /// $(<selector> : { <style> } (,)?)*
     fn parse(input: ParseStream) -> Result<Self> {
-        let mut selector_with_styles = vec![];
-        while !input.is_empty() {
-            selector_with_styles.push(input.parse()?);
-            if input.peek(Token![,]) {
-                input.parse::<Token![,]>()?;
-            }
-        }
         Ok(Self {
-            selector_with_styles,
+            rules: parse_rules(input)?,
         })
     }
 }
 
 impl ToTokens for StyleSheet {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
-        let expanded_selector_with_styles: Vec<_> = self
-            .selector_with_styles
-            .iter()
-            .map(|ss| {
-                quote! {
-                    #ss,
-                }
-            })
-            .collect();
-        let ss_tokens = proc_macro2::TokenStream::from_iter(expanded_selector_with_styles);
-        tokens.extend(quote! {
-            [#ss_tokens].join("\n")
-        });
+        tokens.extend(rules_to_tokens(&self.rules));
     }
 }
 
@@ -67,20 +122,13 @@ impl Parse for StyleSheetWithConditionalGroup {
         input.parse::<Token![:]>()?;
         let inner;
         syn::braced!(inner in input);
-        let mut selector_with_styles = vec![];
-        while !inner.is_empty() {
-            let selector_with_style = inner.parse()?;
-            selector_with_styles.push(selector_with_style);
-            if inner.peek(Token![,]) {
-                inner.parse::<Token![,]>()?;
-            }
-        }
+        let rules = parse_rules(&inner)?;
         if input.peek(Token![,]) {
             input.parse::<Token![,]>()?;
         }
         Ok(Self {
             conditional_group,
-            selector_with_styles,
+            rules,
         })
     }
 }
@@ -88,42 +136,197 @@ impl Parse for StyleSheetWithConditionalGroup {
 impl ToTokens for StyleSheetWithConditionalGroup {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
         let conditional_group = &self.conditional_group;
-        let expanded_selector_with_styles: Vec<_> = self
-            .selector_with_styles
-            .iter()
-            .map(|ss| {
-                quote! {
-                    #ss,
-                }
-            })
-            .collect();
-        let ss_tokens = proc_macro2::TokenStream::from_iter(expanded_selector_with_styles);
+        let rules = rules_to_tokens(&self.rules);
         tokens.extend(quote! {
-            format!("{} {{\n{}\n}}\n", #conditional_group, [#ss_tokens].join("\n"))
+            format!("{} {{\n{}\n}}\n", #conditional_group, #rules)
         });
     }
 }
 
-impl Parse for SelectorWithStyle {
+impl Parse for Rule {
     /// ```ignore
-    /// <expr> : { <style> }
+    /// <selector> : { <style> }
+    /// "@<at-rule>" : { <rules> | <style> }
+    /// "@<at-rule>"
     /// ```
     fn parse(input: ParseStream) -> Result<Self> {
         let selector = input.parse::<Expr>()?;
+        let Some(at_rule) = at_rule(&selector).cloned() else {
+            return SelectorWithStyle::parse_body(selector, input).map(Rule::Selector);
+        };
+        if !input.peek(Token![:]) {
+            return Ok(Rule::Statement(at_rule));
+        }
         input.parse::<Token![:]>()?;
         let inner;
         syn::braced!(inner in input);
-        let style = inner.parse()?;
-        Ok(Self { selector, style })
+        let at_rule_name = at_rule.value();
+        let at_rule_name = at_rule_name.split_whitespace().next().unwrap_or_default();
+        if DECLARATION_AT_RULES.contains(&at_rule_name) {
+            let style = inner.parse()?;
+            Ok(Rule::Declarations { at_rule, style })
+        } else {
+            let rules = parse_rules(&inner)?;
+            Ok(Rule::ConditionalGroup { at_rule, rules })
+        }
     }
 }
 
-impl ToTokens for SelectorWithStyle {
-    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+impl Rule {
+    /// returns an expression of the css blocks, `Vec<String>`
+    fn to_blocks_tokens(&self) -> proc_macro2::TokenStream {
+        match self {
+            Rule::Selector(selector_with_style) => selector_with_style.to_blocks_tokens(None),
+            Rule::ConditionalGroup { at_rule, rules } => {
+                let rules = rules_to_tokens(rules);
+                quote! {
+                    vec![format!("{} {{\n{}\n}}\n", #at_rule, #rules)]
+                }
+            }
+            Rule::Declarations { at_rule, style } => {
+                let style = style.to_tokens_with_pretty();
+                quote! {
+                    vec![format!("{} {{\n{}\n}}\n", #at_rule, #style)]
+                }
+            }
+            Rule::Statement(at_rule) => quote! {
+                vec![format!("{};\n", #at_rule)]
+            },
+        }
+    }
+}
+
+impl SelectorWithStyle {
+    /// ```ignore
+    /// : { $(<property> : <expr> | <selector> : { <style> } (,)?)* }
+    /// ```
+    fn parse_body(selector: Expr, input: ParseStream) -> Result<Self> {
+        input.parse::<Token![:]>()?;
+        let inner;
+        syn::braced!(inner in input);
+        let mut properties = vec![];
+        let mut nested = vec![];
+        while !inner.is_empty() {
+            if Self::peek_nested(&inner) {
+                let nested_selector = inner.parse::<Expr>()?;
+                if let Some(at_rule) = at_rule(&nested_selector) {
+                    return Err(syn::Error::new(
+                        at_rule.span(),
+                        "at-rules can not be nested in a selector, put the selector inside the at-rule instead",
+                    ));
+                }
+                nested.push(Self::parse_body(nested_selector, &inner)?);
+                if inner.peek(Token![,]) {
+                    inner.parse::<Token![,]>()?;
+                }
+            } else {
+                properties.push(style::parse_property(&inner)?);
+            }
+        }
+        Ok(Self {
+            selector,
+            style: Style::from_properties(properties),
+            nested,
+        })
+    }
+
+    /// a nested rule is a selector which is not a property name, followed by a block
+    fn peek_nested(input: ParseStream) -> bool {
+        let is_property_name =
+            input.peek(syn::Ident) && input.peek2(Token![:]) && !input.peek2(Token![::]);
+        if is_property_name || input.peek(Token![#]) {
+            return false;
+        }
+        let fork = input.fork();
+        fork.parse::<Expr>().is_ok()
+            && fork.parse::<Token![:]>().is_ok()
+            && fork.peek(syn::token::Brace)
+    }
+
+    /// returns an expression of the css blocks of this selector and its nested selectors,
+    /// the nested selectors replace `&` with the parent selector,
+    /// or are descendants of the parent selector when there is no `&`.
+    /// Both the parent and the nested selector lists are split at their top-level commas,
+    /// and each nested selector is combined with each parent selector.
+    fn to_blocks_tokens(&self, parent: Option<&syn::Ident>) -> proc_macro2::TokenStream {
         let selector = &self.selector;
-        let style = &self.style.to_tokens_with_pretty();
-        tokens.extend(quote! {
-            format!("{} {{\n{}\n}}\n", #selector, #style)
-        });
+        if parent.is_none() && self.nested.is_empty() {
+            let style = self.style.to_tokens_with_pretty();
+            return quote! {
+                vec![format!("{} {{\n{}\n}}\n", #selector, #style)]
+            };
+        }
+        let selector_ident = quote::format_ident!("__jss_selector");
+        let selector_value = match parent {
+            Some(parent) => quote! {
+                {
+                    let nested = (#selector).to_string();
+                    let nested = __jss_split_selectors(&nested);
+                    __jss_split_selectors(&#parent)
+                        .into_iter()
+                        .flat_map(|parent| {
+                            nested.iter().map(move |nested| {
+                                if nested.contains('&') {
+                                    nested.replace('&', parent)
+                                } else {
+                                    format!("{parent} {nested}")
+                                }
+                            })
+                        })
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                }
+            },
+            None => quote! { (#selector).to_string() },
+        };
+        // the nested rules are inside the block of the top-level selector
+        let split_selectors = if parent.is_none() {
+            quote! {
+                /// split the selector list at the commas which are not inside
+                /// the parentheses or brackets, ie: `:is(h1, h2), .title`
+                fn __jss_split_selectors(selectors: &str) -> Vec<&str> {
+                    let mut split = vec![];
+                    let mut depth = 0usize;
+                    let mut start = 0;
+                    for (index, ch) in selectors.char_indices() {
+                        match ch {
+                            '(' | '[' => depth += 1,
+                            ')' | ']' => depth = depth.saturating_sub(1),
+                            ',' if depth == 0 => {
+                                split.push(selectors[start..index].trim());
+                                start = index + 1;
+                            }
+                            _ => (),
+                        }
+                    }
+                    split.push(selectors[start..].trim());
+                    split
+                }
+            }
+        } else {
+            quote! {}
+        };
+        let block = if self.style.is_empty() {
+            quote! {}
+        } else {
+            let style = self.style.to_tokens_with_pretty();
+            quote! {
+                __jss_blocks.push(format!("{} {{\n{}\n}}\n", #selector_ident, #style));
+            }
+        };
+        let nested = self
+            .nested
+            .iter()
+            .map(|nested| nested.to_blocks_tokens(Some(&selector_ident)));
+        quote! {
+            {
+                #split_selectors
+                let #selector_ident: String = #selector_value;
+                let mut __jss_blocks: Vec<String> = vec![];
+                #block
+                #(__jss_blocks.extend(#nested);)*
+                __jss_blocks
+            }
+        }
     }
 }
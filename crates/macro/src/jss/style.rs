@@ -1,7 +1,7 @@
 use quote::{quote, ToTokens};
-use sauron_core::html::lookup::match_property;
+use sauron_core::html::lookup::{match_property, property_names};
 use syn::parse::{Parse, ParseStream};
-use syn::{Expr, Ident, Lit, Result, Token};
+use syn::{Expr, ExprLit, ExprUnary, Ident, Lit, Result, Token, UnOp};
 
 /// ```ignore
 /// background_color: "red",
@@ -19,14 +19,61 @@ pub(crate) struct Annotation {
 /// key value pair of a style
 /// `background_color: "red"`
 /// `border: (px(1), "solid", "green")`
-struct Property {
+pub(crate) struct Property {
     property: PropertyName,
     value: Expr,
 }
 
 /// `border`
 /// `"background-color"`
-struct PropertyName(String);
+struct PropertyName {
+    name: String,
+    /// the property name is an ident, which is checked against the known properties,
+    /// a quoted property name is used as is, skipping the checks
+    is_checked: bool,
+}
+
+/// the properties which values are lengths, where a number without a unit is ignored
+/// by the browser, except for 0
+const LENGTH_PROPERTIES: [&str; 37] = [
+    "width",
+    "height",
+    "min-width",
+    "max-width",
+    "min-height",
+    "max-height",
+    "top",
+    "right",
+    "bottom",
+    "left",
+    "inset",
+    "margin",
+    "margin-top",
+    "margin-right",
+    "margin-bottom",
+    "margin-left",
+    "padding",
+    "padding-top",
+    "padding-right",
+    "padding-bottom",
+    "padding-left",
+    "font-size",
+    "border-width",
+    "border-top-width",
+    "border-right-width",
+    "border-bottom-width",
+    "border-left-width",
+    "border-radius",
+    "outline-width",
+    "outline-offset",
+    "gap",
+    "row-gap",
+    "column-gap",
+    "letter-spacing",
+    "word-spacing",
+    "text-indent",
+    "flex-basis",
+];
 
 impl Parse for Style {
     /// ```ignore
@@ -35,23 +82,38 @@ impl Parse for Style {
     fn parse(input: ParseStream) -> Result<Self> {
         let mut properties = vec![];
         while !input.is_empty() {
-            // must be attribute annotations
-            let anotation = if input.peek(Token![#]) {
-                Some(input.parse()?)
-            } else {
-                None
-            };
-            let kv = input.parse()?;
-            if input.peek(Token![,]) && !input.peek2(syn::token::Brace) {
-                input.parse::<Token![,]>()?;
-            }
-            properties.push((anotation, kv));
+            properties.push(parse_property(input)?);
         }
         Ok(Self { properties })
     }
 }
 
+/// ```ignore
+/// $(#[<annotation>])? <property> : <expr> (,)?
+/// ```
+pub(crate) fn parse_property(input: ParseStream) -> Result<(Option<Annotation>, Property)> {
+    // must be attribute annotations
+    let anotation = if input.peek(Token![#]) {
+        Some(input.parse()?)
+    } else {
+        None
+    };
+    let kv = input.parse()?;
+    if input.peek(Token![,]) && !input.peek2(syn::token::Brace) {
+        input.parse::<Token![,]>()?;
+    }
+    Ok((anotation, kv))
+}
+
 impl Style {
+    pub(crate) fn from_properties(properties: Vec<(Option<Annotation>, Property)>) -> Self {
+        Self { properties }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.properties.is_empty()
+    }
+
     pub(crate) fn to_attr_tokens(&self) -> proc_macro2::TokenStream {
         let style_tokens = self.to_style_array_tokens();
         quote! {
@@ -106,9 +168,25 @@ impl Parse for Property {
     /// <property> : <expr>
     /// ```
     fn parse(input: ParseStream) -> Result<Self> {
-        let property = input.parse()?;
+        let property: PropertyName = input.parse()?;
         input.parse::<Token![:]>()?;
         let value = input.parse()?;
+        if cfg!(feature = "strict-units")
+            && property.is_checked
+            && LENGTH_PROPERTIES.contains(&property.name.as_str())
+        {
+            if let Some(number) = unitless_number(&value) {
+                let name = &property.name;
+                return Err(syn::Error::new_spanned(
+                    &value,
+                    format!(
+                        "`{name}` expects a length, but `{number}` has no unit, \
+                        use `px({number})` or \"{number}px\" instead\
+                        \nIf this is intended, then use \"{name}\" to ignore this check"
+                    ),
+                ));
+            }
+        }
         Ok(Property { property, value })
     }
 }
@@ -143,17 +221,30 @@ impl Parse for PropertyName {
         if let Ok(ident) = input.parse::<Ident>() {
             let property_name = ident.to_string();
             match match_property(&property_name) {
-                Some(matched) => Ok(PropertyName(matched.to_string())),
-                None => Err(syn::Error::new(
-                    input.span(),
-                    format!(
-                        "invalid property name: {property_name}
-                        \nIf this is intended, then use \"{property_name}\" to ignore this check"
-                    ),
-                )),
+                Some(matched) => Ok(PropertyName {
+                    name: matched.to_string(),
+                    is_checked: true,
+                }),
+                None => {
+                    let suggestion = closest_property(&property_name)
+                        .map(|closest| {
+                            format!("\nhelp: a property with a similar name exists: `{closest}`")
+                        })
+                        .unwrap_or_default();
+                    Err(syn::Error::new(
+                        ident.span(),
+                        format!(
+                            "invalid property name: `{property_name}`{suggestion}\
+                            \nIf this is intended, then use \"{property_name}\" to ignore this check"
+                        ),
+                    ))
+                }
             }
         } else if let Ok(Lit::Str(v)) = input.parse::<Lit>() {
-            Ok(PropertyName(v.value()))
+            Ok(PropertyName {
+                name: v.value(),
+                is_checked: false,
+            })
         } else {
             Err(syn::Error::new(
                 input.span(),
@@ -165,7 +256,92 @@ impl Parse for PropertyName {
 
 impl ToTokens for PropertyName {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
-        let property = &self.0;
+        let property = &self.name;
         tokens.extend(quote! {#property});
     }
 }
+
+/// the known property which is the closest to the misspelled property name
+fn closest_property(property_name: &str) -> Option<&'static str> {
+    // a suggestion which needs more edits than this is likely a different property
+    let max_distance = (property_name.len() / 3).clamp(1, 3);
+    property_names()
+        .map(|name| (edit_distance(property_name, name), name))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min()
+        .map(|(_, name)| name)
+}
+
+/// the number of insertions, deletions, substitutions and transpositions of adjacent characters
+/// to change one string into the other
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    // distances[i][j] is the distance between a[..i] and b[..j]
+    let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, distance) in distances[0].iter_mut().enumerate() {
+        *distance = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let substitution = distances[i - 1][j - 1] + usize::from(a[i - 1] != b[j - 1]);
+            let mut distance = substitution
+                .min(distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(distances[i - 2][j - 2] + 1);
+            }
+            distances[i][j] = distance;
+        }
+    }
+    distances[a.len()][b.len()]
+}
+
+/// the non-zero number of a literal value without a unit, ie: `100`, `1.5`, `-10` or `"100"`
+fn unitless_number(value: &Expr) -> Option<String> {
+    let number = match value {
+        Expr::Lit(ExprLit { lit, .. }) => match lit {
+            Lit::Int(int) if int.suffix().is_empty() => int.base10_digits().to_string(),
+            Lit::Float(float) if float.suffix().is_empty() => float.base10_digits().to_string(),
+            Lit::Str(string) => string.value().trim().to_string(),
+            _ => return None,
+        },
+        Expr::Unary(ExprUnary {
+            op: UnOp::Neg(_),
+            expr,
+            ..
+        }) => format!("-{}", unitless_number(expr)?),
+        _ => return None,
+    };
+    let is_non_zero = number.parse::<f64>().is_ok_and(|number| number != 0.0);
+    is_non_zero.then_some(number)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn suggest_the_closest_property() {
+        assert_eq!(
+            closest_property("backgound_color"),
+            Some("background_color")
+        );
+        assert_eq!(closest_property("widht"), Some("width"));
+        assert_eq!(closest_property("not_a_property_at_all"), None);
+    }
+
+    #[test]
+    fn number_without_unit() {
+        let unitless = |value: &str| unitless_number(&syn::parse_str(value).unwrap());
+        assert_eq!(unitless("100"), Some("100".to_string()));
+        assert_eq!(unitless("-1.5"), Some("-1.5".to_string()));
+        assert_eq!(unitless("\"20\""), Some("20".to_string()));
+        assert_eq!(unitless("0"), None);
+        assert_eq!(unitless("\"20px\""), None);
+        assert_eq!(unitless("px(20)"), None);
+    }
+}
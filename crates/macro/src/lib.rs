@@ -238,6 +238,48 @@ pub fn extract_skip_diff(input: proc_macro::TokenStream) -> proc_macro::TokenStr
 ///     \n";
 /// assert_eq!(expected, css);
 /// ```
+///
+/// The at-rules such as `@keyframes`, `@font-face`, `@supports`, `@layer` and `@media`
+/// are string literals starting with `@`, and the nested selectors are relative to their parent,
+/// where `&` is replaced with the parent selector.
+/// ```rust
+/// use sauron::jss;
+///
+/// let css = jss!(
+///     "@keyframes fade": {
+///         "from": { opacity: 0 },
+///         "to": { opacity: 1 },
+///     },
+///     ".button": {
+///         animation: "fade 1s",
+///         "&:hover": {
+///             background_color: "blue",
+///         },
+///     },
+/// );
+///
+/// let expected = "\
+///     @keyframes fade {\
+///     \nfrom {\
+///     \n  opacity: 0;\
+///     \n}\
+///     \n\
+///     \nto {\
+///     \n  opacity: 1;\
+///     \n}\
+///     \n\
+///     \n}\
+///     \n\
+///     \n.button {\
+///     \n  animation: fade 1s;\
+///     \n}\
+///     \n\
+///     \n.button:hover {\
+///     \n  background-color: blue;\
+///     \n}\
+///     \n";
+/// assert_eq!(expected, css);
+/// ```
 #[proc_macro]
 pub fn jss(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let style_sheet = syn::parse_macro_input!(input as jss::StyleSheet);
@@ -276,6 +318,11 @@ pub fn jss_with_media(input: proc_macro::TokenStream) -> proc_macro::TokenStream
 
 /// build a style attribute
 ///
+/// The property names are checked against the known style properties, a misspelled property
+/// is a compile error which suggests the closest property. With the `strict-units` feature,
+/// so is a length property such as `width` with a non-zero number without a unit.
+/// A quoted property name, ie: `"width"`, skips these checks.
+///
 /// # Example:
 /// ```rust
 /// use sauron::style;
@@ -197,31 +197,23 @@ where
 impl StatefulComponent for DateTimeWidget<()>{
 
     /// this is called when the attributes in the mount is changed
-    fn attribute_changed(
-        &mut self,
-        attr_name: &str,
-        new_value: Vec<DomAttrValue>,
-    ) 
-    {
-        log::info!("attribute changed: {attr_name}: {new_value:?}");
-        match &*attr_name {
+    fn attribute_changed(&mut self, attr: DomAttr) {
+        log::info!("attribute changed: {attr:?}");
+        let Some(new_value) = attr.value.first().and_then(|v| v.as_string()) else {
+            return;
+        };
+        match attr.name {
             "time" => {
-                if let Some(new_value) = new_value[0].as_string(){
-                    Component::update(self, Msg::TimeChange(new_value));
-                }
+                Component::update(self, Msg::TimeChange(new_value));
             }
             "date" => {
-                if let Some(new_value) = new_value[0].as_string(){
-                    Component::update(self, Msg::DateChange(new_value));
-                }
+                Component::update(self, Msg::DateChange(new_value));
             }
             "interval" => {
-                if let Some(new_value) = new_value[0].as_string() {
-                    let new_value: f64 = str::parse(&new_value).expect("must parse to f64");
-                    Component::update(self, Msg::IntervalChange(new_value));
-                }
+                let new_value: f64 = str::parse(&new_value).expect("must parse to f64");
+                Component::update(self, Msg::IntervalChange(new_value));
             }
-            _ => log::warn!("unknown attr_name: {attr_name:?}"),
+            _ => log::warn!("unknown attr_name: {:?}", attr.name),
         }
     }
 
@@ -242,6 +234,11 @@ pub fn time<MSG, V: Into<Value>>(v: V) -> Attribute<MSG> {
     attr("time", v)
 }
 
+/// register the widget as the `<date-time>` custom element
+pub fn register() {
+    register_custom_element::<DateTimeWidget<()>>("date-time");
+}
+
 pub fn date_time<MSG: 'static>(
     attrs: impl IntoIterator<Item = Attribute<MSG>>,
     children: impl IntoIterator<Item = Node<MSG>>,
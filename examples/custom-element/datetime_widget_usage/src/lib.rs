@@ -32,6 +32,6 @@ pub fn main() {
     console_log::init_with_level(log::Level::Trace).unwrap();
     console_error_panic_hook::set_once();
     log::info!("loaded...");
-    //datetime_widget::date_time::register();
+    datetime_widget::date_time::register();
     Program::mount_to_body(App::default());
 }
@@ -249,7 +249,9 @@ where
                 log::info!("content target mounted..");
                 let mount_event = me.clone();
                 let target_node = me.target_node;
-                target_node.append_children(self.children.drain(..).collect());
+                target_node
+                    .append_children(self.children.drain(..).collect())
+                    .expect("must append the children");
                 self.content_target_node = Some(target_node);
                 let external = self
                     .container_mounted_listeners
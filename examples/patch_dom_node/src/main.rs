@@ -29,7 +29,7 @@ fn main() {
     let old_node: Node<()> = parse_html::<()>("").unwrap().unwrap();
     let new_node: Node<()> = parse_html::<()>(new_html).unwrap().unwrap();
 
-    let root = dom::create_dom_node(&old_node, ev_callback);
+    let root = dom::create_dom_node(&old_node, ev_callback).expect("must create the root node");
     let root_node = Rc::new(RefCell::new(Some(root)));
 
     let vdom_patches = vdom::diff(&old_node, &new_node);
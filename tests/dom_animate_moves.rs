@@ -0,0 +1,62 @@
+#![deny(warnings)]
+use sauron::{html::attributes::animate_moves, *};
+
+use test_fixtures::simple_program;
+use wasm_bindgen_test::*;
+
+mod test_fixtures;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[wasm_bindgen_test]
+fn moved_nodes_are_translated_to_their_first_position() {
+    console_log::init_with_level(log::Level::Trace).ok();
+    console_error_panic_hook::set_once();
+
+    let document = web_sys::window().unwrap().document().unwrap();
+
+    let old: Node<()> = main(
+        [class("test_animate_moves")],
+        [ul(
+            [animate_moves(300)],
+            [
+                li([key(0)], [text("item0")]),
+                li([key(1)], [text("item1")]),
+                li([key(2)], [text("item2")]),
+            ],
+        )],
+    );
+
+    // move key2 to the first
+    let update1: Node<()> = main(
+        [class("test_animate_moves")],
+        [ul(
+            [animate_moves(300)],
+            [
+                li([key(2)], [text("item2")]),
+                li([key(0)], [text("item0")]),
+                li([key(1)], [text("item1")]),
+            ],
+        )],
+    );
+
+    let mut simple_program = simple_program();
+    simple_program
+        .update_dom_with_vdom(old)
+        .expect("must update dom");
+
+    simple_program
+        .update_dom_with_vdom(update1)
+        .expect("must update dom");
+
+    let items = document
+        .query_selector_all(".test_animate_moves li")
+        .expect("must not error");
+    assert_eq!(items.length(), 3);
+
+    let first: web_sys::Element = items.get(0).unwrap().unchecked_into();
+    assert_eq!(first.text_content().as_deref(), Some("item2"));
+    let style = first.get_attribute("style").expect("must have style");
+    assert!(style.contains("transform: translate(0px,"), "style: {style}");
+    assert!(style.contains("transition: none"), "style: {style}");
+}
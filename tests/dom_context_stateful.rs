@@ -0,0 +1,90 @@
+#![deny(warnings)]
+use sauron::{
+    dom::{delay, util::body, DomAttr, DomNode, MountProcedure, StatefulComponent},
+    *,
+};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+struct Theme {
+    color: &'static str,
+}
+
+#[derive(Default)]
+struct Counter {
+    count: usize,
+}
+
+impl Component for Counter {
+    type MSG = ();
+    type XMSG = ();
+
+    fn update(&mut self, _msg: ()) -> Effects<(), ()> {
+        self.count += 1;
+        Effects::none()
+    }
+
+    fn view(&self) -> Node<()> {
+        let color = use_context::<Theme>()
+            .map(|theme| theme.color)
+            .unwrap_or("none");
+        button(
+            [class("counter"), on_click(|_| ())],
+            [text(format!("{color} {}", self.count))],
+        )
+    }
+}
+
+impl StatefulComponent for Counter {
+    fn attribute_changed(&mut self, _attr: DomAttr) {}
+
+    fn child_container(&self) -> Option<DomNode> {
+        None
+    }
+}
+
+struct App;
+
+impl Application for App {
+    type MSG = ();
+
+    fn update(&mut self, _msg: ()) -> Cmd<()> {
+        Cmd::none()
+    }
+
+    fn view(&self) -> Node<()> {
+        div(
+            [],
+            [provide(Theme { color: "red" }, || {
+                [stateful_component(Counter::default(), [], [])]
+            })],
+        )
+    }
+}
+
+fn counter() -> web_sys::HtmlElement {
+    document()
+        .query_selector(".counter")
+        .unwrap()
+        .expect("must find the counter")
+        .unchecked_into()
+}
+
+#[wasm_bindgen_test]
+async fn stateful_component_keeps_the_provided_context() {
+    console_error_panic_hook::set_once();
+
+    let mut program = Program::new(App).with_scheduler(SchedulerConfig::sync());
+    program
+        .mount(&body(), MountProcedure::clear_append())
+        .expect("must mount");
+    delay(50).await;
+    assert_eq!(counter().text_content().as_deref(), Some("red 0"));
+
+    // the component is updated by its own program, outside of the view of the app
+    counter().click();
+    delay(50).await;
+    assert_eq!(counter().text_content().as_deref(), Some("red 1"));
+}
@@ -0,0 +1,119 @@
+#![deny(warnings)]
+use sauron::{
+    dom::{delay, util::body, DomAttr, DomNode, StatefulComponent},
+    vdom::AttributeName,
+    *,
+};
+use serde::Serialize;
+use std::{cell::RefCell, rc::Rc};
+use wasm_bindgen::{closure::Closure, JsCast};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[derive(Default)]
+struct Counter {
+    count: i32,
+    connected: bool,
+}
+
+enum Msg {
+    Increment,
+}
+
+#[derive(Serialize)]
+enum CounterEvent {
+    Changed(i32),
+}
+
+impl Component for Counter {
+    type MSG = Msg;
+    type XMSG = CounterEvent;
+
+    fn update(&mut self, msg: Msg) -> Effects<Msg, CounterEvent> {
+        match msg {
+            Msg::Increment => {
+                self.count += 1;
+                Effects::with_external([CounterEvent::Changed(self.count)])
+            }
+        }
+    }
+
+    fn view(&self) -> Node<Msg> {
+        button(
+            [
+                class("counter"),
+                classes_flag([("connected", self.connected)]),
+                on_click(|_| Msg::Increment),
+            ],
+            [text(self.count)],
+        )
+    }
+
+    fn observed_attributes() -> Vec<AttributeName> {
+        vec!["count"]
+    }
+}
+
+impl StatefulComponent for Counter {
+    fn attribute_changed(&mut self, attr: DomAttr) {
+        if let Some(count) = attr.value.first().and_then(|v| v.as_string()) {
+            self.count = count.parse().expect("must be a number");
+        }
+    }
+
+    fn remove_attribute(&mut self, _attr_name: AttributeName) {
+        self.count = 0;
+    }
+
+    fn child_container(&self) -> Option<DomNode> {
+        None
+    }
+
+    fn connected_callback(&mut self) {
+        self.connected = true;
+    }
+}
+
+fn counter_button() -> web_sys::HtmlElement {
+    document()
+        .query_selector("sauron-counter .counter")
+        .unwrap()
+        .expect("must find the counter")
+        .unchecked_into()
+}
+
+#[wasm_bindgen_test]
+async fn component_is_registered_as_a_custom_element() {
+    console_error_panic_hook::set_once();
+
+    register_custom_element::<Counter>("sauron-counter");
+
+    let element = document().create_element("sauron-counter").unwrap();
+    // the attributes set before the element is connected are used in the initial view
+    element.set_attribute("count", "5").unwrap();
+    body().append_child(&element).unwrap();
+    let counter = counter_button();
+    assert_eq!(counter.text_content().as_deref(), Some("5"));
+    assert_eq!(counter.class_name(), "counter connected");
+
+    element.set_attribute("count", "10").unwrap();
+    assert_eq!(counter_button().text_content().as_deref(), Some("10"));
+    element.remove_attribute("count").unwrap();
+    assert_eq!(counter_button().text_content().as_deref(), Some("0"));
+
+    // the XMSG is dispatched from the element as a custom event
+    let changed = Rc::new(RefCell::new(vec![]));
+    let changed_clone = Rc::clone(&changed);
+    let listener: Closure<dyn FnMut(web_sys::CustomEvent)> =
+        Closure::new(move |event: web_sys::CustomEvent| {
+            changed_clone.borrow_mut().push(event.detail().as_f64());
+        });
+    element
+        .add_event_listener_with_callback("Changed", listener.as_ref().unchecked_ref())
+        .unwrap();
+    counter_button().click();
+    delay(50).await;
+    assert_eq!(counter_button().text_content().as_deref(), Some("1"));
+    assert_eq!(*changed.borrow(), [Some(1.0)]);
+}
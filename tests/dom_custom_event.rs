@@ -0,0 +1,125 @@
+#![deny(warnings)]
+use sauron::{
+    dom::{delay, events::on_custom, util::body, MountProcedure},
+    *,
+};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Selected {
+    index: usize,
+    label: String,
+}
+
+#[derive(Default)]
+struct App {
+    selected: Option<Selected>,
+    error: Option<String>,
+}
+
+enum Msg {
+    Select(usize),
+    Selected(Result<Selected, serde_wasm_bindgen::Error>),
+}
+
+impl Application for App {
+    type MSG = Msg;
+
+    fn update(&mut self, msg: Msg) -> Cmd<Msg> {
+        match msg {
+            Msg::Select(index) => {
+                let item = document()
+                    .query_selector(&format!(".item-{index}"))
+                    .unwrap()
+                    .expect("must find the item");
+                Cmd::emit_custom_event(
+                    item,
+                    "item-selected",
+                    &Selected {
+                        index,
+                        label: format!("item {index}"),
+                    },
+                )
+            }
+            Msg::Selected(Ok(selected)) => {
+                self.selected = Some(selected);
+                Cmd::none()
+            }
+            Msg::Selected(Err(e)) => {
+                self.error = Some(e.to_string());
+                Cmd::none()
+            }
+        }
+    }
+
+    fn view(&self) -> Node<Msg> {
+        ul(
+            [
+                class("custom-events"),
+                on_custom("item-selected", Msg::Selected),
+            ],
+            (0..3).map(|index| li([class(format!("item-{index}"))], [text(index)])),
+        )
+    }
+}
+
+#[wasm_bindgen_test]
+async fn custom_events_bubble_to_the_listener() {
+    console_error_panic_hook::set_once();
+
+    let mut program = Program::new(App::default()).with_scheduler(SchedulerConfig::sync());
+    program
+        .mount(&body(), MountProcedure::clear_append())
+        .expect("must mount");
+
+    program.dispatch(Msg::Select(2));
+    delay(50).await;
+    assert_eq!(
+        program.app().selected,
+        Some(Selected {
+            index: 2,
+            label: "item 2".to_string(),
+        })
+    );
+
+    // the events dispatched by non-sauron code are also deserialized
+    dispatch_from_js(
+        &serde_wasm_bindgen::to_value(&Selected {
+            index: 0,
+            label: "from js".to_string(),
+        })
+        .unwrap(),
+    );
+    assert_eq!(
+        program.app().selected.as_ref().map(|s| s.label.as_str()),
+        Some("from js")
+    );
+    assert!(program.app().error.is_none());
+
+    // the detail which can not be deserialized is passed as an error
+    dispatch_from_js(&"not an item".into());
+    assert!(program.app().error.is_some());
+    assert_eq!(
+        program.app().selected.as_ref().map(|s| s.label.as_str()),
+        Some("from js")
+    );
+}
+
+fn dispatch_from_js(detail: &wasm_bindgen::JsValue) {
+    let event_init = web_sys::CustomEventInit::new();
+    event_init.set_bubbles(true);
+    event_init.set_detail(detail);
+    let event = web_sys::CustomEvent::new_with_event_init_dict("item-selected", &event_init)
+        .unwrap()
+        .unchecked_into::<web_sys::Event>();
+    document()
+        .query_selector(".item-0")
+        .unwrap()
+        .expect("must find the item")
+        .dispatch_event(&event)
+        .unwrap();
+}
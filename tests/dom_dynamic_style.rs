@@ -0,0 +1,82 @@
+#![deny(warnings)]
+use sauron::{
+    dom::{delay, util::body, MountProcedure},
+    *,
+};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+struct App {
+    color: &'static str,
+}
+
+enum Msg {
+    SetColor(&'static str),
+    Noop,
+}
+
+impl Application for App {
+    type MSG = Msg;
+
+    fn update(&mut self, msg: Msg) -> Cmd<Msg> {
+        match msg {
+            Msg::SetColor(color) => self.color = color,
+            Msg::Noop => (),
+        }
+        Cmd::none()
+    }
+
+    fn view(&self) -> Node<Msg> {
+        div([class("app")], [text(self.color)])
+    }
+
+    fn style(&self) -> Vec<String> {
+        vec![format!(".app {{ color: {}; }}", self.color)]
+    }
+}
+
+fn dynamic_styles() -> Vec<String> {
+    let styles = document()
+        .head()
+        .expect("must have a head")
+        .query_selector_all("style.dynamic")
+        .expect("must query");
+    (0..styles.length())
+        .filter_map(|i| styles.get(i))
+        .filter_map(|style| style.text_content())
+        .collect()
+}
+
+#[wasm_bindgen_test]
+async fn dynamic_style_is_updated_in_place() {
+    console_error_panic_hook::set_once();
+
+    let mut program = Program::new(App { color: "red" }).with_scheduler(SchedulerConfig::sync());
+    program
+        .mount(&body(), MountProcedure::clear_append())
+        .expect("must mount");
+    delay(50).await;
+    assert_eq!(dynamic_styles(), vec![".app { color: red; }".to_string()]);
+
+    program.dispatch(Msg::SetColor("blue"));
+    assert_eq!(dynamic_styles(), vec![".app { color: blue; }".to_string()]);
+
+    program.dispatch(Msg::Noop);
+    assert_eq!(dynamic_styles(), vec![".app { color: blue; }".to_string()]);
+}
+
+#[wasm_bindgen_test]
+async fn dynamic_style_is_removed_when_the_program_is_dropped() {
+    console_error_panic_hook::set_once();
+
+    let mut program = Program::new(App { color: "green" }).with_scheduler(SchedulerConfig::sync());
+    program
+        .mount(&body(), MountProcedure::clear_append())
+        .expect("must mount");
+    delay(50).await;
+    assert_eq!(dynamic_styles(), vec![".app { color: green; }".to_string()]);
+
+    drop(program);
+    assert!(dynamic_styles().is_empty());
+}
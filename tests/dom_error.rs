@@ -0,0 +1,77 @@
+#![deny(warnings)]
+use sauron::{
+    dom::{self, MountProcedure},
+    vdom::TreePath,
+    *,
+};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+struct App;
+
+impl Application for App {
+    type MSG = ();
+
+    fn update(&mut self, _msg: ()) -> Cmd<()> {
+        Cmd::none()
+    }
+
+    fn view(&self) -> Node<()> {
+        div([class("app")], [text("hello")])
+    }
+}
+
+#[wasm_bindgen_test]
+fn missing_node_is_an_error() {
+    console_error_panic_hook::set_once();
+
+    let old: Node<()> = div([], [span([], [])]);
+    let new: Node<()> = div([], [b([], [])]);
+    // the real dom doesn't have the span
+    let root = dom::create_dom_node(&div([], []), |_: ()| {}).expect("must create");
+
+    let patches = vdom::diff(&old, &new);
+    let result = dom::convert_patches(&root, &patches, |_: ()| {});
+    assert!(
+        matches!(result, Err(Error::NodeNotFound(ref path)) if *path == TreePath::new([0])),
+        "{result:?}"
+    );
+}
+
+#[wasm_bindgen_test]
+fn tag_mismatch_is_an_error() {
+    console_error_panic_hook::set_once();
+
+    let old: Node<()> = div([class("a")], []);
+    let new: Node<()> = div([class("b")], []);
+    let root = dom::create_dom_node(&section([], []), |_: ()| {}).expect("must create");
+
+    let patches = vdom::diff(&old, &new);
+    let result = dom::convert_patches(&root, &patches, |_: ()| {});
+    match result {
+        Err(Error::TagMismatch { expected, found }) => {
+            assert_eq!(expected, "div");
+            assert_eq!(found, "section");
+        }
+        other => panic!("expecting a tag mismatch, got: {other:?}"),
+    }
+}
+
+#[wasm_bindgen_test]
+fn attaching_a_second_shadow_is_an_error() {
+    console_error_panic_hook::set_once();
+
+    let document = web_sys::window().unwrap().document().unwrap();
+    let host = document.create_element("div").unwrap();
+    document.body().unwrap().append_child(&host).unwrap();
+
+    let mut program = Program::new(App);
+    program
+        .mount(&host, MountProcedure::append_to_shadow())
+        .expect("must mount");
+
+    let mut program = Program::new(App);
+    let result = program.mount(&host, MountProcedure::append_to_shadow());
+    assert!(matches!(result, Err(Error::AttachShadow(_))), "{result:?}");
+}
@@ -0,0 +1,96 @@
+#![deny(warnings)]
+use sauron::{
+    dom::{util::body, MountProcedure},
+    *,
+};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[derive(Default)]
+struct App {
+    items: Vec<usize>,
+    clicked: Vec<String>,
+    focused: Option<usize>,
+}
+
+enum Msg {
+    ClickItem(usize),
+    ClickList,
+    Focus(usize),
+    Remove(usize),
+}
+
+impl Application for App {
+    type MSG = Msg;
+
+    fn update(&mut self, msg: Msg) -> Cmd<Msg> {
+        match msg {
+            Msg::ClickItem(item) => self.clicked.push(format!("item {item}")),
+            Msg::ClickList => self.clicked.push("list".to_string()),
+            Msg::Focus(item) => self.focused = Some(item),
+            Msg::Remove(item) => self.items.retain(|i| *i != item),
+        }
+        Cmd::none()
+    }
+
+    fn view(&self) -> Node<Msg> {
+        ul(
+            [class("delegated-list"), on_click(|_| Msg::ClickList)],
+            self.items.iter().map(|item| {
+                let item = *item;
+                li(
+                    [
+                        key(item),
+                        class(format!("item-{item}")),
+                        on_click(move |_| Msg::ClickItem(item)),
+                    ],
+                    [
+                        input([class("edit"), on_focus(move |_| Msg::Focus(item))], []),
+                        button([class("remove"), on_click(move |_| Msg::Remove(item))], []),
+                    ],
+                )
+            }),
+        )
+    }
+}
+
+fn query(selector: &str) -> web_sys::HtmlElement {
+    document()
+        .query_selector(selector)
+        .unwrap()
+        .expect("must find the element")
+        .unchecked_into()
+}
+
+#[wasm_bindgen_test]
+fn events_are_dispatched_from_the_mount_node() {
+    console_error_panic_hook::set_once();
+
+    let mut program = Program::new(App {
+        items: (0..3).collect(),
+        ..Default::default()
+    })
+    .with_scheduler(SchedulerConfig::sync())
+    .with_event_delegation();
+    program
+        .mount(&body(), MountProcedure::clear_append())
+        .expect("must mount");
+    assert!(program.is_event_delegated());
+
+    // the listeners of the target and its ancestors are called as the event bubbles
+    query(".item-1").click();
+    assert_eq!(program.app().clicked, ["item 1", "list"]);
+
+    // events that don't bubble are dispatched to the target only
+    query(".item-2 .edit").focus().unwrap();
+    assert_eq!(program.app().focused, Some(2));
+
+    // the listeners of the removed elements are no longer called
+    query(".item-0 .remove").click();
+    assert_eq!(program.app().items, [1, 2]);
+    program.app_mut().clicked.clear();
+    query(".item-2").click();
+    assert_eq!(program.app().clicked, ["item 2", "list"]);
+}
@@ -0,0 +1,72 @@
+#![deny(warnings)]
+use sauron::{
+    dom::{util::body, MountProcedure},
+    *,
+};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[derive(Default)]
+struct App {
+    clicked: Vec<&'static str>,
+}
+
+enum Msg {
+    Clicked(&'static str),
+}
+
+impl Application for App {
+    type MSG = Msg;
+
+    fn update(&mut self, msg: Msg) -> Cmd<Msg> {
+        match msg {
+            Msg::Clicked(name) => self.clicked.push(name),
+        }
+        Cmd::none()
+    }
+
+    fn view(&self) -> Node<Msg> {
+        div(
+            [
+                class("listener-options"),
+                on_click(|_| Msg::Clicked("outer")).capture(),
+            ],
+            [button(
+                [
+                    class("inner"),
+                    on_click(|_| Msg::Clicked("inner once")).once(),
+                ],
+                [],
+            )],
+        )
+    }
+}
+
+fn click_inner() {
+    document()
+        .query_selector(".listener-options .inner")
+        .unwrap()
+        .expect("must find the button")
+        .unchecked_into::<web_sys::HtmlElement>()
+        .click();
+}
+
+#[wasm_bindgen_test]
+fn listeners_are_attached_with_the_options() {
+    console_error_panic_hook::set_once();
+
+    let mut program = Program::new(App::default()).with_scheduler(SchedulerConfig::sync());
+    program
+        .mount(&body(), MountProcedure::clear_append())
+        .expect("must mount");
+
+    // the capture listener of the parent is called before the listener of the target
+    click_inner();
+    assert_eq!(program.app().clicked, ["outer", "inner once"]);
+
+    // the once listener is no longer called
+    click_inner();
+    assert_eq!(program.app().clicked, ["outer", "inner once", "outer"]);
+}
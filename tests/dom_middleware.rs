@@ -0,0 +1,103 @@
+#![deny(warnings)]
+use sauron::{
+    dom::{delay, util::body, DomPatch, MountProcedure},
+    *,
+};
+use std::{cell::RefCell, rc::Rc};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+struct App {
+    count: i32,
+}
+
+#[derive(Debug)]
+enum Msg {
+    Increment,
+}
+
+impl Application for App {
+    type MSG = Msg;
+
+    fn update(&mut self, msg: Msg) -> Cmd<Msg> {
+        match msg {
+            Msg::Increment => self.count += 1,
+        }
+        Cmd::none()
+    }
+
+    fn view(&self) -> Node<Msg> {
+        div([class("middleware")], [text(self.count)])
+    }
+}
+
+/// record the calls to the hooks
+struct Recorder {
+    events: Rc<RefCell<Vec<String>>>,
+}
+
+impl Middleware<App> for Recorder {
+    fn before_update(&mut self, msg: &Msg) {
+        self.events
+            .borrow_mut()
+            .push(format!("before_update {msg:?}"));
+    }
+
+    fn after_update(&mut self, app: &App) {
+        self.events
+            .borrow_mut()
+            .push(format!("after_update {}", app.count));
+    }
+
+    fn on_cmd(&mut self, cmd: &Cmd<Msg>) {
+        self.events
+            .borrow_mut()
+            .push(format!("on_cmd is_none: {}", cmd.is_none()));
+    }
+
+    fn on_patches(&mut self, patches: &[DomPatch]) {
+        self.events
+            .borrow_mut()
+            .push(format!("on_patches {}", patches.len()));
+    }
+}
+
+#[wasm_bindgen_test]
+async fn middleware_hooks_are_called_in_order() {
+    console_error_panic_hook::set_once();
+
+    let events = Rc::new(RefCell::new(vec![]));
+    let mut program = Program::mount_to_body(App { count: 0 });
+    program.add_middleware(Recorder {
+        events: Rc::clone(&events),
+    });
+
+    program.dispatch(Msg::Increment);
+    delay(50).await;
+
+    assert_eq!(
+        *events.borrow(),
+        [
+            "before_update Increment",
+            "after_update 1",
+            "on_cmd is_none: true",
+            "on_patches 1",
+        ]
+    );
+}
+
+#[wasm_bindgen_test]
+async fn middleware_added_before_mount_observes_the_init_cmd() {
+    console_error_panic_hook::set_once();
+
+    let events = Rc::new(RefCell::new(vec![]));
+    let mut program = Program::new(App { count: 0 }).with_middleware(Recorder {
+        events: Rc::clone(&events),
+    });
+    program
+        .mount(&body(), MountProcedure::append())
+        .expect("must mount");
+
+    assert_eq!(*events.borrow(), ["on_cmd is_none: true"]);
+}
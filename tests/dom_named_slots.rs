@@ -0,0 +1,142 @@
+#![deny(warnings)]
+use sauron::{
+    dom::{delay, util::body, DomAttr, DomNode, MountProcedure, StatefulComponent},
+    html::tags::slot as slot_element,
+    *,
+};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+struct Card {
+    shadow_root: bool,
+}
+
+impl Component for Card {
+    type MSG = ();
+    type XMSG = ();
+
+    fn update(&mut self, _msg: ()) -> Effects<(), ()> {
+        Effects::none()
+    }
+
+    fn view(&self) -> Node<()> {
+        article(
+            [class("card")],
+            [
+                header([], [slot_element([name("header")], [])]),
+                footer([], [slot_element([name("footer")], [])]),
+            ],
+        )
+    }
+}
+
+impl StatefulComponent for Card {
+    fn attribute_changed(&mut self, _attr: DomAttr) {}
+
+    fn child_container(&self) -> Option<DomNode> {
+        None
+    }
+
+    fn use_shadow_root(&self) -> bool {
+        self.shadow_root
+    }
+}
+
+struct App {
+    shadow_root: bool,
+    title: &'static str,
+    with_footer: bool,
+}
+
+enum Msg {
+    SetTitle(&'static str),
+    RemoveFooter,
+}
+
+impl Application for App {
+    type MSG = Msg;
+
+    fn update(&mut self, msg: Msg) -> Cmd<Msg> {
+        match msg {
+            Msg::SetTitle(title) => self.title = title,
+            Msg::RemoveFooter => self.with_footer = false,
+        }
+        Cmd::none()
+    }
+
+    fn view(&self) -> Node<Msg> {
+        let card = Card {
+            shadow_root: self.shadow_root,
+        };
+        let footer = self
+            .with_footer
+            .then(|| p([class("note"), slot("footer")], [text("note")]));
+        div(
+            [class("app")],
+            [stateful_component(
+                card,
+                [],
+                [h1([slot("header")], [text(self.title)])]
+                    .into_iter()
+                    .chain(footer),
+            )],
+        )
+    }
+}
+
+fn mount(shadow_root: bool) -> Program<App> {
+    let mut program = Program::new(App {
+        shadow_root,
+        title: "Title",
+        with_footer: true,
+    })
+    .with_scheduler(SchedulerConfig::sync());
+    program
+        .mount(&body(), MountProcedure::clear_append())
+        .expect("must mount");
+    program
+}
+
+fn find(selector: &str) -> Option<web_sys::Element> {
+    document().query_selector(selector).unwrap()
+}
+
+#[wasm_bindgen_test]
+async fn children_are_appended_into_the_named_slots() {
+    console_error_panic_hook::set_once();
+
+    let mut program = mount(false);
+    delay(50).await;
+    let title = find(".card header slot h1").expect("must be in the header slot");
+    assert_eq!(title.text_content().as_deref(), Some("Title"));
+    assert!(find(".card footer slot p.note").is_some());
+
+    // the slotted children are still patched by the app
+    program.dispatch(Msg::SetTitle("Changed"));
+    let title = find(".card header slot h1").expect("must be in the header slot");
+    assert_eq!(title.text_content().as_deref(), Some("Changed"));
+
+    program.dispatch(Msg::RemoveFooter);
+    assert!(find(".note").is_none());
+}
+
+#[wasm_bindgen_test]
+async fn children_are_assigned_to_the_native_slots_of_the_shadow_root() {
+    console_error_panic_hook::set_once();
+
+    let _program = mount(true);
+    delay(50).await;
+    // the children are in the light DOM of the host, while the view is in the shadow root
+    let title = find(".app > .component > h1").expect("must be a child of the host");
+    assert_eq!(title.get_attribute("slot").as_deref(), Some("header"));
+    assert!(find(".app > .component > p.note").is_some());
+    let shadow_root = find(".app > .component")
+        .unwrap()
+        .shadow_root()
+        .expect("must have a shadow root");
+    assert!(shadow_root
+        .query_selector("slot[name=\"header\"]")
+        .unwrap()
+        .is_some());
+}
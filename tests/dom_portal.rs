@@ -0,0 +1,114 @@
+#![deny(warnings)]
+use sauron::{
+    dom::{util::body, MountProcedure},
+    html::portal,
+    *,
+};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+struct App {
+    open: bool,
+    count: i32,
+}
+
+enum Msg {
+    Open,
+    Increment,
+    Close,
+}
+
+impl Application for App {
+    type MSG = Msg;
+
+    fn update(&mut self, msg: Msg) -> Cmd<Msg> {
+        match msg {
+            Msg::Open => self.open = true,
+            Msg::Increment => self.count += 1,
+            Msg::Close => self.open = false,
+        }
+        Cmd::none()
+    }
+
+    fn view(&self) -> Node<Msg> {
+        div(
+            [class("portal-app"), style! {overflow: "hidden"}],
+            [
+                button([class("open"), on_click(|_| Msg::Open)], [text("open")]),
+                if self.open {
+                    portal(
+                        "#modals",
+                        [div(
+                            [class("modal")],
+                            [
+                                span([class("count")], [text(self.count)]),
+                                button([class("increment"), on_click(|_| Msg::Increment)], []),
+                                button([class("close"), on_click(|_| Msg::Close)], []),
+                            ],
+                        )],
+                    )
+                } else {
+                    comment("no modal")
+                },
+            ],
+        )
+    }
+}
+
+fn query(selector: &str) -> Option<web_sys::Element> {
+    document().query_selector(selector).unwrap()
+}
+
+fn click(selector: &str) {
+    query(selector)
+        .expect("must find the element")
+        .unchecked_into::<web_sys::HtmlElement>()
+        .click();
+}
+
+#[wasm_bindgen_test]
+fn portal_children_are_rendered_into_the_target() {
+    console_error_panic_hook::set_once();
+
+    let modals = document().create_element("div").unwrap();
+    modals.set_id("modals");
+    body().append_child(&modals).unwrap();
+
+    let mut program = Program::new(App {
+        open: false,
+        count: 0,
+    })
+    .with_scheduler(SchedulerConfig::sync());
+    program
+        .mount(&body(), MountProcedure::append())
+        .expect("must mount");
+
+    program.dispatch(Msg::Open);
+    assert!(query(".portal-app .modal").is_none());
+    assert_eq!(
+        query("#modals .modal .count")
+            .unwrap()
+            .text_content()
+            .unwrap(),
+        "0"
+    );
+
+    // the events in the portal are dispatched to the app
+    click("#modals .increment");
+    assert_eq!(program.app().count, 1);
+    assert_eq!(
+        query("#modals .modal .count")
+            .unwrap()
+            .text_content()
+            .unwrap(),
+        "1"
+    );
+
+    // the portal content is removed together with the portal
+    click("#modals .close");
+    assert!(!program.app().open);
+    assert!(query("#modals .modal").is_none());
+    assert_eq!(modals.child_element_count(), 0);
+}
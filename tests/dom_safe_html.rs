@@ -27,8 +27,8 @@ fn same_node() {
     );
     let new: Node<()> = ul([], [li([], [text("Hi")]), li([], [text("Hello")])]);
     let simple_program = simple_program();
-    let old_node = simple_program.create_dom_node(&old);
-    let new_node = simple_program.create_dom_node(&new);
+    let old_node = simple_program.create_dom_node(&old).expect("must create");
+    let new_node = simple_program.create_dom_node(&new).expect("must create");
     log::info!("old_node: {}", old_node.render_to_string());
     assert_eq!(old_node.render_to_string(), new_node.render_to_string());
 }
@@ -0,0 +1,75 @@
+#![deny(warnings)]
+use sauron::{
+    dom::{util::body, MountProcedure},
+    *,
+};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+struct App {
+    count: i32,
+}
+
+enum Msg {
+    Increment,
+}
+
+impl Application for App {
+    type MSG = Msg;
+
+    fn update(&mut self, msg: Msg) -> Cmd<Msg> {
+        match msg {
+            Msg::Increment => self.count += 1,
+        }
+        Cmd::none()
+    }
+
+    fn view(&self) -> Node<Msg> {
+        div([class("scheduler")], [text(self.count)])
+    }
+}
+
+fn mount(scheduler_config: SchedulerConfig) -> Program<App> {
+    let mut program = Program::new(App { count: 0 }).with_scheduler(scheduler_config);
+    program
+        .mount(&body(), MountProcedure::clear_append())
+        .expect("must mount");
+    program
+}
+
+fn rendered_count() -> String {
+    let document = web_sys::window().unwrap().document().unwrap();
+    document
+        .query_selector(".scheduler")
+        .unwrap()
+        .expect("must have the app")
+        .text_content()
+        .unwrap()
+}
+
+#[wasm_bindgen_test]
+fn sync_strategy_updates_the_dom_right_away() {
+    console_error_panic_hook::set_once();
+    let mut program = mount(SchedulerConfig::sync());
+    program.dispatch(Msg::Increment);
+    assert_eq!(rendered_count(), "1");
+    // no debouncing even if the updates are within the frame budget
+    program.dispatch(Msg::Increment);
+    assert_eq!(rendered_count(), "2");
+}
+
+#[wasm_bindgen_test]
+fn update_dom_sync_forces_the_pending_msgs() {
+    console_error_panic_hook::set_once();
+    let mut program = mount(
+        SchedulerConfig::default()
+            .with_strategy(UpdateStrategy::Microtask)
+            .with_debounce(true),
+    );
+    program.dispatch_multiple([Msg::Increment, Msg::Increment]);
+    assert_eq!(rendered_count(), "0");
+    program.update_dom_sync().expect("must update");
+    assert_eq!(rendered_count(), "2");
+    assert_eq!(program.app().count, 2);
+}
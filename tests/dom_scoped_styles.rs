@@ -0,0 +1,54 @@
+#![deny(warnings)]
+use sauron::{
+    dom::{util::body, MountProcedure},
+    *,
+};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+struct App;
+
+impl Application for App {
+    type MSG = ();
+
+    fn update(&mut self, _msg: ()) -> Cmd<()> {
+        Cmd::none()
+    }
+
+    fn view(&self) -> Node<()> {
+        div([class("scoped")], [text("in the shadow")])
+    }
+
+    fn stylesheet() -> Vec<String> {
+        vec![".scoped { color: rgb(255, 0, 0); }".to_string()]
+    }
+}
+
+#[wasm_bindgen_test]
+fn stylesheet_is_injected_into_the_shadow_root() {
+    console_log::init_with_level(log::Level::Trace).ok();
+    console_error_panic_hook::set_once();
+
+    let host = document().create_element("div").expect("must create host");
+    body().append_child(&host).expect("must append host");
+
+    let head_styles = document().head().expect("head").child_element_count();
+
+    let mut program = Program::new(App).with_scheduler(SchedulerConfig::sync());
+    program
+        .mount(&host, MountProcedure::append_to_shadow())
+        .expect("must mount");
+
+    let shadow_root = host.shadow_root().expect("must have a shadow root");
+    let style = shadow_root
+        .query_selector("style")
+        .expect("must query")
+        .expect("must have the style in the shadow root");
+    assert!(style.text_content().unwrap().contains(".scoped"));
+    assert_eq!(
+        head_styles,
+        document().head().expect("head").child_element_count(),
+        "the stylesheet must not leak into the document head"
+    );
+}
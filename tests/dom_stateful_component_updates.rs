@@ -0,0 +1,202 @@
+#![deny(warnings)]
+use sauron::{
+    dom::{delay, util::body, DomAttr, DomNode, MountProcedure, StatefulComponent},
+    vdom::AttributeName,
+    *,
+};
+use std::{cell::RefCell, rc::Rc};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+/// what the stateful component received from the parent
+#[derive(Default)]
+struct Received {
+    events: Vec<String>,
+    children: Vec<DomNode>,
+    /// the number of times the view of the component is rendered
+    views: usize,
+}
+
+struct Badge {
+    label: String,
+    received: Rc<RefCell<Received>>,
+}
+
+impl Component for Badge {
+    type MSG = ();
+    type XMSG = ();
+
+    fn update(&mut self, _msg: ()) -> Effects<(), ()> {
+        Effects::none()
+    }
+
+    fn view(&self) -> Node<()> {
+        self.received.borrow_mut().views += 1;
+        span([class("badge")], [text(&self.label)])
+    }
+
+    fn observed_attributes() -> Vec<AttributeName> {
+        vec!["label"]
+    }
+}
+
+impl StatefulComponent for Badge {
+    fn attribute_changed(&mut self, attr: DomAttr) {
+        if attr.name == "label" {
+            self.label = attr.value[0].as_string().expect("must be a string");
+            self.received
+                .borrow_mut()
+                .events
+                .push(format!("label {}", self.label));
+        }
+    }
+
+    fn remove_attribute(&mut self, attr_name: AttributeName) {
+        self.label.clear();
+        self.received
+            .borrow_mut()
+            .events
+            .push(format!("remove {attr_name}"));
+    }
+
+    fn child_container(&self) -> Option<DomNode> {
+        None
+    }
+
+    fn append_children(&mut self, children: Vec<DomNode>) {
+        let mut received = self.received.borrow_mut();
+        for child in children {
+            received
+                .events
+                .push(format!("append {}", child.outer_html()));
+            received.children.push(child);
+        }
+    }
+
+    fn remove_child(&mut self, index: usize) {
+        let mut received = self.received.borrow_mut();
+        received.children.remove(index);
+        received.events.push(format!("remove {index}"));
+    }
+}
+
+struct App {
+    label: Option<String>,
+    items: Vec<&'static str>,
+    received: Rc<RefCell<Received>>,
+}
+
+enum Msg {
+    SetLabel(Option<String>),
+    SetItems(Vec<&'static str>),
+    SetLabelAndItems(Option<String>, Vec<&'static str>),
+}
+
+impl Application for App {
+    type MSG = Msg;
+
+    fn update(&mut self, msg: Msg) -> Cmd<Msg> {
+        match msg {
+            Msg::SetLabel(label) => self.label = label,
+            Msg::SetItems(items) => self.items = items,
+            Msg::SetLabelAndItems(label, items) => {
+                self.label = label;
+                self.items = items;
+            }
+        }
+        Cmd::none()
+    }
+
+    fn view(&self) -> Node<Msg> {
+        let badge = Badge {
+            label: String::new(),
+            received: Rc::clone(&self.received),
+        };
+        div(
+            [],
+            [stateful_component(
+                badge,
+                self.label.iter().map(|label| attr("label", label)),
+                self.items.iter().map(|item| li([], [text(item)])),
+            )],
+        )
+    }
+}
+
+fn badge_text() -> Option<String> {
+    document()
+        .query_selector(".badge")
+        .unwrap()
+        .expect("must find the badge")
+        .text_content()
+}
+
+#[wasm_bindgen_test]
+async fn attributes_and_children_are_passed_to_the_stateful_component() {
+    console_error_panic_hook::set_once();
+
+    let received = Rc::new(RefCell::new(Received::default()));
+    let mut program = Program::new(App {
+        label: Some("new".to_string()),
+        items: vec!["a"],
+        received: Rc::clone(&received),
+    })
+    .with_scheduler(SchedulerConfig::sync());
+    program
+        .mount(&body(), MountProcedure::clear_append())
+        .expect("must mount");
+    delay(50).await;
+    assert_eq!(badge_text().as_deref(), Some("new"));
+    assert_eq!(received.borrow().events, ["label new", "append <li>a</li>"]);
+
+    // the changed attributes are passed to the created component and its view is updated
+    program.dispatch(Msg::SetLabel(Some("hot".to_string())));
+    delay(50).await;
+    assert_eq!(badge_text().as_deref(), Some("hot"));
+    program.dispatch(Msg::SetLabel(None));
+    delay(50).await;
+    assert_eq!(badge_text().as_deref(), Some(""));
+    assert_eq!(
+        received.borrow().events[2..],
+        ["label hot".to_string(), "remove label".to_string()]
+    );
+
+    // the external children are patched, appended and removed
+    program.dispatch(Msg::SetItems(vec!["z", "b"]));
+    assert_eq!(received.borrow().children[0].outer_html(), "<li>z</li>");
+    assert_eq!(
+        received.borrow().events[4..],
+        ["append <li>b</li>".to_string()]
+    );
+    program.dispatch(Msg::SetItems(vec![]));
+    assert!(received.borrow().children.is_empty());
+    assert_eq!(
+        received.borrow().events[5..],
+        ["remove 1".to_string(), "remove 0".to_string()]
+    );
+}
+
+#[wasm_bindgen_test]
+async fn the_view_of_the_stateful_component_is_rendered_once_per_update() {
+    console_error_panic_hook::set_once();
+
+    let received = Rc::new(RefCell::new(Received::default()));
+    let mut program = Program::new(App {
+        label: Some("new".to_string()),
+        items: vec!["a", "b"],
+        received: Rc::clone(&received),
+    })
+    .with_scheduler(SchedulerConfig::sync());
+    program
+        .mount(&body(), MountProcedure::clear_append())
+        .expect("must mount");
+    delay(50).await;
+    let views = received.borrow().views;
+
+    // the attribute and children patches of the component are applied before its view is rendered
+    program.dispatch(Msg::SetLabelAndItems(Some("hot".to_string()), vec!["c"]));
+    assert_eq!(badge_text().as_deref(), Some("hot"));
+    assert_eq!(received.borrow().children.len(), 1);
+    assert_eq!(received.borrow().views, views + 1);
+}
@@ -0,0 +1,157 @@
+#![deny(warnings)]
+use sauron::{
+    dom::{component, delay, util::body, DomAttr, DomNode, MountProcedure, StatefulComponent},
+    *,
+};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+struct Badge;
+
+impl Component for Badge {
+    type MSG = Msg;
+    type XMSG = ();
+
+    fn update(&mut self, _msg: Msg) -> Effects<Msg, ()> {
+        Effects::none()
+    }
+
+    fn view(&self) -> Node<Msg> {
+        span([class("badge")], [text("new")])
+    }
+
+    fn stylesheet() -> Vec<String> {
+        vec![".badge { color: rgb(0, 128, 0); }".to_string()]
+    }
+}
+
+#[derive(Default)]
+struct Counter;
+
+impl Component for Counter {
+    type MSG = ();
+    type XMSG = ();
+
+    fn update(&mut self, _msg: ()) -> Effects<(), ()> {
+        Effects::none()
+    }
+
+    fn view(&self) -> Node<()> {
+        div([class("counter")], [text("0")])
+    }
+
+    fn stylesheet() -> Vec<String> {
+        vec![".counter { color: rgb(0, 0, 255); }".to_string()]
+    }
+}
+
+impl StatefulComponent for Counter {
+    fn attribute_changed(&mut self, _attr: DomAttr) {}
+
+    fn child_container(&self) -> Option<DomNode> {
+        None
+    }
+}
+
+struct App {
+    badges: usize,
+    counters: usize,
+}
+
+enum Msg {
+    SetBadges(usize),
+    SetCounters(usize),
+}
+
+impl Application for App {
+    type MSG = Msg;
+
+    fn update(&mut self, msg: Msg) -> Cmd<Msg> {
+        match msg {
+            Msg::SetBadges(badges) => self.badges = badges,
+            Msg::SetCounters(counters) => self.counters = counters,
+        }
+        Cmd::none()
+    }
+
+    fn view(&self) -> Node<Msg> {
+        div(
+            [],
+            (0..self.badges)
+                .map(|_| component(&Badge))
+                .chain((0..self.counters).map(|_| stateful_component(Counter, [], []))),
+        )
+    }
+}
+
+/// the number of style elements in the document head containing the css
+fn style_count(css: &str) -> usize {
+    let styles = document()
+        .head()
+        .expect("must have a head")
+        .query_selector_all("style")
+        .expect("must query");
+    (0..styles.length())
+        .filter_map(|i| styles.get(i))
+        .filter(|style| {
+            style
+                .text_content()
+                .is_some_and(|content| content.contains(css))
+        })
+        .count()
+}
+
+#[wasm_bindgen_test]
+async fn stylesheet_of_the_components_is_injected_once() {
+    console_error_panic_hook::set_once();
+
+    let mut program = Program::new(App {
+        badges: 0,
+        counters: 0,
+    })
+    .with_scheduler(SchedulerConfig::sync());
+    program
+        .mount(&body(), MountProcedure::clear_append())
+        .expect("must mount");
+    delay(50).await;
+    assert_eq!(style_count(".badge"), 0);
+
+    program.dispatch(Msg::SetBadges(3));
+    assert_eq!(style_count(".badge"), 1);
+
+    program.dispatch(Msg::SetBadges(1));
+    assert_eq!(style_count(".badge"), 1);
+
+    // the stylesheet is removed with the last instance of the component
+    program.dispatch(Msg::SetBadges(0));
+    assert_eq!(style_count(".badge"), 0);
+
+    program.dispatch(Msg::SetCounters(2));
+    delay(50).await;
+    assert_eq!(style_count(".counter"), 1);
+
+    program.dispatch(Msg::SetCounters(0));
+    assert_eq!(style_count(".counter"), 0);
+}
+
+#[wasm_bindgen_test]
+async fn stylesheet_of_the_components_is_released_when_the_program_is_dropped() {
+    console_error_panic_hook::set_once();
+
+    let mut program = Program::new(App {
+        badges: 2,
+        counters: 1,
+    })
+    .with_scheduler(SchedulerConfig::sync());
+    program
+        .mount(&body(), MountProcedure::clear_append())
+        .expect("must mount");
+    delay(50).await;
+    assert_eq!(style_count(".badge"), 1);
+    assert_eq!(style_count(".counter"), 1);
+
+    drop(program);
+    assert_eq!(style_count(".badge"), 0);
+    assert_eq!(style_count(".counter"), 0);
+}
@@ -0,0 +1,89 @@
+#![deny(warnings)]
+use sauron::{html::attributes::transition, *};
+
+use test_fixtures::simple_program;
+use wasm_bindgen_test::*;
+
+mod test_fixtures;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[wasm_bindgen_test]
+fn removed_node_is_kept_until_leave_transition_ends() {
+    console_log::init_with_level(log::Level::Trace).ok();
+    console_error_panic_hook::set_once();
+
+    let document = web_sys::window().unwrap().document().unwrap();
+
+    let old: Node<()> = main(
+        [class("test_transition")],
+        [ul(
+            [],
+            [
+                li([key(0), transition("fade")], [text("item0")]),
+                li([key(1), transition("fade")], [text("item1")]),
+                li([key(2), transition("fade")], [text("item2")]),
+                li([key(3), transition("fade")], [text("item3")]),
+            ],
+        )],
+    );
+
+    // remove key0
+    let update1: Node<()> = main(
+        [class("test_transition")],
+        [ul(
+            [],
+            [
+                li([key(1), transition("fade")], [text("item1")]),
+                li([key(2), transition("fade")], [text("item2")]),
+                li([key(3), transition("fade")], [text("item3")]),
+            ],
+        )],
+    );
+
+    // remove key3, the patch path must not count the leaving key0
+    let update2: Node<()> = main(
+        [class("test_transition")],
+        [ul(
+            [],
+            [
+                li([key(1), transition("fade")], [text("item1")]),
+                li([key(2), transition("fade")], [text("item2")]),
+            ],
+        )],
+    );
+
+    let mut simple_program = simple_program();
+    simple_program
+        .update_dom_with_vdom(old)
+        .expect("must update dom");
+
+    simple_program
+        .update_dom_with_vdom(update1)
+        .expect("must update dom");
+
+    let container = document
+        .query_selector(".test_transition")
+        .expect("must not error")
+        .expect("must exist");
+
+    let expected = "<main class=\"test_transition\"><ul>\
+        <li key=\"0\" transition=\"fade\" class=\"fade-leave-from fade-leave-active\">item0</li>\
+        <li key=\"1\" transition=\"fade\">item1</li>\
+        <li key=\"2\" transition=\"fade\">item2</li>\
+        <li key=\"3\" transition=\"fade\">item3</li>\
+        </ul></main>";
+    assert_eq!(expected, container.outer_html());
+
+    simple_program
+        .update_dom_with_vdom(update2)
+        .expect("must update dom");
+
+    let expected = "<main class=\"test_transition\"><ul>\
+        <li key=\"0\" transition=\"fade\" class=\"fade-leave-from fade-leave-active\">item0</li>\
+        <li key=\"1\" transition=\"fade\">item1</li>\
+        <li key=\"2\" transition=\"fade\">item2</li>\
+        <li key=\"3\" transition=\"fade\" class=\"fade-leave-from fade-leave-active\">item3</li>\
+        </ul></main>";
+    assert_eq!(expected, container.outer_html());
+}
@@ -39,7 +39,7 @@ fn test_dom_vdom_standalone() {
 
     let old_node: Node<()> = parse_html::<()>("").unwrap().unwrap();
     let new_node: Node<()> = raw_html::<()>(new_html);
-    let root = dom::create_dom_node(&old_node, ev_callback);
+    let root = dom::create_dom_node(&old_node, ev_callback).unwrap();
     let root_node = Rc::new(RefCell::new(Some(root)));
 
     let vdom_patches = vdom::diff(&old_node, &new_node);
@@ -48,7 +48,7 @@ fn test_dom_vdom_standalone() {
 
     // convert vdom patch to real dom patches
     let dom_patches = dom::convert_patches(
-        &root_node.borrow().as_ref().unwrap(),
+        root_node.borrow().as_ref().unwrap(),
         &vdom_patches,
         ev_callback,
     )
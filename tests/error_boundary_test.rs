@@ -0,0 +1,91 @@
+#![deny(warnings)]
+use sauron::{dom::HeadlessProgram, html::ViewError, *};
+use std::cell::RefCell;
+
+fn broken_widget(count: i32) -> Node<Msg> {
+    if count > 0 {
+        panic!("count is {count}");
+    }
+    span([], [text(count)])
+}
+
+fn fallback(error: &ViewError) -> Node<Msg> {
+    span([class("error")], [text(error)])
+}
+
+struct App {
+    count: i32,
+}
+
+enum Msg {
+    Increment,
+}
+
+impl Application for App {
+    type MSG = Msg;
+
+    fn update(&mut self, msg: Msg) -> Cmd<Msg> {
+        match msg {
+            Msg::Increment => self.count += 1,
+        }
+        Cmd::none()
+    }
+
+    fn view(&self) -> Node<Msg> {
+        let count = self.count;
+        div(
+            [],
+            [
+                button([on("click", |_| Msg::Increment)], [text("+")]),
+                error_boundary(fallback, || broken_widget(count)),
+            ],
+        )
+    }
+}
+
+#[test]
+fn renders_the_view_when_it_succeeds() {
+    let node = error_boundary(fallback, || broken_widget(0));
+    assert_eq!(node.render_to_string(), "<span>0</span>");
+}
+
+#[test]
+fn renders_the_fallback_when_the_view_panics() {
+    let node = error_boundary(fallback, || broken_widget(2));
+    assert_eq!(
+        node.render_to_string(),
+        r#"<span class="error">the view panicked: count is 2</span>"#
+    );
+}
+
+#[test]
+fn renders_the_fallback_when_the_view_returns_an_error() {
+    let reported = RefCell::new(None);
+    let node: Node<Msg> = error_boundary_with_report(
+        fallback,
+        || -> Result<Node<Msg>, String> { Err("no data".to_string()) },
+        |error| *reported.borrow_mut() = Some(error.clone()),
+    );
+    assert_eq!(
+        node.render_to_string(),
+        r#"<span class="error">the view failed: no data</span>"#
+    );
+    assert_eq!(
+        reported.into_inner(),
+        Some(ViewError::Failed("no data".to_string()))
+    );
+}
+
+#[test]
+fn the_rest_of_the_app_still_works() {
+    let program = HeadlessProgram::new(App { count: 0 });
+    let button = program.root_node().unwrap().children()[0].clone();
+    button.dispatch_event("click");
+    assert_eq!(program.app().count, 1);
+
+    let widget = program.root_node().unwrap().children()[1].clone();
+    assert_eq!(widget.text_content(), "the view panicked: count is 1");
+
+    button.dispatch_event("click");
+    assert_eq!(program.app().count, 2);
+}
@@ -0,0 +1,150 @@
+#![deny(warnings)]
+use sauron::{
+    dom::{Debugger, DebuggerMsg, HeadlessProgram},
+    *,
+};
+
+#[derive(Clone, Default)]
+struct App {
+    count: i32,
+    errors: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+enum Msg {
+    Increment,
+    Add(i32),
+}
+
+impl Application for App {
+    type MSG = Msg;
+
+    fn init(&mut self) -> Cmd<Msg> {
+        self.count = 10;
+        Cmd::none()
+    }
+
+    fn update(&mut self, msg: Msg) -> Cmd<Msg> {
+        match msg {
+            Msg::Increment => {
+                self.count += 1;
+                Cmd::none()
+            }
+            Msg::Add(n) => {
+                self.count += n;
+                // the increment is recorded as a msg when the cmd is executed
+                Cmd::new(async { Msg::Increment })
+            }
+        }
+    }
+
+    fn view(&self) -> Node<Msg> {
+        button([on("click", |_| Msg::Increment)], [text(self.count)])
+    }
+
+    fn on_error(&mut self, error: &Error) {
+        self.errors.push(error.to_string());
+    }
+}
+
+fn count(program: &HeadlessProgram<Debugger<App>>) -> i32 {
+    program.app().app().count
+}
+
+#[test]
+fn msgs_are_recorded() {
+    let mut program = HeadlessProgram::new(Debugger::new(App::default()));
+    program.dispatch(DebuggerMsg::App(Msg::Add(5)));
+    assert_eq!(count(&program), 16);
+    assert_eq!(program.app().cursor(), 2);
+    assert_eq!(
+        program
+            .app()
+            .history()
+            .iter()
+            .map(|msg| format!("{msg:?}"))
+            .collect::<Vec<_>>(),
+        ["Add(5)", "Increment"]
+    );
+}
+
+#[test]
+fn step_back_and_forward_replays_from_the_initial_state() {
+    let mut program = HeadlessProgram::new(Debugger::new(App::default()));
+    program.dispatch_multiple([
+        DebuggerMsg::App(Msg::Increment),
+        DebuggerMsg::App(Msg::Increment),
+        DebuggerMsg::App(Msg::Increment),
+    ]);
+    assert_eq!(count(&program), 13);
+
+    program.dispatch(DebuggerMsg::StepBack);
+    assert_eq!(count(&program), 12);
+    program.dispatch(DebuggerMsg::JumpTo(0));
+    assert_eq!(count(&program), 10);
+    program.dispatch(DebuggerMsg::StepBack);
+    assert_eq!(count(&program), 10);
+    program.dispatch(DebuggerMsg::StepForward);
+    assert_eq!(count(&program), 11);
+    assert_eq!(program.app().history().len(), 3);
+
+    // the dom is patched to match the app at that point
+    let button = program.root_node().unwrap().children()[0].clone();
+    assert_eq!(button.text_content(), "11");
+}
+
+#[test]
+fn replaying_does_not_execute_the_cmd() {
+    let mut program = HeadlessProgram::new(Debugger::new(App::default()));
+    program.dispatch(DebuggerMsg::App(Msg::Add(5)));
+    program.dispatch(DebuggerMsg::JumpTo(1));
+    assert_eq!(count(&program), 15);
+    assert_eq!(program.app().history().len(), 2);
+}
+
+#[test]
+fn new_msg_discards_the_msgs_after_the_cursor() {
+    let mut program = HeadlessProgram::new(Debugger::new(App::default()));
+    program.dispatch_multiple([
+        DebuggerMsg::App(Msg::Increment),
+        DebuggerMsg::App(Msg::Increment),
+    ]);
+    program.dispatch(DebuggerMsg::StepBack);
+
+    // clicking the button of the app view
+    let button = program.root_node().unwrap().children()[0].clone();
+    button.dispatch_event("click");
+    assert_eq!(program.app().history().len(), 2);
+    assert_eq!(program.app().cursor(), 2);
+    assert_eq!(count(&program), 12);
+}
+
+#[test]
+fn overlay_lists_the_msgs() {
+    let mut program = HeadlessProgram::new(Debugger::new(App::default()));
+    program.dispatch(DebuggerMsg::App(Msg::Increment));
+    let overlay = program.root_node().unwrap().children()[1].clone();
+    assert_eq!(
+        overlay.get_attribute("class").as_deref(),
+        Some("sauron-debugger")
+    );
+    let msgs = overlay.children()[1].clone();
+    assert_eq!(
+        msgs.children()
+            .iter()
+            .map(|li| li.text_content())
+            .collect::<Vec<_>>(),
+        ["init", "Increment"]
+    );
+
+    // clicking the first item goes back to the initial state
+    msgs.children()[0].dispatch_event("click");
+    assert_eq!(count(&program), 10);
+}
+
+#[test]
+fn errors_are_passed_to_the_app() {
+    let mut debugger = Debugger::new(App::default());
+    debugger.on_error(&Error::NotMounted);
+    assert_eq!(debugger.app().errors, [Error::NotMounted.to_string()]);
+}
@@ -0,0 +1,298 @@
+#![deny(warnings)]
+use sauron::{dom::HeadlessProgram, *};
+
+#[derive(Default)]
+struct App {
+    count: i32,
+    name: String,
+    items: Vec<usize>,
+}
+
+#[derive(Debug)]
+enum Msg {
+    Increment,
+    NameChanged(String),
+    FetchItems,
+    ItemsFetched(Vec<usize>),
+}
+
+impl Application for App {
+    type MSG = Msg;
+
+    fn update(&mut self, msg: Msg) -> Cmd<Msg> {
+        match msg {
+            Msg::Increment => {
+                self.count += 1;
+                Cmd::none()
+            }
+            Msg::NameChanged(name) => {
+                self.name = name;
+                Cmd::none()
+            }
+            Msg::FetchItems => Cmd::new(async { Msg::ItemsFetched(vec![3, 1, 2]) }),
+            Msg::ItemsFetched(items) => {
+                self.items = items;
+                Cmd::none()
+            }
+        }
+    }
+
+    fn view(&self) -> Node<Msg> {
+        main(
+            [],
+            [
+                button([on("click", |_| Msg::Increment)], [text(self.count)]),
+                input(
+                    [value(&self.name), on_input(|e| Msg::NameChanged(e.value()))],
+                    [],
+                ),
+                ul(
+                    [],
+                    self.items.iter().map(|item| li([key(*item)], [text(item)])),
+                ),
+            ],
+        )
+    }
+}
+
+#[test]
+fn click_event_updates_the_app_and_the_dom() {
+    let program = HeadlessProgram::new(App::default());
+    let button = program.root_node().unwrap().children()[0].clone();
+    button.dispatch_event("click");
+    button.dispatch_event("click");
+    assert_eq!(program.app().count, 2);
+    assert_eq!(
+        program.render_to_string(),
+        "<main><button>2</button><input value=\"\"/><ul></ul></main>"
+    );
+}
+
+#[test]
+fn input_event_reads_the_value_of_the_target() {
+    let program = HeadlessProgram::new(App::default());
+    let input = program.root_node().unwrap().children()[1].clone();
+    input.set_attribute("value", "sauron").unwrap();
+    input.dispatch_event("input");
+    assert_eq!(program.app().name, "sauron");
+}
+
+#[test]
+fn msgs_from_cmd_are_dispatched() {
+    let mut program = HeadlessProgram::new(App::default());
+    program.dispatch(Msg::FetchItems);
+    assert_eq!(program.app().items, vec![3, 1, 2]);
+    assert_eq!(
+        program.render_to_string(),
+        "<main><button>0</button><input value=\"\"/><ul>\
+        <li key=\"3\">3</li><li key=\"1\">1</li><li key=\"2\">2</li>\
+        </ul></main>"
+    );
+}
+
+#[test]
+fn keyed_nodes_are_removed_and_reordered() {
+    let mut program = HeadlessProgram::new(App::default());
+    program.dispatch(Msg::ItemsFetched(vec![0, 1, 2, 3]));
+    // the node with the same key is kept
+    let item2 = program.root_node().unwrap().children()[2].children()[2].clone();
+    assert_eq!(item2.text_content(), "2");
+
+    program.dispatch(Msg::ItemsFetched(vec![0, 3, 2, 1]));
+    let items = program.root_node().unwrap().children()[2].children();
+    assert_eq!(
+        items.iter().map(|li| li.text_content()).collect::<Vec<_>>(),
+        ["0", "3", "2", "1"]
+    );
+    assert_eq!(items[2], item2);
+
+    // subsequent patches must resolve to the correct nodes
+    program.dispatch(Msg::ItemsFetched(vec![2, 5]));
+    assert_eq!(
+        program.render_to_string(),
+        "<main><button>0</button><input value=\"\"/><ul>\
+        <li key=\"2\">2</li><li key=\"5\">5</li>\
+        </ul></main>"
+    );
+}
+
+#[test]
+fn patches_replace_the_root_node() {
+    let mut program = HeadlessProgram::new(App::default());
+    let total = program
+        .update_dom_with_vdom(article([class("replaced")], [text("hello")]))
+        .expect("must patch");
+    assert_eq!(total, 1);
+    assert_eq!(
+        program.render_to_string(),
+        "<article class=\"replaced\">hello</article>"
+    );
+}
+
+struct Modal {
+    count: i32,
+}
+
+impl Application for Modal {
+    type MSG = Msg;
+
+    fn update(&mut self, msg: Msg) -> Cmd<Msg> {
+        if let Msg::Increment = msg {
+            self.count += 1;
+        }
+        Cmd::none()
+    }
+
+    fn view(&self) -> Node<Msg> {
+        div(
+            [],
+            [html::portal(
+                "#modals",
+                [button(
+                    [on("click", |_| Msg::Increment)],
+                    [text(self.count)],
+                )],
+            )],
+        )
+    }
+}
+
+#[test]
+fn children_of_portal_are_patched_in_its_host() {
+    let program = HeadlessProgram::new(Modal { count: 0 });
+    let portal = program.root_node().unwrap().children()[0].clone();
+    let button = portal.children()[0].clone();
+    button.dispatch_event("click");
+    assert_eq!(program.app().count, 1);
+    assert_eq!(portal.children()[0].text_content(), "1");
+    // the children of the portal is not rendered in place
+    assert_eq!(program.render_to_string(), "<div><!--portal--></div>");
+}
+
+struct Panel {
+    clicks: i32,
+    focused: bool,
+}
+
+impl Application for Panel {
+    type MSG = Msg;
+
+    fn update(&mut self, msg: Msg) -> Cmd<Msg> {
+        match msg {
+            Msg::Increment => self.clicks += 1,
+            _ => self.focused = true,
+        }
+        Cmd::none()
+    }
+
+    fn view(&self) -> Node<Msg> {
+        div(
+            [
+                on_click(|_| Msg::Increment),
+                on_focus(|_| Msg::NameChanged("focused".to_string())),
+            ],
+            [button([], [span([], [text(self.clicks)])])],
+        )
+    }
+}
+
+#[test]
+fn typed_listeners_are_called_as_the_event_bubbles_up() {
+    let program = HeadlessProgram::new(Panel {
+        clicks: 0,
+        focused: false,
+    });
+    let span = program.root_node().unwrap().children()[0].children()[0].clone();
+    span.dispatch_event("click");
+    assert_eq!(program.app().clicks, 1);
+    assert_eq!(span.text_content(), "1");
+    // focus does not bubble
+    span.dispatch_event("focus");
+    assert!(!program.app().focused);
+}
+
+struct Badge {
+    label: String,
+    clicks: i32,
+}
+
+impl Component for Badge {
+    type MSG = ();
+    type XMSG = ();
+
+    fn update(&mut self, _msg: ()) -> Effects<(), ()> {
+        self.clicks += 1;
+        Effects::none()
+    }
+
+    fn view(&self) -> Node<()> {
+        button(
+            [class("badge"), on_click(|_| ())],
+            [text!("{} {}", self.label, self.clicks)],
+        )
+    }
+
+    fn observed_attributes() -> Vec<vdom::AttributeName> {
+        vec!["label"]
+    }
+}
+
+impl dom::StatefulComponent for Badge {
+    fn attribute_changed(&mut self, attr: dom::DomAttr) {
+        if attr.name == "label" {
+            self.label = attr.value[0].as_string().unwrap_or_default();
+        }
+    }
+
+    fn child_container(&self) -> Option<dom::DomNode> {
+        None
+    }
+}
+
+struct Toolbar {
+    label: String,
+}
+
+impl Application for Toolbar {
+    type MSG = Msg;
+
+    fn update(&mut self, msg: Msg) -> Cmd<Msg> {
+        if let Msg::NameChanged(label) = msg {
+            self.label = label;
+        }
+        Cmd::none()
+    }
+
+    fn view(&self) -> Node<Msg> {
+        nav(
+            [],
+            [stateful_component(
+                Badge {
+                    label: String::new(),
+                    clicks: 0,
+                },
+                [attr("label", &self.label)],
+                [],
+            )],
+        )
+    }
+}
+
+#[test]
+fn stateful_components_are_created_and_updated() {
+    let mut program = HeadlessProgram::new(Toolbar {
+        label: "inbox".to_string(),
+    });
+    assert_eq!(
+        program.render_to_string(),
+        "<nav><div class=\"component\" label=\"inbox\"><button class=\"badge\">inbox 0</button></div></nav>"
+    );
+    let comp = program.root_node().unwrap().children()[0].clone();
+    assert!(comp.is_stateful_component());
+
+    program.dispatch(Msg::NameChanged("sent".to_string()));
+    assert_eq!(
+        program.render_to_string(),
+        "<nav><div class=\"component\" label=\"sent\"><button class=\"badge\">sent 0</button></div></nav>"
+    );
+}
@@ -0,0 +1,67 @@
+#![deny(warnings)]
+use sauron::{dom::component, *};
+
+struct Theme(&'static str);
+
+struct Locale(&'static str);
+
+struct Greeting;
+
+impl Component for Greeting {
+    type MSG = ();
+    type XMSG = ();
+
+    fn update(&mut self, _msg: ()) -> Effects<(), ()> {
+        Effects::none()
+    }
+
+    fn view(&self) -> Node<()> {
+        let theme = use_context::<Theme>()
+            .map(|theme| theme.0)
+            .unwrap_or("none");
+        let locale = use_context::<Locale>()
+            .map(|locale| locale.0)
+            .unwrap_or("none");
+        span([class(theme)], [text(locale)])
+    }
+}
+
+#[test]
+fn component_views_read_the_nearest_provided_value() {
+    let view: Node<()> = div(
+        [],
+        [provide(Theme("dark"), || {
+            [
+                component(&Greeting),
+                provide(Locale("en"), || {
+                    [
+                        component(&Greeting),
+                        provide(Theme("light"), || [component(&Greeting)]),
+                    ]
+                }),
+                component(&Greeting),
+            ]
+        })],
+    );
+    assert_eq!(
+        view.render_to_string(),
+        concat!(
+            "<div>",
+            r#"<span class="dark">none</span>"#,
+            r#"<span class="dark">en</span>"#,
+            r#"<span class="light">en</span>"#,
+            r#"<span class="dark">none</span>"#,
+            "</div>"
+        )
+    );
+}
+
+#[test]
+fn values_are_not_provided_outside_of_provide() {
+    let _: Node<()> = provide(Theme("dark"), || [component(&Greeting)]);
+    assert!(use_context::<Theme>().is_none());
+    assert_eq!(
+        component(&Greeting).render_to_string(),
+        r#"<span class="none">none</span>"#
+    );
+}
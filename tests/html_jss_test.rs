@@ -153,3 +153,128 @@ fn test_jss_ns_with_media_query() {
             \n";
     assert_eq!(expected, css);
 }
+
+#[test]
+fn test_jss_keyframes() {
+    let css = jss!(
+        "@keyframes fade": {
+            "0%": {
+                opacity: 0,
+            },
+            "100%": {
+                opacity: 1,
+            },
+        },
+        ".toast": {
+            animation: "fade 1s",
+        },
+    );
+
+    let expected = "@keyframes fade {\n0% {\n  opacity: 0;\n}\n\n100% {\n  opacity: 1;\n}\n\n}\n\n.toast {\n  animation: fade 1s;\n}\n";
+    assert_eq!(expected, css);
+}
+
+#[test]
+fn test_jss_font_face() {
+    let css = jss!(
+        "@font-face": {
+            font_family: "Fira",
+            src: "url(fira.woff2) format(\"woff2\")",
+            font_display: "swap",
+        },
+    );
+
+    let expected = "@font-face {\n  font-family: Fira;\n  src: url(fira.woff2) format(\"woff2\");\n  font-display: swap;\n}\n";
+    assert_eq!(expected, css);
+}
+
+#[test]
+fn test_jss_supports_and_layer() {
+    let css = jss!(
+        "@layer reset, base",
+        "@layer base": {
+            "@supports (display: grid)": {
+                ".grid": {
+                    display: "grid",
+                },
+            },
+        },
+    );
+
+    let expected = "@layer reset, base;\n\n@layer base {\n@supports (display: grid) {\n.grid {\n  display: grid;\n}\n\n}\n\n}\n";
+    assert_eq!(expected, css);
+}
+
+#[test]
+fn test_jss_nested_selectors() {
+    let css = jss!(
+        ".button, .link": {
+            color: "red",
+            "&:hover": {
+                color: "blue",
+                "& > .icon": {
+                    opacity: 1,
+                },
+            },
+            ".label": {
+                font_weight: "bold",
+            },
+        },
+    );
+
+    let expected = "\
+        .button, .link {\n  color: red;\n}\n\
+        \n.button:hover, .link:hover {\n  color: blue;\n}\n\
+        \n.button:hover > .icon, .link:hover > .icon {\n  opacity: 1;\n}\n\
+        \n.button .label, .link .label {\n  font-weight: bold;\n}\n";
+    assert_eq!(expected, css);
+}
+
+#[test]
+fn test_jss_nested_selector_without_properties() {
+    let selector = ".card";
+    let css = jss!(
+        selector: {
+            "&.active": {
+                border: "1px solid green",
+            },
+        },
+    );
+
+    let expected = ".card.active {\n  border: 1px solid green;\n}\n";
+    assert_eq!(expected, css);
+}
+
+#[test]
+fn test_jss_nested_selector_list() {
+    let css = jss!(
+        ".card": {
+            "h1, h2": {
+                margin: 0,
+            },
+            "&.active, &:focus-within": {
+                outline: "none",
+            },
+        },
+    );
+
+    let expected = "\
+        .card h1, .card h2 {\n  margin: 0;\n}\n\
+        \n.card.active, .card:focus-within {\n  outline: none;\n}\n";
+    assert_eq!(expected, css);
+}
+
+#[test]
+fn test_jss_nested_selector_with_a_comma_inside_parentheses() {
+    let css = jss!(
+        ":is(.card, .panel), .dialog": {
+            "& > :where(h1, h2)": {
+                margin: 0,
+            },
+        },
+    );
+
+    let expected = "\
+        :is(.card, .panel) > :where(h1, h2), .dialog > :where(h1, h2) {\n  margin: 0;\n}\n";
+    assert_eq!(expected, css);
+}
@@ -0,0 +1,53 @@
+#![deny(warnings)]
+use sauron::{
+    vdom::{ListenerOptions, PatchType},
+    *,
+};
+
+fn view(options: ListenerOptions) -> Node<()> {
+    div([on_scroll(|_| ()).with_listener_options(options)], [])
+}
+
+fn passive() -> ListenerOptions {
+    ListenerOptions {
+        passive: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn modifiers_set_the_listener_options() {
+    let attr: Attribute<()> = on_click(|_| ()).capture().once();
+    assert_eq!(
+        attr.listener_options(),
+        ListenerOptions {
+            capture: true,
+            passive: false,
+            once: true,
+        }
+    );
+    assert!(on_click(|_| ()).listener_options().is_default());
+}
+
+#[test]
+fn changed_listener_options_are_patched() {
+    let old = view(ListenerOptions::default());
+    let new = view(passive());
+    let diff = diff(&old, &new);
+    assert_eq!(diff.len(), 1);
+    let Patch {
+        patch_type: PatchType::AddAttributes { attrs },
+        ..
+    } = &diff[0]
+    else {
+        panic!("expecting an add attributes patch, got: {diff:?}");
+    };
+    assert_eq!(attrs[0].listener_options(), passive());
+}
+
+#[test]
+fn same_listener_options_are_not_patched() {
+    let old = view(passive());
+    let new = view(passive());
+    assert!(diff(&old, &new).is_empty());
+}
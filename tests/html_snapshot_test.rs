@@ -0,0 +1,71 @@
+#![deny(warnings)]
+use sauron::{
+    testing::{assert_snapshot, assert_snapshot_in, UPDATE_SNAPSHOTS_ENV},
+    *,
+};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+enum Msg {
+    Increment,
+    Decrement,
+}
+
+fn counter(count: i32) -> Node<Msg> {
+    div(
+        [class("counter")],
+        [
+            button([on_click(|_| Msg::Decrement)], [text("-")]),
+            span([class("count")], [text(count)]),
+            button(
+                [class("increment"), on_click(|_| Msg::Increment)],
+                [text("+")],
+            ),
+        ],
+    )
+}
+
+#[test]
+fn event_listeners_are_only_rendered_in_the_snapshots() {
+    let view: Node<()> = input([r#type("text"), on_input(|_| ())], []);
+    // the listeners are not part of the rendered html
+    assert_eq!(view.render_to_string_pretty(), r#"<input type="text" />"#);
+    assert_eq!(view.render_to_string(), r#"<input type="text" />"#);
+}
+
+#[test]
+fn counter_matches_snapshot() {
+    assert_snapshot("counter", &counter(3));
+}
+
+/// a copy of the committed snapshots in a temporary directory,
+/// so that the failing assertions can never overwrite the committed snapshots
+fn snapshots_copy(test_name: &str) -> PathBuf {
+    let snapshots = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("snapshots");
+    let dir = env::temp_dir().join(format!("sauron-{test_name}-{}", std::process::id()));
+    fs::create_dir_all(&dir).expect("must create the temp directory");
+    fs::copy(snapshots.join("counter.snap"), dir.join("counter.snap"))
+        .expect("must copy the snapshot");
+    dir
+}
+
+#[test]
+#[should_panic(expected = "-   <span class=\"count\">3</span>\n+   <span class=\"count\">4</span>")]
+fn mismatched_snapshot_shows_the_diff() {
+    // the mismatch must panic even when the snapshots are being updated
+    env::remove_var(UPDATE_SNAPSHOTS_ENV);
+    let dir = snapshots_copy("mismatched-snapshot");
+    assert_snapshot_in(&dir, "counter", &counter(4));
+}
+
+#[test]
+#[should_panic(expected = "snapshot `missing` does not exist")]
+fn missing_snapshot() {
+    env::remove_var(UPDATE_SNAPSHOTS_ENV);
+    let dir = snapshots_copy("missing-snapshot");
+    assert_snapshot_in(&dir, "missing", &counter(0));
+}
@@ -0,0 +1,143 @@
+#![deny(warnings)]
+use sauron::{
+    dom::{component, fingerprint_of, HeadlessProgram},
+    *,
+};
+use std::{cell::Cell, rc::Rc};
+
+struct Label {
+    text: &'static str,
+    version: Option<u32>,
+}
+
+impl Component for Label {
+    type MSG = ();
+    type XMSG = ();
+
+    fn update(&mut self, _msg: ()) -> Effects<(), ()> {
+        Effects::none()
+    }
+
+    fn view(&self) -> Node<()> {
+        span([], [text(self.text)])
+    }
+
+    fn fingerprint(&self) -> Option<u64> {
+        self.version.map(|version| fingerprint_of(&version))
+    }
+}
+
+fn view(text: &'static str, version: Option<u32>) -> Node<()> {
+    div([], [component(&Label { text, version })])
+}
+
+#[test]
+fn component_with_the_same_fingerprint_is_not_diffed() {
+    let old = view("old", Some(1));
+    let new = view("new", Some(1));
+    assert_eq!(diff(&old, &new), vec![]);
+}
+
+#[test]
+fn component_with_a_changed_fingerprint_is_diffed() {
+    let old = view("old", Some(1));
+    let new = view("new", Some(2));
+    let new_text = text("new");
+    assert_eq!(
+        diff(&old, &new),
+        vec![Patch::replace_node(
+            None,
+            TreePath::new([0, 0]),
+            [&new_text]
+        )]
+    );
+}
+
+#[test]
+fn component_without_a_fingerprint_is_always_diffed() {
+    let old = view("old", None);
+    let new = view("new", None);
+    assert_eq!(diff(&old, &new).len(), 1);
+}
+
+struct Avatar {
+    name: &'static str,
+    /// the number of times the view is created
+    views: Rc<Cell<usize>>,
+}
+
+impl Component for Avatar {
+    type MSG = Msg;
+    type XMSG = ();
+
+    fn update(&mut self, _msg: Msg) -> Effects<Msg, ()> {
+        Effects::none()
+    }
+
+    fn view(&self) -> Node<Msg> {
+        self.views.set(self.views.get() + 1);
+        img([alt(self.name)], [])
+    }
+
+    fn fingerprint(&self) -> Option<u64> {
+        Some(fingerprint_of(self.name))
+    }
+}
+
+struct App {
+    name: &'static str,
+    clicks: u32,
+    views: Rc<Cell<usize>>,
+}
+
+enum Msg {
+    Click,
+    Rename(&'static str),
+}
+
+impl Application for App {
+    type MSG = Msg;
+
+    fn update(&mut self, msg: Msg) -> Cmd<Msg> {
+        match msg {
+            Msg::Click => self.clicks += 1,
+            Msg::Rename(name) => self.name = name,
+        }
+        Cmd::none()
+    }
+
+    fn view(&self) -> Node<Msg> {
+        div(
+            [],
+            [
+                text(self.clicks),
+                component(&Avatar {
+                    name: self.name,
+                    views: Rc::clone(&self.views),
+                }),
+            ],
+        )
+    }
+}
+
+#[test]
+fn view_of_the_component_with_the_same_fingerprint_is_reused() {
+    let views = Rc::new(Cell::new(0));
+    let mut program = HeadlessProgram::new(App {
+        name: "alice",
+        clicks: 0,
+        views: Rc::clone(&views),
+    });
+    assert_eq!(views.get(), 1);
+
+    program.dispatch(Msg::Click);
+    assert_eq!(views.get(), 1);
+    assert_eq!(
+        program.render_to_string(),
+        r#"<div>1<img alt="alice"/></div>"#
+    );
+
+    program.dispatch(Msg::Rename("bob"));
+    assert_eq!(views.get(), 2);
+    assert_eq!(program.render_to_string(), r#"<div>1<img alt="bob"/></div>"#);
+}
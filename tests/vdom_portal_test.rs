@@ -0,0 +1,45 @@
+#![deny(warnings)]
+use sauron::{
+    html::portal,
+    vdom::{diff::*, patch::*},
+    *,
+};
+
+#[test]
+fn portal_children_are_diffed_relative_to_the_portal() {
+    let old: Node<()> = div([], [portal("#modals", [span([], [text("hello")])])]);
+    let new: Node<()> = div([], [portal("#modals", [span([], [text("world")])])]);
+
+    let diff = diff(&old, &new);
+    assert_eq!(
+        diff,
+        vec![Patch::replace_node(
+            None,
+            TreePath::new([0, 0, 0]),
+            vec![&text("world")]
+        )]
+    );
+}
+
+#[test]
+fn changing_the_target_replaces_the_portal() {
+    let old: Node<()> = div([], [portal("#modals", [span([], [])])]);
+    let new_portal: Node<()> = portal("body", [span([], [])]);
+    let new: Node<()> = div([], [new_portal.clone()]);
+
+    let diff = diff(&old, &new);
+    assert_eq!(
+        diff,
+        vec![Patch::replace_node(
+            None,
+            TreePath::new([0]),
+            vec![&new_portal]
+        )]
+    );
+}
+
+#[test]
+fn portal_children_are_rendered_in_place() {
+    let node: Node<()> = div([], [portal("#modals", [span([], [text("hello")])])]);
+    assert_eq!(node.render_to_string(), "<div><span>hello</span></div>");
+}
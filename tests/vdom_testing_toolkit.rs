@@ -0,0 +1,144 @@
+#![deny(warnings)]
+use sauron::{
+    testing::SelectorError,
+    vdom::{fragment, node_list},
+    *,
+};
+
+#[derive(Debug, PartialEq)]
+enum Msg {
+    Save,
+    Cancel,
+    Search(String),
+}
+
+fn view() -> Node<Msg> {
+    main(
+        [id("app")],
+        [
+            header([], [h1([], [text("Todo"), text(" list")])]),
+            form(
+                [class("search")],
+                [
+                    input([r#type("text"), on_input(|e| Msg::Search(e.value()))], []),
+                    button(
+                        [class("btn"), class("primary"), on("click", |_| Msg::Save)],
+                        [text("Save")],
+                    ),
+                    button(
+                        [class("btn"), on("click", |_| Msg::Cancel)],
+                        [text("Cancel")],
+                    ),
+                ],
+            ),
+            ul(
+                [],
+                [
+                    node_list([li([class("done")], [text("a")]), li([], [text("b")])]),
+                    fragment([li([class("done")], [text("c")])]),
+                ],
+            ),
+        ],
+    )
+}
+
+#[test]
+fn find_with_compound_selectors() -> Result<(), SelectorError> {
+    let view = view();
+    let save = view.find("button.primary")?.expect("must find");
+    assert_eq!(save.text_content(), "Save");
+    assert!(view.find("button.secondary")?.is_none());
+    assert_eq!(view.find("#app")?.and_then(|n| n.tag()), Some(&"main"));
+    assert!(view.find("input[type=text]")?.is_some());
+    assert!(view.find("input[type='checkbox']")?.is_none());
+    assert!(view.find("input[type]")?.is_some());
+    Ok(())
+}
+
+#[test]
+fn find_all_with_combinators() -> Result<(), SelectorError> {
+    let view = view();
+    assert_eq!(view.find_all("button")?.len(), 2);
+    assert_eq!(view.find_all("main button")?.len(), 2);
+    assert_eq!(view.find_all("main > button")?.len(), 0);
+    assert_eq!(view.find_all("form > button.btn")?.len(), 2);
+    assert_eq!(view.find_all("h1, button")?.len(), 3);
+    assert_eq!(view.find_all("*")?.len(), 11);
+    Ok(())
+}
+
+#[test]
+fn find_through_node_list_and_fragment() -> Result<(), SelectorError> {
+    let view = view();
+    let done: Vec<String> = view
+        .find_all("ul > li.done")?
+        .into_iter()
+        .map(|li| li.text_content())
+        .collect();
+    assert_eq!(done, ["a", "c"]);
+    Ok(())
+}
+
+#[test]
+fn text_attributes_and_classes() -> Result<(), SelectorError> {
+    let view = view();
+    assert_eq!(view.find("h1")?.unwrap().text_content(), "Todo list");
+    let save = view.find("button.primary")?.unwrap();
+    assert_eq!(save.get_attribute("class"), Some("btn primary".to_string()));
+    assert_eq!(save.classes(), ["btn", "primary"]);
+    assert!(save.has_class("btn"));
+    assert_eq!(save.get_attribute("id"), None);
+    Ok(())
+}
+
+#[test]
+fn simulate_events_returns_the_msg() -> Result<(), SelectorError> {
+    let view = view();
+    assert_eq!(
+        view.find("button.primary")?.unwrap().simulate_click(),
+        Some(Msg::Save)
+    );
+    assert_eq!(
+        view.find("input")?.unwrap().simulate_input("hello"),
+        Some(Msg::Search("hello".to_string()))
+    );
+    assert_eq!(view.find("h1")?.unwrap().simulate_click(), None);
+    Ok(())
+}
+
+#[test]
+fn typed_listeners_are_called_with_a_synthetic_event() {
+    let view: Node<Msg> = button([on_click(|_| Msg::Save)], []);
+    assert_eq!(view.simulate_click(), Some(Msg::Save));
+}
+
+#[test]
+fn simulate_click_calls_on_click() -> Result<(), SelectorError> {
+    let view: Node<Msg> = form(
+        [],
+        [
+            button([class("save"), on_click(|_| Msg::Save)], [text("Save")]),
+            a(
+                [attr("data-x", "a,b"), on_click(|_| Msg::Cancel)],
+                [text("Cancel")],
+            ),
+        ],
+    );
+    assert_eq!(
+        view.find("button.save")?.unwrap().simulate_click(),
+        Some(Msg::Save)
+    );
+    assert_eq!(
+        view.find("a[data-x='a,b']")?.unwrap().simulate_click(),
+        Some(Msg::Cancel)
+    );
+    Ok(())
+}
+
+#[test]
+fn invalid_selector_returns_an_error() {
+    assert_eq!(
+        view().find("button.").err(),
+        Some(SelectorError::UnexpectedChar('.', 6))
+    );
+}
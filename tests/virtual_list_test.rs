@@ -0,0 +1,151 @@
+#![deny(warnings)]
+use sauron::{
+    dom::{ItemSize, VirtualList, VirtualListMsg},
+    *,
+};
+
+enum Msg {
+    List(VirtualListMsg),
+}
+
+/// how the app passes the msgs of the list back to the list
+fn update(list: &mut VirtualList, msg: Msg) {
+    match msg {
+        Msg::List(msg) => list.update(msg),
+    }
+}
+
+fn scrolled(scroll_top: f64, scroll_left: f64) -> VirtualListMsg {
+    VirtualListMsg::Scrolled {
+        scroll_top,
+        scroll_left,
+        measured: vec![],
+    }
+}
+
+fn rendered_indexes(node: &Node<Msg>) -> Vec<String> {
+    node.find_all(".sauron-virtual-list__row")
+        .expect("must be a valid selector")
+        .iter()
+        .map(|row| row.get_attribute("data-index").expect("must have an index"))
+        .collect()
+}
+
+#[test]
+fn only_the_visible_rows_are_rendered() {
+    let list = VirtualList::new(100_000, ItemSize::Fixed(20.0), 100.0).with_overscan(2);
+    assert_eq!(list.visible_rows(), 0..8);
+
+    let view = list.view(Msg::List, |index| text!("row {index}"));
+    assert_eq!(
+        rendered_indexes(&view),
+        ["0", "1", "2", "3", "4", "5", "6", "7"]
+    );
+    let content = view
+        .find(".sauron-virtual-list__content")
+        .expect("must be a valid selector")
+        .expect("must have a content");
+    assert_eq!(
+        content.get_attribute("style").as_deref(),
+        Some("position:relative;height:2000000px;")
+    );
+}
+
+#[test]
+fn scrolling_moves_the_rendered_window() {
+    let mut list = VirtualList::new(1000, ItemSize::Fixed(20.0), 100.0).with_overscan(2);
+    update(&mut list, Msg::List(scrolled(1000.0, 0.0)));
+    assert_eq!(list.visible_rows(), 48..58);
+
+    let view = list.view(Msg::List, |index| text!("row {index}"));
+    let rows = view
+        .find(".sauron-virtual-list__rows")
+        .expect("must be a valid selector")
+        .expect("must have rows");
+    assert_eq!(
+        rows.get_attribute("style").as_deref(),
+        Some("position:absolute;top:960px;left:0px;right:0px;")
+    );
+
+    // the window is clamped at the end of the list
+    list.update(scrolled(19_950.0, 0.0));
+    assert_eq!(list.visible_rows(), 995..1000);
+}
+
+#[test]
+fn measured_heights_are_used_for_the_offsets() {
+    let mut list =
+        VirtualList::new(100, ItemSize::Measured { estimated: 10.0 }, 50.0).with_overscan(0);
+    assert_eq!(list.total_height(), 1000.0);
+    assert_eq!(list.visible_rows(), 0..6);
+
+    list.update(VirtualListMsg::Scrolled {
+        scroll_top: 0.0,
+        scroll_left: 0.0,
+        measured: vec![(0, 30.0), (1, 30.0)],
+    });
+    assert_eq!(list.row_offset(2), 60.0);
+    assert_eq!(list.total_height(), 1040.0);
+    assert_eq!(list.visible_rows(), 0..2);
+
+    // the measured heights are kept when rows are added
+    list.set_row_count(150);
+    assert_eq!(list.total_height(), 1540.0);
+    list.update(VirtualListMsg::Scrolled {
+        scroll_top: 75.0,
+        scroll_left: 0.0,
+        measured: vec![(1, 20.0)],
+    });
+    assert_eq!(list.row_offset(2), 50.0);
+    assert_eq!(list.visible_rows(), 4..10);
+}
+
+#[test]
+fn scroll_to_index() {
+    let mut list = VirtualList::new(1000, ItemSize::Fixed(20.0), 100.0).with_overscan(0);
+    list.update(VirtualListMsg::ScrollToIndex(500));
+    assert_eq!(list.scroll_top(), 10_000.0);
+    assert_eq!(list.visible_rows(), 500..506);
+
+    // can not scroll past the end of the list
+    list.scroll_to_index(999);
+    assert_eq!(list.scroll_top(), 19_900.0);
+}
+
+#[test]
+fn grid_renders_only_the_visible_columns() {
+    let mut list = VirtualList::new(1000, ItemSize::Fixed(20.0), 40.0)
+        .with_columns(500, 100.0, 250.0)
+        .with_overscan(0);
+    list.update(scrolled(0.0, 1000.0));
+    assert_eq!(list.visible_columns(), 10..13);
+
+    let view = list.view_grid(Msg::List, |row, column| text!("{row},{column}"));
+    let first_row = view
+        .find(".sauron-virtual-list__row")
+        .expect("must be a valid selector")
+        .expect("must have a row");
+    assert_eq!(
+        first_row
+            .find_all(".sauron-virtual-list__cell")
+            .expect("must be a valid selector")
+            .iter()
+            .map(|cell| cell.text_content())
+            .collect::<Vec<_>>(),
+        ["0,10", "0,11", "0,12"]
+    );
+}
+
+#[test]
+fn grid_without_columns_renders_a_single_column() {
+    let list = VirtualList::new(1000, ItemSize::Fixed(20.0), 40.0).with_overscan(0);
+    assert_eq!(list.visible_columns(), 0..0);
+
+    let view = list.view_grid(Msg::List, |row, column| text!("{row},{column}"));
+    assert_eq!(rendered_indexes(&view), ["0", "1", "2"]);
+    let first_row = view
+        .find(".sauron-virtual-list__row")
+        .expect("must be a valid selector")
+        .expect("must have a row");
+    assert_eq!(first_row.text_content(), "0,0");
+}